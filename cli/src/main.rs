@@ -1,28 +1,29 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 
 use clap::{Parser, Subcommand, ValueEnum};
-use engine::Engine;
-use lob_core::{LevelUpdate, MarketEvent, Price, Qty, Side, SymbolId, SymbolTable};
-use metrics::{LatencyStats, ThroughputTracker};
+use engine::{Engine, EngineBook, EngineConfig, EngineCore, MultiEngine};
+use lob_core::{MarketEvent, Price, Qty, SymbolId, SymbolTable};
+use metrics::{Clock, LatencyStats, LatencySummary, ThroughputTracker, VirtualClock};
 use oms::Oms;
-use orderbook::OrderBook;
+use orderbook::{ApplyReject, MultiBook, OrderBook};
 use portfolio::Portfolio;
 use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
-use replay::ReplayReader;
+use rand::SeedableRng;
+use replay::{ReplayError, ReplayReader, SyntheticFeed, SyntheticFeedConfig, Validation};
 use risk::RiskEngine;
+use serde::Serialize;
 use strategies::{MmStrategy, NoopStrategy, TwapStrategy};
 use trading_types::OrderStatus;
 use venue::ExecutionVenue;
-use venue_sim::SimVenue;
+use venue_sim::{RoutingVenue, SimVenue};
 
 const GEN_SEED_DEFAULT: u64 = 42;
 const SIM_TIMER_INTERVAL_NS_DEFAULT: u64 = 1_000_000_000;
-const MAX_TIMER_TICKS_PER_EVENT: usize = 1024;
 
 #[derive(Parser)]
 #[command(
@@ -33,6 +34,15 @@ const MAX_TIMER_TICKS_PER_EVENT: usize = 1024;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// How `replay`, `simulate` and `stats` print their summary.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output_format: OutputFormat,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -41,6 +51,25 @@ enum LogFormat {
     Bin,
 }
 
+#[derive(Clone, Debug)]
+struct SimulateRunOptions {
+    limit: Option<u64>,
+    timer_interval_ns: u64,
+    blotter: Option<std::path::PathBuf>,
+    output_format: OutputFormat,
+    virtual_clock: bool,
+    flatten: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct GenOptions {
+    events: u64,
+    seed: u64,
+    snapshot_first: bool,
+    format: LogFormat,
+    feed: SyntheticFeedConfig,
+}
+
 #[derive(Clone, Copy, Debug)]
 struct SimulateStrategyConfig {
     twap_target: i64,
@@ -69,12 +98,16 @@ enum Commands {
         limit: Option<u64>,
         #[arg(long, value_enum, default_value_t = LogFormat::Jsonl)]
         format: LogFormat,
+        /// Drop empty deltas and events that would cross the book.
+        #[arg(long)]
+        strict: bool,
     },
     Gen {
         #[arg(long)]
         output: std::path::PathBuf,
-        #[arg(long)]
-        symbol: String,
+        /// Repeat to interleave several symbols round-robin.
+        #[arg(long, required = true)]
+        symbol: Vec<String>,
         #[arg(long)]
         events: u64,
         #[arg(long, default_value_t = GEN_SEED_DEFAULT)]
@@ -83,12 +116,30 @@ enum Commands {
         snapshot_first: bool,
         #[arg(long, value_enum, default_value_t = LogFormat::Jsonl)]
         format: LogFormat,
+        /// Largest mid move per event, in ticks.
+        #[arg(long, default_value_t = 1)]
+        volatility: u32,
+        #[arg(long, default_value_t = 1)]
+        min_qty: i64,
+        #[arg(long, default_value_t = 10)]
+        max_qty: i64,
+        /// Probability that a delta removes its level.
+        #[arg(long, default_value_t = 0.1)]
+        remove_prob: f64,
+        /// Snapshot depth per side and furthest delta offset from mid, in ticks (at most
+        /// 10000).
+        #[arg(long, default_value_t = 5)]
+        levels: u32,
+        /// Probability that an event is a trade print at the touch.
+        #[arg(long, default_value_t = 0.0)]
+        trade_prob: f64,
     },
     Simulate {
         #[arg(long)]
         input: std::path::PathBuf,
-        #[arg(long)]
-        symbol: String,
+        /// Repeat to simulate several symbols, each with its own strategy instance.
+        #[arg(long, required = true)]
+        symbol: Vec<String>,
         #[arg(long, value_enum, default_value_t = StrategyKind::Noop)]
         strategy: StrategyKind,
         #[arg(long, default_value_t = 10)]
@@ -109,6 +160,48 @@ enum Commands {
         timer_interval_ns: u64,
         #[arg(long, value_enum, default_value_t = LogFormat::Jsonl)]
         format: LogFormat,
+        /// Write every execution report as a CSV row to this path.
+        #[arg(long)]
+        blotter: Option<std::path::PathBuf>,
+        /// Measure throughput in event time, making it reproducible; latency stays
+        /// wall-clock.
+        #[arg(long)]
+        virtual_clock: bool,
+        /// Close every position with a market order after the last event, so realized
+        /// PnL covers the whole run.
+        #[arg(long)]
+        flatten: bool,
+    },
+    Convert {
+        #[arg(long)]
+        input: std::path::PathBuf,
+        #[arg(long)]
+        output: std::path::PathBuf,
+        #[arg(long, value_enum)]
+        from: LogFormat,
+        #[arg(long, value_enum)]
+        to: LogFormat,
+        #[arg(long)]
+        symbol: String,
+    },
+    Validate {
+        #[arg(long)]
+        input: std::path::PathBuf,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long, value_enum, default_value_t = LogFormat::Jsonl)]
+        format: LogFormat,
+    },
+    Stats {
+        #[arg(long)]
+        input: std::path::PathBuf,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long, value_enum, default_value_t = LogFormat::Jsonl)]
+        format: LogFormat,
+        /// Drop empty deltas and events that would cross the book.
+        #[arg(long)]
+        strict: bool,
     },
 }
 
@@ -121,13 +214,15 @@ fn main() {
 
 fn run() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    let output_format = cli.output_format;
     match cli.command {
         Commands::Replay {
             input,
             symbol,
             limit,
             format,
-        } => run_replay(&input, &symbol, limit, format),
+            strict,
+        } => run_replay(&input, &symbol, limit, format, strict, output_format),
         Commands::Gen {
             output,
             symbol,
@@ -135,7 +230,29 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             seed,
             snapshot_first,
             format,
-        } => run_gen(&output, &symbol, events, seed, snapshot_first, format),
+            volatility,
+            min_qty,
+            max_qty,
+            remove_prob,
+            levels,
+            trade_prob,
+        } => {
+            let options = GenOptions {
+                events,
+                seed,
+                snapshot_first,
+                format,
+                feed: SyntheticFeedConfig {
+                    volatility_ticks: volatility,
+                    min_qty_lots: min_qty,
+                    max_qty_lots: max_qty,
+                    remove_prob,
+                    levels,
+                    trade_prob,
+                },
+            };
+            run_gen(&output, &symbol, &options)
+        }
         Commands::Simulate {
             input,
             symbol,
@@ -149,6 +266,9 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             limit,
             timer_interval_ns,
             format,
+            blotter,
+            virtual_clock,
+            flatten,
         } => {
             let config = SimulateStrategyConfig {
                 twap_target,
@@ -163,11 +283,251 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                 &symbol,
                 strategy,
                 &config,
-                limit,
-                timer_interval_ns,
+                &SimulateRunOptions {
+                    limit,
+                    timer_interval_ns,
+                    blotter,
+                    output_format,
+                    virtual_clock,
+                    flatten,
+                },
                 format,
             )
         }
+        Commands::Convert {
+            input,
+            output,
+            from,
+            to,
+            symbol,
+        } => run_convert(&input, &output, from, to, &symbol),
+        Commands::Validate {
+            input,
+            symbol,
+            format,
+        } => run_validate(&input, &symbol, format),
+        Commands::Stats {
+            input,
+            symbol,
+            format,
+            strict,
+        } => run_stats(&input, &symbol, format, strict, output_format),
+    }
+}
+
+/// A command's final metrics, printed as `key=value` lines or as one JSON object.
+trait Summary: Serialize {
+    fn print_text(&self);
+}
+
+fn print_summary<S: Summary>(
+    summary: &S,
+    output_format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match output_format {
+        OutputFormat::Text => summary.print_text(),
+        OutputFormat::Json => println!("{}", serde_json::to_string(summary)?),
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct LevelSummary {
+    price_ticks: i64,
+    qty_lots: i64,
+}
+
+impl LevelSummary {
+    fn from_level((price, qty): (Price, Qty)) -> Self {
+        Self {
+            price_ticks: price.ticks(),
+            qty_lots: qty.lots(),
+        }
+    }
+}
+
+fn level_text(level: &Option<LevelSummary>) -> String {
+    level
+        .as_ref()
+        .map(|level| format!("{}@{}", level.price_ticks, level.qty_lots))
+        .unwrap_or_else(|| "None".to_string())
+}
+
+/// Events the book refused, tallied by [`ApplyReject`] reason.
+#[derive(Default, Serialize)]
+struct DropCounts {
+    symbol_mismatch: u64,
+    crossed: u64,
+    empty_updates: u64,
+}
+
+impl DropCounts {
+    fn record(&mut self, reject: ApplyReject) {
+        match reject {
+            ApplyReject::SymbolMismatch { .. } => self.symbol_mismatch += 1,
+            ApplyReject::Crossed => self.crossed += 1,
+            ApplyReject::EmptyUpdates => self.empty_updates += 1,
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.symbol_mismatch + self.crossed + self.empty_updates
+    }
+}
+
+impl std::fmt::Display for DropCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "symbol_mismatch={} crossed={} empty_updates={}",
+            self.symbol_mismatch, self.crossed, self.empty_updates
+        )
+    }
+}
+
+fn checked_book(symbol: SymbolId, strict: bool) -> OrderBook {
+    OrderBook::new(symbol)
+        .with_reject_crossed(strict)
+        .with_reject_empty_updates(strict)
+}
+
+#[derive(Serialize)]
+struct ReplaySummary {
+    total_events_read: u64,
+    events_applied: u64,
+    events_dropped: u64,
+    dropped_by_reason: DropCounts,
+    throughput_windowed: f64,
+    throughput_overall: f64,
+    latency: LatencySummary,
+    best_bid: Option<LevelSummary>,
+    best_ask: Option<LevelSummary>,
+}
+
+impl Summary for ReplaySummary {
+    fn print_text(&self) {
+        println!("total_events_read={}", self.total_events_read);
+        println!("events_applied={}", self.events_applied);
+        println!("events_dropped={}", self.events_dropped);
+        println!("dropped_by_reason={}", self.dropped_by_reason);
+        println!(
+            "throughput_windowed={:.2} events/sec",
+            self.throughput_windowed
+        );
+        println!(
+            "throughput_overall={:.2} events/sec",
+            self.throughput_overall
+        );
+        println!("latency={}", self.latency);
+        println!(
+            "best_bid={} best_ask={}",
+            level_text(&self.best_bid),
+            level_text(&self.best_ask)
+        );
+    }
+}
+
+#[derive(Serialize)]
+struct StatsSummary {
+    events: u64,
+    events_dropped: u64,
+    dropped_by_reason: DropCounts,
+    duration_ns: u64,
+    update_rate: f64,
+    spread_avg_ticks: f64,
+    spread_ticks: LatencySummary,
+    level_updates: u64,
+    remove_fraction: f64,
+    bid_levels_avg: f64,
+    ask_levels_avg: f64,
+    bid_levels_max: usize,
+    ask_levels_max: usize,
+    time_weighted_mid: Option<f64>,
+}
+
+impl Summary for StatsSummary {
+    fn print_text(&self) {
+        println!("events={}", self.events);
+        println!("events_dropped={}", self.events_dropped);
+        println!("dropped_by_reason={}", self.dropped_by_reason);
+        println!("duration_ns={}", self.duration_ns);
+        println!("update_rate={:.2} events/sec", self.update_rate);
+        println!("spread_avg_ticks={:.2}", self.spread_avg_ticks);
+        println!("spread_ticks={}", self.spread_ticks);
+        println!("level_updates={}", self.level_updates);
+        println!("remove_fraction={:.4}", self.remove_fraction);
+        println!("bid_levels_avg={:.2}", self.bid_levels_avg);
+        println!("ask_levels_avg={:.2}", self.ask_levels_avg);
+        println!("bid_levels_max={}", self.bid_levels_max);
+        println!("ask_levels_max={}", self.ask_levels_max);
+        match self.time_weighted_mid {
+            Some(mid) => println!("time_weighted_mid={:.2}", mid),
+            None => println!("time_weighted_mid=None"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SymbolPositionSummary {
+    symbol: String,
+    final_position_lots: i64,
+    realized_pnl_ticks: i128,
+    fees_paid_ticks: i128,
+    rebates_received_ticks: i128,
+}
+
+#[derive(Serialize)]
+struct SimulateSummary {
+    events_read: u64,
+    events_applied_to_book: u64,
+    orders_sent: u64,
+    fills_count: u64,
+    positions: Vec<SymbolPositionSummary>,
+    throughput_windowed: f64,
+    throughput_overall: f64,
+    latency: LatencySummary,
+}
+
+impl Summary for SimulateSummary {
+    fn print_text(&self) {
+        println!("events_read={}", self.events_read);
+        println!("events_applied_to_book={}", self.events_applied_to_book);
+        println!("orders_sent={}", self.orders_sent);
+        println!("fills_count={}", self.fills_count);
+        if let [position] = &self.positions[..] {
+            println!("final_position_lots={}", position.final_position_lots);
+            println!("realized_pnl_ticks={}", position.realized_pnl_ticks);
+            println!("fees_paid_ticks={}", position.fees_paid_ticks);
+            println!("rebates_received_ticks={}", position.rebates_received_ticks);
+        } else {
+            for position in &self.positions {
+                println!(
+                    "symbol={} final_position_lots={} realized_pnl_ticks={} fees_paid_ticks={} \
+                     rebates_received_ticks={}",
+                    position.symbol,
+                    position.final_position_lots,
+                    position.realized_pnl_ticks,
+                    position.fees_paid_ticks,
+                    position.rebates_received_ticks
+                );
+            }
+        }
+        println!(
+            "throughput_windowed={:.2} events/sec",
+            self.throughput_windowed
+        );
+        println!(
+            "throughput_overall={:.2} events/sec",
+            self.throughput_overall
+        );
+        println!("latency={}", self.latency);
+    }
+}
+
+fn replay_format(format: LogFormat) -> replay::ReplayFormat {
+    match format {
+        LogFormat::Jsonl => replay::ReplayFormat::Jsonl,
+        LogFormat::Bin => replay::ReplayFormat::Bin,
     }
 }
 
@@ -176,35 +536,34 @@ fn run_replay(
     symbol: &str,
     limit: Option<u64>,
     format: LogFormat,
+    strict: bool,
+    output_format: OutputFormat,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let symbol_id = SymbolId::from_u32(0);
-    let format = match format {
-        LogFormat::Jsonl => replay::ReplayFormat::Jsonl,
-        LogFormat::Bin => replay::ReplayFormat::Bin,
-    };
+    let format = replay_format(format);
     let mut reader =
         ReplayReader::open_with_format_and_predeclared_symbols(input, format, [symbol])?;
-    let mut book = OrderBook::new(symbol_id);
+    let mut book = checked_book(symbol_id, strict);
     let mut latency = LatencyStats::new();
     let mut throughput = ThroughputTracker::new(Duration::from_secs(1));
 
     let start = Instant::now();
     let mut total_events_read = 0u64;
     let mut events_applied = 0u64;
-    let mut events_dropped = 0u64;
+    let mut dropped = DropCounts::default();
 
     while let Some(event) = reader.next_event()? {
         total_events_read += 1;
         let t0 = Instant::now();
-        let applied = book.apply(&event);
-        if applied {
-            let ns = t0.elapsed().as_nanos().min(u64::MAX as u128) as u64;
-            let ns = ns.max(1);
-            latency.record(ns);
-            throughput.record(1);
-            events_applied += 1;
-        } else {
-            events_dropped += 1;
+        match book.apply_checked(&event) {
+            Ok(()) => {
+                let ns = t0.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+                let ns = ns.max(1);
+                latency.record(ns);
+                throughput.record(1);
+                events_applied += 1;
+            }
+            Err(reject) => dropped.record(reject),
         }
 
         if let Some(limit) = limit {
@@ -223,156 +582,397 @@ fn run_replay(
         0.0
     };
 
-    let best_bid = book
-        .best_bid()
-        .map(|(price, qty)| format!("{}@{}", price.ticks(), qty.lots()))
-        .unwrap_or_else(|| "None".to_string());
-    let best_ask = book
-        .best_ask()
-        .map(|(price, qty)| format!("{}@{}", price.ticks(), qty.lots()))
-        .unwrap_or_else(|| "None".to_string());
-
-    println!("total_events_read={}", total_events_read);
-    println!("events_applied={}", events_applied);
-    println!("events_dropped={}", events_dropped);
-    println!("throughput_windowed={:.2} events/sec", throughput_windowed);
-    println!("throughput_overall={:.2} events/sec", throughput_overall);
-    println!("latency={}", latency.summary_string());
-    println!("best_bid={} best_ask={}", best_bid, best_ask);
+    let summary = ReplaySummary {
+        total_events_read,
+        events_applied,
+        events_dropped: dropped.total(),
+        dropped_by_reason: dropped,
+        throughput_windowed,
+        throughput_overall,
+        latency: latency.summary(),
+        best_bid: book.best_bid().map(LevelSummary::from_level),
+        best_ask: book.best_ask().map(LevelSummary::from_level),
+    };
+    print_summary(&summary, output_format)
+}
+
+fn run_gen(
+    output: &Path,
+    symbols: &[String],
+    options: &GenOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    options.feed.validate()?;
+    let symbols = SymbolTable::try_from_symbols(symbols)?;
+    let file = std::fs::File::create(output)?;
+    let writer = BufWriter::new(file);
+    if output.extension().is_some_and(|ext| ext == "zst") {
+        #[cfg(feature = "compression")]
+        {
+            let mut encoder = zstd::Encoder::new(writer, 0)?;
+            write_gen_events(&mut encoder, &symbols, options)?;
+            encoder.finish()?.flush()?;
+        }
+        #[cfg(not(feature = "compression"))]
+        {
+            drop(writer);
+            return Err("writing .zst output requires the compression feature".into());
+        }
+    } else {
+        let mut writer = writer;
+        write_gen_events(&mut writer, &symbols, options)?;
+        writer.flush()?;
+    }
 
+    println!("generated={} output={}", options.events, output.display());
     Ok(())
 }
 
-fn run_gen(
+fn write_gen_events<W: Write>(
+    writer: &mut W,
+    symbols: &SymbolTable,
+    options: &GenOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rng = StdRng::seed_from_u64(options.seed);
+    let symbol_ids: Vec<SymbolId> = (0..symbols.len() as u32).map(SymbolId::from_u32).collect();
+    let mut feed = SyntheticFeed::with_config(SymbolId::from_u32(0), rng, options.feed)?
+        .with_symbols(&symbol_ids)
+        .with_snapshot_first(options.snapshot_first);
+    let snapshots = if options.snapshot_first {
+        feed.symbol_count() as u64
+    } else {
+        0
+    };
+    for _ in 0..options.events + snapshots {
+        write_event(writer, &feed.next_event(), options.format, symbols)?;
+    }
+    Ok(())
+}
+
+fn run_convert(
+    input: &Path,
     output: &Path,
+    from: LogFormat,
+    to: LogFormat,
+    symbol: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut reader = ReplayReader::open_with_format_and_predeclared_symbols(
+        input,
+        replay_format(from),
+        [symbol],
+    )?;
+    let mut writer = BufWriter::new(std::fs::File::create(output)?);
+    let mut converted = 0u64;
+    while let Some(event) = reader.next_event()? {
+        write_event(&mut writer, &event, to, reader.symbols())?;
+        converted += 1;
+    }
+    writer.flush()?;
+
+    println!("converted={} output={}", converted, output.display());
+    Ok(())
+}
+
+/// Decode errors are structural and fail the run; backwards timestamps and crossed books
+/// are reported as counts only.
+fn run_validate(
+    input: &Path,
     symbol: &str,
-    events: u64,
-    seed: u64,
-    snapshot_first: bool,
     format: LogFormat,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let symbols = SymbolTable::try_from_symbols([symbol])?;
-    let symbol = SymbolId::from_u32(0);
-    let file = std::fs::File::create(output)?;
-    let mut writer = BufWriter::new(file);
-    let mut rng = StdRng::seed_from_u64(seed);
-    let mut mid: i64 = 100_000;
-    let mut ts_ns = 0u64;
-
-    if snapshot_first {
-        let mut bids = Vec::with_capacity(5);
-        let mut asks = Vec::with_capacity(5);
-        for level in 1..=5i64 {
-            let bid_price = (mid - level).max(1);
-            let ask_price = mid + level;
-            let bid_qty = rng.gen_range(1..=10);
-            let ask_qty = rng.gen_range(1..=10);
-            bids.push((Price::new(bid_price)?, Qty::new(bid_qty)?));
-            asks.push((Price::new(ask_price)?, Qty::new(ask_qty)?));
-        }
-
-        let snapshot = MarketEvent::L2Snapshot {
-            ts_ns,
-            symbol,
-            bids,
-            asks,
-        };
-        write_event(&mut writer, &snapshot, format, &symbols)?;
-        ts_ns += 1;
+    let mut reader = ReplayReader::open_with_format_and_predeclared_symbols(
+        input,
+        replay_format(format),
+        [symbol],
+    )?
+    .with_validation(Validation::StrictMonotonicTs);
+    let mut book = OrderBook::new(SymbolId::from_u32(0));
+
+    let mut records = 0u64;
+    let mut first_ts_ns = None;
+    let mut last_ts_ns = None;
+    let mut non_monotonic = 0u64;
+    let mut crossed_states = 0u64;
+    let mut decode_error = None;
+    loop {
+        match reader.next_event() {
+            Ok(Some(event)) => {
+                records += 1;
+                first_ts_ns.get_or_insert(event.ts_ns());
+                last_ts_ns = Some(event.ts_ns());
+                if book.apply(&event) && book.is_crossed() {
+                    crossed_states += 1;
+                }
+            }
+            Ok(None) => break,
+            Err(ReplayError::NonMonotonicTimestamp { prev, got }) => {
+                records += 1;
+                non_monotonic += 1;
+                last_ts_ns = Some(got);
+                eprintln!("non_monotonic_ts prev={} got={}", prev, got);
+            }
+            Err(err) => {
+                decode_error = Some(err);
+                break;
+            }
+        }
     }
 
-    for idx in 0..events {
-        let drift: i64 = rng.gen_range(-1..=1);
-        mid = (mid + drift).max(1);
-        let side = if rng.gen_bool(0.5) {
-            Side::Bid
-        } else {
-            Side::Ask
-        };
-        let offset: i64 = rng.gen_range(1..=5);
-        let price_ticks = match side {
-            Side::Bid => (mid - offset).max(1),
-            Side::Ask => mid + offset,
-        };
-        let remove = rng.gen_bool(0.1);
-        let qty_lots: i64 = if remove { 0 } else { rng.gen_range(1..=10) };
+    let ts_or_none = |ts: Option<u64>| ts.map_or_else(|| "None".to_string(), |ts| ts.to_string());
+    println!("records={}", records);
+    println!("first_ts_ns={}", ts_or_none(first_ts_ns));
+    println!("last_ts_ns={}", ts_or_none(last_ts_ns));
+    println!("non_monotonic_ts={}", non_monotonic);
+    println!("crossed_states={}", crossed_states);
+    println!("decode_errors={}", u8::from(decode_error.is_some()));
+    let Some(err) = decode_error else {
+        return Ok(());
+    };
+    if matches!(format, LogFormat::Bin) {
+        println!("error_offset={}", reader.record_offset());
+    }
+    println!("error={}", err);
+    Err(format!("validation failed: {err}").into())
+}
 
-        let update = LevelUpdate {
-            side,
-            price: Price::new(price_ticks)?,
-            qty: Qty::new(qty_lots)?,
-        };
-        let event = MarketEvent::L2Delta {
-            ts_ns: ts_ns + idx,
-            symbol,
-            updates: vec![update],
+fn run_stats(
+    input: &Path,
+    symbol: &str,
+    format: LogFormat,
+    strict: bool,
+    output_format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut reader = ReplayReader::open_with_format_and_predeclared_symbols(
+        input,
+        replay_format(format),
+        [symbol],
+    )?;
+    let mut book = checked_book(SymbolId::from_u32(0), strict);
+    let mut dropped = DropCounts::default();
+    // Spread ticks go through the latency histogram for percentiles.
+    let mut spreads = LatencyStats::new();
+    let mut spread_sum = 0u64;
+
+    let mut events = 0u64;
+    let mut first_ts_ns = None;
+    let mut last_ts_ns = 0u64;
+    let mut level_updates = 0u64;
+    let mut removes = 0u64;
+    let (mut bid_levels_sum, mut ask_levels_sum) = (0u64, 0u64);
+    let (mut bid_levels_max, mut ask_levels_max) = (0usize, 0usize);
+    let mut mid_since: Option<(u64, f64)> = None;
+    let mut mid_weighted = 0.0f64;
+    let mut mid_weight_ns = 0u64;
+
+    while let Some(event) = reader.next_event()? {
+        if let Err(reject) = book.apply_checked(&event) {
+            dropped.record(reject);
+            continue;
+        }
+        events += 1;
+        let ts_ns = event.ts_ns();
+        first_ts_ns.get_or_insert(ts_ns);
+        last_ts_ns = ts_ns;
+        if let MarketEvent::L2Delta { updates, .. } = &event {
+            level_updates += updates.len() as u64;
+            removes += updates.iter().filter(|update| update.is_remove()).count() as u64;
+        }
+
+        let bid_levels = book.bid_levels().count();
+        let ask_levels = book.ask_levels().count();
+        bid_levels_sum += bid_levels as u64;
+        ask_levels_sum += ask_levels as u64;
+        bid_levels_max = bid_levels_max.max(bid_levels);
+        ask_levels_max = ask_levels_max.max(ask_levels);
+
+        if let Some(spread) = book.spread() {
+            let ticks = spread.ticks() as u64;
+            spreads.record(ticks);
+            spread_sum += ticks;
+        }
+
+        if let Some((since_ns, mid)) = mid_since {
+            let held_ns = ts_ns.saturating_sub(since_ns);
+            mid_weighted += mid * held_ns as f64;
+            mid_weight_ns += held_ns;
+        }
+        mid_since = match (book.best_bid(), book.best_ask()) {
+            (Some((bid, _)), Some((ask, _))) => {
+                Some((ts_ns, (bid.ticks() + ask.ticks()) as f64 / 2.0))
+            }
+            _ => None,
         };
-        write_event(&mut writer, &event, format, &symbols)?;
     }
 
-    writer.flush()?;
-    println!("generated={} output={}", events, output.display());
-    Ok(())
+    let duration_ns = last_ts_ns.saturating_sub(first_ts_ns.unwrap_or(0));
+    let update_rate = if duration_ns > 0 {
+        events as f64 / (duration_ns as f64 / 1e9)
+    } else {
+        0.0
+    };
+    let per_event = |sum: u64| {
+        if events > 0 {
+            sum as f64 / events as f64
+        } else {
+            0.0
+        }
+    };
+    let spread_avg = if spreads.count() > 0 {
+        spread_sum as f64 / spreads.count() as f64
+    } else {
+        0.0
+    };
+    let remove_fraction = if level_updates > 0 {
+        removes as f64 / level_updates as f64
+    } else {
+        0.0
+    };
+    let time_weighted_mid = (mid_weight_ns > 0).then(|| mid_weighted / mid_weight_ns as f64);
+
+    let summary = StatsSummary {
+        events,
+        events_dropped: dropped.total(),
+        dropped_by_reason: dropped,
+        duration_ns,
+        update_rate,
+        spread_avg_ticks: spread_avg,
+        spread_ticks: spreads.summary(),
+        level_updates,
+        remove_fraction,
+        bid_levels_avg: per_event(bid_levels_sum),
+        ask_levels_avg: per_event(ask_levels_sum),
+        bid_levels_max,
+        ask_levels_max,
+        time_weighted_mid,
+    };
+    print_summary(&summary, output_format)
 }
 
 fn run_simulate(
     input: &Path,
-    symbol: &str,
+    symbols: &[String],
     strategy: StrategyKind,
     config: &SimulateStrategyConfig,
-    limit: Option<u64>,
-    timer_interval_ns: u64,
+    options: &SimulateRunOptions,
     format: LogFormat,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let symbol_id = SymbolId::from_u32(0);
-    let format = match format {
-        LogFormat::Jsonl => replay::ReplayFormat::Jsonl,
-        LogFormat::Bin => replay::ReplayFormat::Bin,
-    };
-
+    let format = replay_format(format);
     let mut reader =
-        ReplayReader::open_with_format_and_predeclared_symbols(input, format, [symbol])?;
-    let shared_book = Rc::new(RefCell::new(OrderBook::new(symbol_id)));
-    let sim_venue = SimVenue::new(shared_book.clone(), 0, 0);
+        ReplayReader::open_with_format_and_predeclared_symbols(input, format, symbols)?;
+    let symbol_ids: Vec<SymbolId> = (0..symbols.len() as u32).map(SymbolId::from_u32).collect();
     let counters = Rc::new(RefCell::new(VenueCounters::default()));
-    let venue = CountingVenue::new(sim_venue, counters.clone());
+    let limit = options.limit;
+    let engine_config = EngineConfig {
+        timer_interval_ns: Some(options.timer_interval_ns.max(1)),
+        ..EngineConfig::default()
+    };
+    let clock = options.virtual_clock.then(VirtualClock::new);
+    let blotter = match &options.blotter {
+        Some(path) => Some(Rc::new(RefCell::new(Blotter::create(path, symbols)?))),
+        None => None,
+    };
+
+    if let [symbol_id] = symbol_ids[..] {
+        let shared_book = Rc::new(RefCell::new(OrderBook::new(symbol_id)));
+        let sim_venue = SimVenue::new(shared_book.clone(), 0, 0);
+        let venue = CountingVenue::new(sim_venue, counters.clone()).with_blotter(blotter.clone());
+        let mut engine = Engine::with_config(
+            shared_book,
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            make_strategy(strategy, config),
+            Box::new(venue),
+            engine_config,
+        );
+
+        let run = drive_simulation(&mut engine, &mut reader, &symbol_ids, limit, clock.as_ref())?;
+        if options.flatten {
+            flatten_all(&mut engine, run.last_ts_ns, &symbol_ids);
+        }
+        let summary = simulation_summary(run, &counters.borrow(), &engine, symbols);
+        print_summary(&summary, options.output_format)?;
+        return finish_blotter(blotter);
+    }
 
-    let mut engine = Engine::with_shared_book(
-        shared_book,
+    // Each symbol gets its own strategy instance and venue book; the engine routes by symbol.
+    let venue = RoutingVenue::for_symbols(&symbol_ids);
+    let per_symbol = PerSymbolStrategy {
+        strategies: symbol_ids
+            .iter()
+            .map(|&symbol_id| (symbol_id, make_strategy(strategy, config)))
+            .collect(),
+    };
+    let mut engine = MultiEngine::with_config(
+        Rc::new(RefCell::new(MultiBook::new())),
         Portfolio::new(),
         Oms::new(),
         RiskEngine::new(),
-        make_strategy(strategy, config),
-        Box::new(venue),
+        Box::new(per_symbol),
+        Box::new(CountingVenue::new(venue, counters.clone()).with_blotter(blotter.clone())),
+        engine_config,
     );
 
+    let run = drive_simulation(&mut engine, &mut reader, &symbol_ids, limit, clock.as_ref())?;
+    if options.flatten {
+        flatten_all(&mut engine, run.last_ts_ns, &symbol_ids);
+    }
+    let summary = simulation_summary(run, &counters.borrow(), &engine, symbols);
+    print_summary(&summary, options.output_format)?;
+    finish_blotter(blotter)
+}
+
+fn finish_blotter(blotter: Option<Rc<RefCell<Blotter>>>) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(blotter) = blotter {
+        blotter.borrow_mut().finish()?;
+    }
+    Ok(())
+}
+
+fn flatten_all<B: EngineBook>(engine: &mut EngineCore<B>, ts_ns: u64, symbols: &[SymbolId]) {
+    for &symbol in symbols {
+        engine.flatten(ts_ns, symbol);
+    }
+}
+
+struct SimulationRun {
+    events_read: u64,
+    /// Timestamp of the last event read, or 0 for an empty input.
+    last_ts_ns: u64,
+    events_applied: u64,
+    elapsed: Duration,
+    throughput_windowed: f64,
+}
+
+/// Feeds replayed events to the engine, advancing its timer schedule for every symbol first.
+/// With `clock`, time is the event timestamps rather than the wall clock.
+fn drive_simulation<B: EngineBook>(
+    engine: &mut EngineCore<B>,
+    reader: &mut ReplayReader,
+    timer_symbols: &[SymbolId],
+    limit: Option<u64>,
+    clock: Option<&VirtualClock>,
+) -> Result<SimulationRun, Box<dyn std::error::Error>> {
     let mut throughput = ThroughputTracker::new(Duration::from_secs(1));
     let start = Instant::now();
+    let mut first_ts_ns = None;
     let mut events_read = 0u64;
     let mut events_applied = 0u64;
-    let timer_interval_ns = timer_interval_ns.max(1);
-    let mut last_tick_ts_ns: Option<u64> = None;
+    let mut last_ts_ns = 0u64;
 
     while let Some(event) = reader.next_event()? {
-        let event_ts_ns = event_ts_ns(&event);
-        if let Some(mut last_tick) = last_tick_ts_ns {
-            let mut ticks_processed = 0usize;
-            while event_ts_ns.saturating_sub(last_tick) >= timer_interval_ns {
-                if ticks_processed >= MAX_TIMER_TICKS_PER_EVENT {
-                    debug_assert!(
-                        false,
-                        "timer processing exceeded MAX_TIMER_TICKS_PER_EVENT; stopping to prevent churn"
-                    );
-                    break;
-                }
-                last_tick = last_tick.saturating_add(timer_interval_ns);
-                engine.on_timer(last_tick, symbol_id);
-                ticks_processed += 1;
+        last_ts_ns = event.ts_ns();
+        if let Some(clock) = clock {
+            clock.advance_to(event.ts_ns());
+            if first_ts_ns.is_none() {
+                // Anchor the tracker at the first event rather than at virtual time zero.
+                first_ts_ns = Some(clock.now_ns());
+                throughput = ThroughputTracker::new_with_clock(
+                    Duration::from_secs(1),
+                    Rc::new(clock.clone()),
+                );
             }
-            last_tick_ts_ns = Some(last_tick);
-        } else {
-            last_tick_ts_ns = Some(event_ts_ns);
         }
+        engine.advance_to_symbols(event.ts_ns(), timer_symbols);
 
         events_read += 1;
         if engine.on_market_event(&event) {
@@ -387,30 +987,59 @@ fn run_simulate(
         }
     }
 
-    let elapsed = start.elapsed();
-    let throughput_windowed = throughput.events_per_sec().unwrap_or(0.0);
-    let throughput_overall = if elapsed.as_secs_f64() > 0.0 {
-        events_applied as f64 / elapsed.as_secs_f64()
+    let elapsed = match (clock, first_ts_ns) {
+        (Some(clock), Some(first_ts_ns)) => {
+            Duration::from_nanos(clock.now_ns().saturating_sub(first_ts_ns))
+        }
+        (Some(_), None) => Duration::ZERO,
+        (None, _) => start.elapsed(),
+    };
+    Ok(SimulationRun {
+        events_read,
+        last_ts_ns,
+        events_applied,
+        elapsed,
+        throughput_windowed: throughput.events_per_sec().unwrap_or(0.0),
+    })
+}
+
+/// Symbol ids are assigned in `symbols` order, matching the predeclared reader table.
+fn simulation_summary<B: EngineBook>(
+    run: SimulationRun,
+    counts: &VenueCounters,
+    engine: &EngineCore<B>,
+    symbols: &[String],
+) -> SimulateSummary {
+    let throughput_overall = if run.elapsed.as_secs_f64() > 0.0 {
+        run.events_applied as f64 / run.elapsed.as_secs_f64()
     } else {
         0.0
     };
+    let positions = symbols
+        .iter()
+        .enumerate()
+        .map(|(index, symbol)| {
+            let symbol_id = SymbolId::from_u32(index as u32);
+            SymbolPositionSummary {
+                symbol: symbol.clone(),
+                final_position_lots: engine.position_lots(symbol_id),
+                realized_pnl_ticks: engine.realized_pnl_ticks(symbol_id),
+                fees_paid_ticks: engine.fees_paid_ticks(symbol_id),
+                rebates_received_ticks: engine.rebates_received_ticks(symbol_id),
+            }
+        })
+        .collect();
 
-    let counts = counters.borrow();
-    println!("events_read={}", events_read);
-    println!("events_applied_to_book={}", events_applied);
-    println!("orders_sent={}", counts.orders_sent);
-    println!("fills_count={}", counts.fills_count);
-    println!("final_position_lots={}", engine.position_lots(symbol_id));
-    println!(
-        "realized_pnl_ticks={}",
-        engine.realized_pnl_ticks(symbol_id)
-    );
-    println!("fees_paid_ticks={}", engine.fees_paid_ticks(symbol_id));
-    println!("throughput_windowed={:.2} events/sec", throughput_windowed);
-    println!("throughput_overall={:.2} events/sec", throughput_overall);
-    println!("latency={}", engine.latency_stats().summary_string());
-
-    Ok(())
+    SimulateSummary {
+        events_read: run.events_read,
+        events_applied_to_book: run.events_applied,
+        orders_sent: counts.orders_sent,
+        fills_count: counts.fills_count,
+        positions,
+        throughput_windowed: run.throughput_windowed,
+        throughput_overall,
+        latency: engine.latency_stats().summary(),
+    }
 }
 
 fn make_strategy(
@@ -441,23 +1070,25 @@ struct VenueCounters {
 struct CountingVenue<V: ExecutionVenue> {
     inner: V,
     counters: Rc<RefCell<VenueCounters>>,
+    blotter: Option<Rc<RefCell<Blotter>>>,
 }
 
 impl<V: ExecutionVenue> CountingVenue<V> {
     fn new(inner: V, counters: Rc<RefCell<VenueCounters>>) -> Self {
-        Self { inner, counters }
+        Self {
+            inner,
+            counters,
+            blotter: None,
+        }
     }
-}
 
-impl<V: ExecutionVenue> ExecutionVenue for CountingVenue<V> {
-    fn submit(&mut self, req: &oms::OrderRequest, out: &mut Vec<trading_types::ExecutionReport>) {
-        {
-            let mut counters = self.counters.borrow_mut();
-            counters.orders_sent += 1;
-        }
+    fn with_blotter(mut self, blotter: Option<Rc<RefCell<Blotter>>>) -> Self {
+        self.blotter = blotter;
+        self
+    }
 
-        self.inner.submit(req, out);
-        let fills = out
+    fn record_reports(&self, reports: &[trading_types::ExecutionReport]) {
+        let fills = reports
             .iter()
             .filter(|report| {
                 matches!(
@@ -471,11 +1102,96 @@ impl<V: ExecutionVenue> ExecutionVenue for CountingVenue<V> {
             let mut counters = self.counters.borrow_mut();
             counters.fills_count += fills;
         }
+
+        if let Some(blotter) = &self.blotter {
+            let mut blotter = blotter.borrow_mut();
+            for report in reports {
+                blotter.record(report);
+            }
+        }
     }
 }
 
-fn write_event(
-    writer: &mut BufWriter<std::fs::File>,
+impl<V: ExecutionVenue> ExecutionVenue for CountingVenue<V> {
+    fn on_market_event(&mut self, event: &MarketEvent) {
+        self.inner.on_market_event(event);
+    }
+
+    fn submit(&mut self, req: &oms::OrderRequest, out: &mut Vec<trading_types::ExecutionReport>) {
+        {
+            let mut counters = self.counters.borrow_mut();
+            counters.orders_sent += 1;
+        }
+
+        let start = out.len();
+        self.inner.submit(req, out);
+        self.record_reports(&out[start..]);
+    }
+
+    fn on_book_update(&mut self, out: &mut Vec<trading_types::ExecutionReport>) {
+        let start = out.len();
+        self.inner.on_book_update(out);
+        self.record_reports(&out[start..]);
+    }
+}
+
+/// CSV trade blotter: one row per execution report returned by the venue.
+struct Blotter {
+    writer: BufWriter<std::fs::File>,
+    symbols: Vec<String>,
+    error: Option<std::io::Error>,
+}
+
+impl Blotter {
+    const HEADER: &'static str =
+        "ts_ns,client_order_id,symbol,side,status,filled_qty,price,fee_ticks";
+
+    fn create(path: &Path, symbols: &[String]) -> std::io::Result<Self> {
+        let mut writer = BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "{}", Self::HEADER)?;
+        Ok(Self {
+            writer,
+            symbols: symbols.to_vec(),
+            error: None,
+        })
+    }
+
+    /// Write failures are kept and surfaced by `finish`, since venues cannot return errors.
+    fn record(&mut self, report: &trading_types::ExecutionReport) {
+        if self.error.is_some() {
+            return;
+        }
+        let symbol = self
+            .symbols
+            .get(report.symbol.as_u32() as usize)
+            .map(String::as_str)
+            .unwrap_or("");
+        if let Err(err) = writeln!(
+            self.writer,
+            "{},{},{},{},{:?},{},{},{}",
+            report.ts_ns,
+            report.client_order_id.0,
+            symbol,
+            report.side,
+            report.status,
+            report.filled_qty.lots(),
+            report.last_fill_price.ticks(),
+            report.fee_ticks
+        ) {
+            self.error = Some(err);
+        }
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        match self.error.take() {
+            Some(err) => Err(err),
+            None => self.writer.flush(),
+        }
+    }
+}
+
+fn write_event<W: Write>(
+    writer: &mut W,
     event: &MarketEvent,
     format: LogFormat,
     symbols: &SymbolTable,
@@ -493,9 +1209,80 @@ fn write_event(
     Ok(())
 }
 
-fn event_ts_ns(event: &MarketEvent) -> u64 {
-    match event {
-        MarketEvent::L2Delta { ts_ns, .. } => *ts_ns,
-        MarketEvent::L2Snapshot { ts_ns, .. } => *ts_ns,
+/// Runs one strategy instance per symbol, dispatching on the context's symbol.
+struct PerSymbolStrategy {
+    strategies: HashMap<SymbolId, Box<dyn strategy_api::Strategy>>,
+}
+
+impl strategy_api::Strategy for PerSymbolStrategy {
+    fn is_ready(&self, ctx: &strategy_api::ContextSnapshot) -> bool {
+        self.strategies
+            .get(&ctx.symbol)
+            .is_none_or(|strategy| strategy.is_ready(ctx))
+    }
+
+    fn on_market_event(
+        &mut self,
+        ctx: &strategy_api::ContextSnapshot,
+        event: &MarketEvent,
+        out: &mut Vec<trading_types::Intent>,
+    ) {
+        if let Some(strategy) = self.strategies.get_mut(&ctx.symbol) {
+            strategy.on_market_event(ctx, event, out);
+        }
+    }
+
+    fn on_timer(
+        &mut self,
+        ctx: &strategy_api::ContextSnapshot,
+        out: &mut Vec<trading_types::Intent>,
+    ) {
+        if let Some(strategy) = self.strategies.get_mut(&ctx.symbol) {
+            strategy.on_timer(ctx, out);
+        }
+    }
+
+    fn on_timer_id(
+        &mut self,
+        timer: strategy_api::TimerId,
+        ctx: &strategy_api::ContextSnapshot,
+        out: &mut Vec<trading_types::Intent>,
+    ) {
+        if let Some(strategy) = self.strategies.get_mut(&ctx.symbol) {
+            strategy.on_timer_id(timer, ctx, out);
+        }
+    }
+
+    fn on_execution_report(
+        &mut self,
+        ctx: &strategy_api::ContextSnapshot,
+        report: &trading_types::ExecutionReport,
+        out: &mut Vec<trading_types::Intent>,
+    ) {
+        if let Some(strategy) = self.strategies.get_mut(&report.symbol) {
+            strategy.on_execution_report(ctx, report, out);
+        }
+    }
+
+    fn on_order_accepted(
+        &mut self,
+        ctx: &strategy_api::ContextSnapshot,
+        report: &trading_types::ExecutionReport,
+        out: &mut Vec<trading_types::Intent>,
+    ) {
+        if let Some(strategy) = self.strategies.get_mut(&report.symbol) {
+            strategy.on_order_accepted(ctx, report, out);
+        }
+    }
+
+    fn on_order_rejected(
+        &mut self,
+        ctx: &strategy_api::ContextSnapshot,
+        report: &trading_types::ExecutionReport,
+        out: &mut Vec<trading_types::Intent>,
+    ) {
+        if let Some(strategy) = self.strategies.get_mut(&report.symbol) {
+            strategy.on_order_rejected(ctx, report, out);
+        }
     }
 }
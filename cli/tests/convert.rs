@@ -0,0 +1,69 @@
+#![cfg(feature = "bin")]
+
+use std::process::Command;
+
+use replay::{ReplayFormat, ReplayReader};
+use tempfile::tempdir;
+
+fn read_all(path: &std::path::Path, format: ReplayFormat) -> Vec<lob_core::MarketEvent> {
+    let mut reader = ReplayReader::open_with_format(path, format).expect("open replay");
+    let mut events = Vec::new();
+    while let Some(event) = reader.next_event().expect("read event") {
+        events.push(event);
+    }
+    events
+}
+
+#[test]
+fn convert_round_trips_jsonl_through_bin() {
+    let dir = tempdir().expect("temp dir");
+    let original = dir.path().join("events.jsonl");
+    let bin = dir.path().join("events.bin");
+    let round_trip = dir.path().join("round-trip.jsonl");
+
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    let output = Command::new(exe)
+        .args([
+            "gen",
+            "--output",
+            original.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+            "--events",
+            "40",
+            "--snapshot-first",
+        ])
+        .output()
+        .expect("run gen");
+    assert!(output.status.success());
+
+    for (input, output_path, from, to) in [
+        (&original, &bin, "jsonl", "bin"),
+        (&bin, &round_trip, "bin", "jsonl"),
+    ] {
+        let output = Command::new(exe)
+            .args([
+                "convert",
+                "--input",
+                input.to_str().expect("path str"),
+                "--output",
+                output_path.to_str().expect("path str"),
+                "--from",
+                from,
+                "--to",
+                to,
+                "--symbol",
+                "BTC-USD",
+            ])
+            .output()
+            .expect("run convert");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("converted=41"), "stdout: {stdout}");
+    }
+
+    let expected = read_all(&original, ReplayFormat::Jsonl);
+    assert_eq!(expected.len(), 41);
+    assert_eq!(read_all(&bin, ReplayFormat::Bin), expected);
+    assert_eq!(read_all(&round_trip, ReplayFormat::Jsonl), expected);
+}
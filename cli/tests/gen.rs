@@ -1,6 +1,9 @@
+use std::collections::HashSet;
 use std::fs;
 use std::process::Command;
 
+use lob_core::MarketEvent;
+use orderbook::MultiBook;
 use replay::ReplayReader;
 use tempfile::tempdir;
 
@@ -59,3 +62,158 @@ fn gen_is_deterministic_and_replayable() {
     }
     assert_eq!(count, 20);
 }
+
+#[cfg(feature = "compression")]
+#[test]
+fn gen_zst_output_replays_like_plain_output() {
+    let dir = tempdir().expect("temp dir");
+    let plain = dir.path().join("events.log");
+    let compressed = dir.path().join("events.log.zst");
+
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    for path in [&plain, &compressed] {
+        let output = Command::new(exe)
+            .args([
+                "gen",
+                "--output",
+                path.to_str().expect("path str"),
+                "--symbol",
+                "BTC-USD",
+                "--events",
+                "50",
+                "--snapshot-first",
+            ])
+            .output()
+            .expect("run gen");
+        assert!(output.status.success());
+    }
+
+    assert_ne!(
+        fs::read(&plain).expect("read plain"),
+        fs::read(&compressed).expect("read compressed")
+    );
+
+    let mut plain_reader = ReplayReader::open(&plain).expect("open plain");
+    let mut compressed_reader = ReplayReader::open(&compressed).expect("open compressed");
+    loop {
+        let expected = plain_reader.next_event().expect("plain event");
+        let actual = compressed_reader.next_event().expect("compressed event");
+        assert_eq!(actual, expected);
+        if expected.is_none() {
+            break;
+        }
+    }
+}
+
+fn gen_price_range(dir: &std::path::Path, volatility: &str) -> i64 {
+    let path = dir.join(format!("vol-{volatility}.log"));
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    let output = Command::new(exe)
+        .args([
+            "gen",
+            "--output",
+            path.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+            "--events",
+            "500",
+            "--seed",
+            "7",
+            "--volatility",
+            volatility,
+        ])
+        .output()
+        .expect("run gen");
+    assert!(output.status.success());
+
+    let mut reader = ReplayReader::open(&path).expect("open replay");
+    let (mut min, mut max) = (i64::MAX, i64::MIN);
+    while let Some(event) = reader.next_event().expect("read event") {
+        if let MarketEvent::L2Delta { updates, .. } = event {
+            for update in updates {
+                min = min.min(update.price.ticks());
+                max = max.max(update.price.ticks());
+            }
+        }
+    }
+    max - min
+}
+
+#[test]
+fn gen_higher_volatility_widens_price_range() {
+    let dir = tempdir().expect("temp dir");
+    let calm = gen_price_range(dir.path(), "1");
+    let wild = gen_price_range(dir.path(), "20");
+    assert!(wild > calm, "calm={calm} wild={wild}");
+}
+
+#[test]
+fn gen_rejects_invalid_feed_parameters() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("bad.log");
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    for extra in [
+        ["--min-qty", "5", "--max-qty", "2"],
+        ["--remove-prob", "1.5", "--levels", "5"],
+        ["--levels", "4294967295", "--remove-prob", "0.1"],
+    ] {
+        let output = Command::new(exe)
+            .args([
+                "gen",
+                "--output",
+                path.to_str().expect("path str"),
+                "--symbol",
+                "BTC-USD",
+                "--events",
+                "5",
+            ])
+            .args(extra)
+            .output()
+            .expect("run gen");
+        assert!(!output.status.success());
+    }
+    assert!(!path.exists());
+}
+
+#[test]
+fn gen_interleaves_symbols_and_prints_trades_inside_the_spread() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("multi.log");
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    let output = Command::new(exe)
+        .args([
+            "gen",
+            "--output",
+            path.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+            "--symbol",
+            "ETH-USD",
+            "--events",
+            "400",
+            "--snapshot-first",
+            "--trade-prob",
+            "0.3",
+        ])
+        .output()
+        .expect("run gen");
+    assert!(output.status.success());
+
+    let mut reader = ReplayReader::open(&path).expect("open replay");
+    let mut books = MultiBook::new();
+    let mut seen = HashSet::new();
+    let mut trades = 0u64;
+    while let Some(event) = reader.next_event().expect("read event") {
+        seen.insert(event.symbol());
+        if let MarketEvent::Trade { symbol, price, .. } = &event {
+            let book = books.book(*symbol).expect("book before trade");
+            let (bid, _) = book.best_bid().expect("best bid");
+            let (ask, _) = book.best_ask().expect("best ask");
+            assert!(bid <= *price && *price <= ask, "{bid:?} {price:?} {ask:?}");
+            trades += 1;
+        }
+        books.apply(&event);
+    }
+    assert_eq!(seen.len(), 2);
+    assert!(trades > 0);
+}
@@ -106,9 +106,66 @@ fn replay_command_processes_events() {
     assert!(stdout.contains("total_events_read=6"));
     assert!(stdout.contains("events_applied=5"));
     assert!(stdout.contains("events_dropped=1"));
+    assert!(stdout.contains("dropped_by_reason=symbol_mismatch=1 crossed=0 empty_updates=0"));
     assert!(stdout.contains("throughput_windowed="));
     assert!(stdout.contains("throughput_overall="));
     assert!(stdout.contains("latency="));
     assert!(stdout.contains("best_bid=102@1"));
     assert!(stdout.contains("best_ask=103@2"));
 }
+
+#[test]
+fn replay_strict_tallies_drops_by_reason() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("events.log");
+    let mut symbols = SymbolTable::new();
+    let symbol = symbols.try_intern("BTC-USD").expect("symbol");
+    let other_symbol = symbols.try_intern("ETH-USD").expect("symbol");
+    let level = |side, price, qty| LevelUpdate {
+        side,
+        price: Price::new(price).unwrap(),
+        qty: Qty::new(qty).unwrap(),
+    };
+
+    let events = vec![
+        event(
+            symbol,
+            1,
+            vec![level(Side::Bid, 100, 1), level(Side::Ask, 102, 1)],
+        ),
+        event(symbol, 2, vec![level(Side::Bid, 103, 1)]),
+        event(symbol, 3, vec![]),
+        event(other_symbol, 4, vec![level(Side::Bid, 999, 1)]),
+        event(symbol, 5, vec![level(Side::Bid, 101, 2)]),
+    ];
+
+    let mut file = File::create(&path).expect("create log");
+    for event in events {
+        writeln!(
+            file,
+            "{}",
+            encode_event_json_line(&event, &symbols).expect("encode log")
+        )
+        .expect("write log");
+    }
+
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    let output = Command::new(exe)
+        .args([
+            "replay",
+            "--input",
+            path.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+            "--strict",
+        ])
+        .output()
+        .expect("run cli");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout");
+    assert!(stdout.contains("events_applied=2"));
+    assert!(stdout.contains("events_dropped=3"));
+    assert!(stdout.contains("dropped_by_reason=symbol_mismatch=1 crossed=1 empty_updates=1"));
+    assert!(stdout.contains("best_bid=101@2 best_ask=102@1"));
+}
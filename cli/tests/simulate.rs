@@ -78,3 +78,265 @@ fn simulate_noop_outputs_stats() {
     assert!(stdout.contains("throughput_overall="));
     assert!(stdout.contains("latency="));
 }
+
+#[test]
+fn simulate_multiple_symbols_reports_each_symbol() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("events.log");
+    let mut symbols = SymbolTable::new();
+    let btc = symbols.try_intern("BTC-USD").expect("symbol");
+    let eth = symbols.try_intern("ETH-USD").expect("symbol");
+
+    let snapshot = |ts_ns, symbol, bid, ask| MarketEvent::L2Snapshot {
+        ts_ns,
+        symbol,
+        bids: vec![(Price::new(bid).unwrap(), Qty::new(5).unwrap())],
+        asks: vec![(Price::new(ask).unwrap(), Qty::new(5).unwrap())],
+    };
+    let events = vec![
+        snapshot(1, btc, 100, 104),
+        snapshot(2, eth, 50, 54),
+        snapshot(3, btc, 101, 105),
+        snapshot(4, eth, 51, 55),
+    ];
+
+    let mut file = File::create(&path).expect("create log");
+    for event in events {
+        writeln!(
+            file,
+            "{}",
+            encode_event_json_line(&event, &symbols).expect("encode log")
+        )
+        .expect("write log");
+    }
+
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    let output = Command::new(exe)
+        .args([
+            "simulate",
+            "--input",
+            path.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+            "--symbol",
+            "ETH-USD",
+            "--strategy",
+            "mm",
+        ])
+        .output()
+        .expect("run cli");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout");
+    assert!(stdout.contains("events_read=4"));
+    assert!(stdout.contains("events_applied_to_book=4"));
+    assert!(!stdout.contains("orders_sent=0"));
+    assert!(stdout.contains("symbol=BTC-USD final_position_lots="));
+    assert!(stdout.contains("symbol=ETH-USD final_position_lots="));
+    assert!(!stdout.contains("\nfinal_position_lots="));
+    assert!(stdout.contains("latency="));
+}
+
+#[test]
+fn simulate_twap_writes_blotter_rows() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("events.log");
+    let blotter = dir.path().join("blotter.csv");
+    let mut symbols = SymbolTable::new();
+    let symbol = symbols.try_intern("BTC-USD").expect("symbol");
+
+    let mut events = vec![MarketEvent::L2Snapshot {
+        ts_ns: 1,
+        symbol,
+        bids: vec![(Price::new(100).unwrap(), Qty::new(10).unwrap())],
+        asks: vec![(Price::new(101).unwrap(), Qty::new(10).unwrap())],
+    }];
+    for ts_ns in 2..=8 {
+        events.push(MarketEvent::L2Delta {
+            ts_ns,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Bid,
+                price: Price::new(100).unwrap(),
+                qty: Qty::new(ts_ns as i64).unwrap(),
+            }],
+        });
+    }
+
+    let mut file = File::create(&path).expect("create log");
+    for event in events {
+        writeln!(
+            file,
+            "{}",
+            encode_event_json_line(&event, &symbols).expect("encode log")
+        )
+        .expect("write log");
+    }
+
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    let output = Command::new(exe)
+        .args([
+            "simulate",
+            "--input",
+            path.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+            "--strategy",
+            "twap",
+            "--twap-target",
+            "3",
+            "--twap-horizon",
+            "0",
+            "--blotter",
+            blotter.to_str().expect("path str"),
+        ])
+        .output()
+        .expect("run cli");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout");
+    assert!(stdout.contains("fills_count=3"));
+
+    let contents = std::fs::read_to_string(&blotter).expect("read blotter");
+    let mut lines = contents.lines();
+    assert_eq!(
+        lines.next(),
+        Some("ts_ns,client_order_id,symbol,side,status,filled_qty,price,fee_ticks")
+    );
+    let fills: Vec<&str> = lines.filter(|line| line.contains(",Filled,")).collect();
+    assert_eq!(fills.len(), 3);
+    assert!(fills
+        .iter()
+        .all(|line| line.contains(",BTC-USD,bid,Filled,1,101,")));
+}
+
+#[test]
+fn simulate_flatten_closes_the_final_position() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("events.log");
+    let mut symbols = SymbolTable::new();
+    let symbol = symbols.try_intern("BTC-USD").expect("symbol");
+
+    let mut events = vec![MarketEvent::L2Snapshot {
+        ts_ns: 1,
+        symbol,
+        bids: vec![(Price::new(100).unwrap(), Qty::new(10).unwrap())],
+        asks: vec![(Price::new(101).unwrap(), Qty::new(10).unwrap())],
+    }];
+    for ts_ns in 2..=8 {
+        events.push(MarketEvent::L2Delta {
+            ts_ns,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Bid,
+                price: Price::new(100).unwrap(),
+                qty: Qty::new(10 + ts_ns as i64).unwrap(),
+            }],
+        });
+    }
+
+    let mut file = File::create(&path).expect("create log");
+    for event in events {
+        writeln!(
+            file,
+            "{}",
+            encode_event_json_line(&event, &symbols).expect("encode log")
+        )
+        .expect("write log");
+    }
+
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    let run = |extra: &[&str]| {
+        let output = Command::new(exe)
+            .args([
+                "simulate",
+                "--input",
+                path.to_str().expect("path str"),
+                "--symbol",
+                "BTC-USD",
+                "--strategy",
+                "twap",
+                "--twap-target",
+                "3",
+                "--twap-horizon",
+                "0",
+            ])
+            .args(extra)
+            .output()
+            .expect("run cli");
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).expect("stdout")
+    };
+
+    let open = run(&[]);
+    assert!(open.contains("final_position_lots=3"));
+    assert!(open.contains("realized_pnl_ticks=0"));
+
+    // Bought 3 at the 101 ask, sold back at the 100 bid.
+    let flat = run(&["--flatten"]);
+    assert!(flat.contains("final_position_lots=0"));
+    assert!(flat.contains("realized_pnl_ticks=-3"));
+    assert!(flat.contains("fills_count=4"));
+}
+
+#[test]
+fn simulate_virtual_clock_metrics_are_reproducible() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("events.log");
+    let mut symbols = SymbolTable::new();
+    let symbol = symbols.try_intern("BTC-USD").expect("symbol");
+
+    let mut file = File::create(&path).expect("create log");
+    for i in 0..=2_000u64 {
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1_000_000 + i * 1_000_000,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Bid,
+                price: Price::new(100).unwrap(),
+                qty: Qty::new(1 + (i % 5) as i64).unwrap(),
+            }],
+        };
+        writeln!(
+            file,
+            "{}",
+            encode_event_json_line(&event, &symbols).expect("encode log")
+        )
+        .expect("write log");
+    }
+
+    let exe = env!("CARGO_BIN_EXE_orderbook-replay-lab-rs");
+    let run = || {
+        let output = Command::new(exe)
+            .args([
+                "simulate",
+                "--input",
+                path.to_str().expect("path str"),
+                "--symbol",
+                "BTC-USD",
+                "--virtual-clock",
+            ])
+            .output()
+            .expect("run cli");
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).expect("stdout");
+        // Engine latency stays wall-clock, so it is the one line allowed to differ.
+        assert!(stdout.contains("latency=count=2001 "), "{stdout}");
+        stdout
+            .lines()
+            .filter(|line| !line.starts_with("latency="))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let first = run();
+    assert_eq!(first, run());
+    assert!(
+        first.contains("throughput_overall=1000.50 events/sec"),
+        "{first}"
+    );
+    assert!(
+        first.contains("throughput_windowed=1000.50 events/sec"),
+        "{first}"
+    );
+}
@@ -0,0 +1,105 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use codec::encode_event_json_line;
+use lob_core::{LevelUpdate, MarketEvent, Price, Qty, Side, SymbolTable};
+use tempfile::tempdir;
+
+fn write_feed(path: &Path) {
+    let mut symbols = SymbolTable::new();
+    let symbol = symbols.try_intern("BTC-USD").expect("symbol");
+
+    let updates = [
+        (0, Side::Bid, 100, 1),
+        (1_000, Side::Ask, 104, 1),
+        (3_000, Side::Bid, 102, 1),
+        (4_000, Side::Bid, 102, 0),
+    ];
+    let mut file = File::create(path).expect("create log");
+    for (ts_ns, side, price, qty) in updates {
+        let event = MarketEvent::L2Delta {
+            ts_ns,
+            symbol,
+            updates: vec![LevelUpdate {
+                side,
+                price: Price::new(price).unwrap(),
+                qty: Qty::new(qty).unwrap(),
+            }],
+        };
+        let line = encode_event_json_line(&event, &symbols).expect("encode");
+        writeln!(file, "{}", line).expect("write line");
+    }
+}
+
+#[test]
+fn stats_command_reports_feed_statistics() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("events.log");
+    write_feed(&path);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_orderbook-replay-lab-rs"))
+        .args([
+            "stats",
+            "--input",
+            path.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+        ])
+        .output()
+        .expect("run stats");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for expected in [
+        "events=4",
+        "duration_ns=4000",
+        "update_rate=1000000.00 events/sec",
+        "spread_avg_ticks=3.33",
+        "spread_ticks=count=3",
+        "remove_fraction=0.2500",
+        "bid_levels_max=2",
+        "ask_levels_max=1",
+        "time_weighted_mid=102.33",
+    ] {
+        assert!(stdout.contains(expected), "missing {expected} in: {stdout}");
+    }
+}
+
+#[test]
+fn stats_command_emits_json_summary() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("events.log");
+    write_feed(&path);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_orderbook-replay-lab-rs"))
+        .args([
+            "--output-format",
+            "json",
+            "stats",
+            "--input",
+            path.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+        ])
+        .output()
+        .expect("run stats");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.lines().count(),
+        1,
+        "expected one JSON line: {stdout}"
+    );
+    let summary: serde_json::Value = serde_json::from_str(&stdout).expect("valid json");
+    assert_eq!(summary["events"], 4);
+    assert_eq!(summary["duration_ns"], 4000);
+    assert_eq!(summary["spread_ticks"]["count"], 3);
+    assert_eq!(summary["bid_levels_max"], 2);
+    assert_eq!(summary["ask_levels_max"], 1);
+    assert_eq!(summary["remove_fraction"], 0.25);
+    let mid = summary["time_weighted_mid"].as_f64().expect("mid");
+    assert!((mid - 102.333).abs() < 0.01, "unexpected mid {mid}");
+}
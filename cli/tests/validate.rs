@@ -0,0 +1,61 @@
+#![cfg(feature = "bin")]
+
+use std::fs;
+use std::process::Command;
+
+use tempfile::tempdir;
+
+fn validate(path: &std::path::Path) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_orderbook-replay-lab-rs"))
+        .args([
+            "validate",
+            "--input",
+            path.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+            "--format",
+            "bin",
+        ])
+        .output()
+        .expect("run validate")
+}
+
+#[test]
+fn validate_flags_corrupted_bin_file() {
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("events.bin");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_orderbook-replay-lab-rs"))
+        .args([
+            "gen",
+            "--output",
+            path.to_str().expect("path str"),
+            "--symbol",
+            "BTC-USD",
+            "--events",
+            "30",
+            "--format",
+            "bin",
+        ])
+        .output()
+        .expect("run gen");
+    assert!(output.status.success());
+
+    let clean = validate(&path);
+    assert!(clean.status.success());
+    let stdout = String::from_utf8_lossy(&clean.stdout);
+    assert!(stdout.contains("records=30"), "stdout: {stdout}");
+    assert!(stdout.contains("first_ts_ns=0"), "stdout: {stdout}");
+    assert!(stdout.contains("decode_errors=0"), "stdout: {stdout}");
+
+    let mut bytes = fs::read(&path).expect("read bin");
+    let middle = bytes.len() / 2;
+    bytes[middle] ^= 0xFF;
+    fs::write(&path, bytes).expect("write corrupted bin");
+
+    let corrupted = validate(&path);
+    assert!(!corrupted.status.success());
+    let stdout = String::from_utf8_lossy(&corrupted.stdout);
+    assert!(stdout.contains("decode_errors=1"), "stdout: {stdout}");
+    assert!(stdout.contains("error_offset="), "stdout: {stdout}");
+}
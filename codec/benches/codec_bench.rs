@@ -86,6 +86,28 @@ fn bench_bin(c: &mut Criterion) {
         })
     });
 
+    let batch: Vec<MarketEvent> = (0..256).map(|_| delta.clone()).collect();
+    c.bench_function("codec/bin_encode_per_event_256x_delta_32", |b| {
+        let mut out = Vec::new();
+        b.iter(|| {
+            out.clear();
+            for event in black_box(&batch) {
+                let record =
+                    codec::encode_event_bin_record(event, black_box(&symbols)).expect("encode");
+                out.extend_from_slice(&record);
+            }
+            out.len()
+        })
+    });
+    c.bench_function("codec/bin_encode_batch_256x_delta_32", |b| {
+        let mut out = Vec::new();
+        b.iter(|| {
+            out.clear();
+            codec::encode_events_bin(black_box(&batch), black_box(&symbols), &mut out)
+                .expect("encode")
+        })
+    });
+
     let mut decode_symbols = SymbolTable::try_from_symbols(["BTC-USD"]).expect("symbol table");
     c.bench_function("codec/bin_decode_delta_32", |b| {
         b.iter(|| {
@@ -93,6 +115,14 @@ fn bench_bin(c: &mut Criterion) {
                 .expect("decode")
         })
     });
+
+    let id_record = codec::encode_event_bin_id_record(&delta).expect("encode bin ids");
+    c.bench_function("codec/bin_decode_delta_32_symbol_ids", |b| {
+        b.iter(|| {
+            codec::decode_event_bin_id_record(black_box(&id_record), black_box(&symbols))
+                .expect("decode")
+        })
+    });
 }
 
 fn bench_codec(c: &mut Criterion) {
@@ -1,22 +1,59 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use lob_core::{CoreError, MarketEvent, SymbolTable};
-
-#[cfg(feature = "bin")]
-use lob_core::{LevelUpdate, Price, Qty};
-#[cfg(feature = "bin")]
-use serde::{Deserialize, Serialize};
+use lob_core::{
+    CoreError, LevelUpdate, MarketEvent, Price, Qty, SequencedEvent, Side, SymbolId, SymbolTable,
+};
 
 pub const BIN_RECORD_MAGIC: [u8; 4] = *b"LOB2";
 pub const BIN_RECORD_VERSION: u8 = 1;
+/// Records that reference symbols by `SymbolId` from a preceding file header.
+pub const BIN_RECORD_VERSION_SYMBOL_IDS: u8 = 2;
+/// Symbol-id records whose timestamp is a varint delta from the previous record.
+pub const BIN_RECORD_VERSION_DELTA_TS: u8 = 3;
+/// Newest record version this build can decode.
+pub const BIN_RECORD_VERSION_MAX: u8 = BIN_RECORD_VERSION_DELTA_TS;
 pub const BIN_RECORD_HEADER_LEN: usize = 13;
+/// Optional leading block of a binary file holding its symbol table; uses the record
+/// header layout.
+pub const BIN_FILE_MAGIC: [u8; 4] = *b"LOBF";
+pub const BIN_FILE_VERSION: u8 = 1;
+/// File header that also carries the base timestamp for delta-encoded records.
+pub const BIN_FILE_VERSION_BASE_TS: u8 = 2;
+/// MessagePack records share the binary header layout under their own magic.
+pub const MSGPACK_RECORD_MAGIC: [u8; 4] = *b"LOBM";
+/// Optional first line of a CSV log; decoders skip it wherever it appears.
+pub const CSV_HEADER: &str = "record_type,ts_ns,symbol,side,price,qty,remaining";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct BinRecordHeader {
+    pub version: u8,
     pub payload_len: usize,
     pub checksum: u32,
 }
 
+/// Line layout used by `encode_event_json_line_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonEncoding {
+    /// The bare event object, as `encode_event_json_line` writes.
+    #[default]
+    Plain,
+    /// Appends a top-level `crc` as the last key: CRC32 of the line with that key removed,
+    /// i.e. as `Plain` writes it. Decoders verify a trailing `crc` against the raw line.
+    Checksummed,
+}
+
+/// Record layout used by `encode_events_bin_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinEncoding {
+    /// Self-contained records carrying symbol text, as `encode_events_bin` writes.
+    #[default]
+    Standard,
+    /// A symbol-table header with a base timestamp, then id records whose `ts_ns` is a
+    /// varint delta from the previous record.
+    DeltaTimestamps,
+}
+
 #[derive(Debug, Error)]
 pub enum CodecError {
     #[error("empty input line")]
@@ -27,23 +64,45 @@ pub enum CodecError {
     Core(#[from] CoreError),
     #[error("unknown symbol id: {0}")]
     UnknownSymbolId(u32),
+    #[error("csv decode error: {0}")]
+    Csv(String),
     #[error("binary format support disabled; enable codec/bin feature")]
     BinaryUnsupported,
     #[error("binary record too short")]
     BinaryRecordTooShort,
     #[error("binary record magic mismatch: got {0:?}")]
     BinaryMagicMismatch([u8; 4]),
-    #[error("unsupported binary record version: {0}")]
-    BinaryUnsupportedVersion(u8),
+    #[error("unsupported binary record version {got}; newest supported is {max_supported}")]
+    BinaryVersionUnsupported { got: u8, max_supported: u8 },
     #[error("binary length mismatch: expected {expected} bytes, got {actual}")]
     BinaryLengthMismatch { expected: usize, actual: usize },
     #[error("binary checksum mismatch: expected {expected:#010x}, got {actual:#010x}")]
     BinaryChecksumMismatch { expected: u32, actual: u32 },
+    #[error("json checksum mismatch: expected {expected:#010x}, got {actual:#010x}")]
+    JsonChecksumMismatch { expected: u32, actual: u32 },
+    #[error("json crc must be the trailing `\"crc\":<u32>` key")]
+    JsonChecksumMalformed,
+    #[error("delta-timestamp record without a preceding base timestamp")]
+    BinaryMissingTimestampBase,
     #[error("binary payload too large: {0}")]
     BinaryLengthOverflow(usize),
+    #[error("file symbol table puts {symbol:?} at id {expected}, already mapped to {actual}")]
+    SymbolIdConflict {
+        symbol: String,
+        expected: u32,
+        actual: u32,
+    },
     #[cfg(feature = "bin")]
     #[error("binary codec error: {0}")]
     Binary(#[from] bincode::Error),
+    #[error("msgpack format support disabled; enable codec/msgpack feature")]
+    MsgPackUnsupported,
+    #[cfg(feature = "msgpack")]
+    #[error("msgpack encode error: {0}")]
+    MsgPackEncode(#[from] rmp_serde::encode::Error),
+    #[cfg(feature = "msgpack")]
+    #[error("msgpack decode error: {0}")]
+    MsgPackDecode(#[from] rmp_serde::decode::Error),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +119,13 @@ enum JsonMarketEventOwned {
         bids: Vec<(lob_core::Price, lob_core::Qty)>,
         asks: Vec<(lob_core::Price, lob_core::Qty)>,
     },
+    Trade {
+        ts_ns: u64,
+        symbol: String,
+        price: lob_core::Price,
+        qty: lob_core::Qty,
+        aggressor_side: lob_core::Side,
+    },
 }
 
 #[derive(Debug, Serialize)]
@@ -76,6 +142,76 @@ enum JsonMarketEventRef<'a> {
         bids: &'a [(lob_core::Price, lob_core::Qty)],
         asks: &'a [(lob_core::Price, lob_core::Qty)],
     },
+    Trade {
+        ts_ns: u64,
+        symbol: &'a str,
+        price: lob_core::Price,
+        qty: lob_core::Qty,
+        aggressor_side: lob_core::Side,
+    },
+}
+
+/// A JSON line plus an optional top-level `seq`; plain decoders ignore the extra key.
+#[derive(Debug, Deserialize)]
+struct JsonSequencedEventOwned {
+    #[serde(flatten)]
+    event: JsonMarketEventOwned,
+    #[serde(default)]
+    seq: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSequencedEventRef<'a> {
+    #[serde(flatten)]
+    event: JsonMarketEventRef<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u64>,
+}
+
+/// Only used to tell whether a line carries a top-level `crc` key at all.
+#[derive(Deserialize)]
+struct JsonCrcProbe {
+    #[serde(default)]
+    crc: Option<serde::de::IgnoredAny>,
+}
+
+/// Splits a line ending in exactly `,"crc":<u32>}` into the text before the key and the crc.
+fn split_trailing_crc(line: &str) -> Option<(&str, u32)> {
+    let body = line.strip_suffix('}')?;
+    let key_start = body.rfind(",\"crc\":")?;
+    let value = &body[key_start + ",\"crc\":".len()..];
+    let canonical = !value.is_empty()
+        && value.bytes().all(|byte| byte.is_ascii_digit())
+        && (value == "0" || !value.starts_with('0'));
+    if !canonical {
+        return None;
+    }
+    Some((&body[..key_start], value.parse().ok()?))
+}
+
+/// Checks a trailing `,"crc":N}` against the CRC32 of the raw line without it, which is
+/// exactly what `JsonEncoding::Checksummed` hashed. Lines without a `crc` key pass
+/// unchecked; a `crc` in any other form (spacing, position, non-`u32` value) is an error,
+/// so corrupting the checksum cannot switch the check off.
+fn verify_json_crc(line: &str) -> Result<(), CodecError> {
+    let Some((body, expected)) = split_trailing_crc(line) else {
+        // Only lines that could spell the key, plainly or escaped, pay for the probe.
+        let may_have_crc = line.contains("crc") || line.contains("\\u");
+        if may_have_crc
+            && serde_json::from_str::<JsonCrcProbe>(line).is_ok_and(|probe| probe.crc.is_some())
+        {
+            return Err(CodecError::JsonChecksumMalformed);
+        }
+        return Ok(());
+    };
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(body.as_bytes());
+    hasher.update(b"}");
+    let actual = hasher.finalize();
+    if actual != expected {
+        return Err(CodecError::JsonChecksumMismatch { expected, actual });
+    }
+    Ok(())
 }
 
 impl JsonMarketEventOwned {
@@ -101,6 +237,19 @@ impl JsonMarketEventOwned {
                 bids,
                 asks,
             }),
+            JsonMarketEventOwned::Trade {
+                ts_ns,
+                symbol,
+                price,
+                qty,
+                aggressor_side,
+            } => Ok(MarketEvent::Trade {
+                ts_ns,
+                symbol: symbols.try_intern(&symbol)?,
+                price,
+                qty,
+                aggressor_side,
+            }),
         }
     }
 }
@@ -140,6 +289,24 @@ fn encode_event_json_ref<'a>(
                 asks,
             })
         }
+        MarketEvent::Trade {
+            ts_ns,
+            symbol,
+            price,
+            qty,
+            aggressor_side,
+        } => {
+            let symbol = symbols
+                .try_resolve(*symbol)
+                .ok_or(CodecError::UnknownSymbolId(symbol.as_u32()))?;
+            Ok(JsonMarketEventRef::Trade {
+                ts_ns: *ts_ns,
+                symbol,
+                price: *price,
+                qty: *qty,
+                aggressor_side: *aggressor_side,
+            })
+        }
     }
 }
 
@@ -151,6 +318,21 @@ pub fn encode_event_json_line(
     Ok(serde_json::to_string(&wire)?)
 }
 
+pub fn encode_event_json_line_with(
+    event: &MarketEvent,
+    symbols: &SymbolTable,
+    encoding: JsonEncoding,
+) -> Result<String, CodecError> {
+    let mut line = encode_event_json_line(event, symbols)?;
+    if encoding == JsonEncoding::Checksummed {
+        let crc = crc32fast::hash(line.as_bytes());
+        // The canonical line is a JSON object, so the key goes before its closing brace.
+        line.pop();
+        line.push_str(&format!(",\"crc\":{crc}}}"));
+    }
+    Ok(line)
+}
+
 pub fn decode_event_json_line(
     line: &str,
     symbols: &mut SymbolTable,
@@ -161,27 +343,294 @@ pub fn decode_event_json_line(
         return Err(CodecError::EmptyLine);
     }
 
+    verify_json_crc(line)?;
     let wire: JsonMarketEventOwned = serde_json::from_str(line)?;
     wire.into_core(symbols)
 }
 
+/// Like [`encode_event_json_line`], adding a top-level `seq` key when the event has one.
+pub fn encode_sequenced_event_json_line(
+    event: &SequencedEvent,
+    symbols: &SymbolTable,
+) -> Result<String, CodecError> {
+    let wire = JsonSequencedEventRef {
+        event: encode_event_json_ref(&event.event, symbols)?,
+        seq: event.seq,
+    };
+    Ok(serde_json::to_string(&wire)?)
+}
+
+/// Like [`decode_event_json_line`], also returning the line's `seq` key if present.
+pub fn decode_sequenced_event_json_line(
+    line: &str,
+    symbols: &mut SymbolTable,
+) -> Result<SequencedEvent, CodecError> {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    if line.is_empty() {
+        return Err(CodecError::EmptyLine);
+    }
+
+    verify_json_crc(line)?;
+    let wire: JsonSequencedEventOwned = serde_json::from_str(line)?;
+    Ok(SequencedEvent {
+        seq: wire.seq,
+        event: wire.event.into_core(symbols)?,
+    })
+}
+
+/// Encodes one event as CSV rows separated by `\n` (no trailing newline).
+///
+/// Every delta update and snapshot level is its own row; `remaining` counts the rows still
+/// to follow for the same event, so the last row of each event has `remaining == 0`.
+/// Events without levels are a single row with empty `side`, `price` and `qty`.
+pub fn encode_event_csv_line(
+    event: &MarketEvent,
+    symbols: &SymbolTable,
+) -> Result<String, CodecError> {
+    let symbol_id = event.symbol();
+    let symbol = symbols
+        .try_resolve(symbol_id)
+        .ok_or(CodecError::UnknownSymbolId(symbol_id.as_u32()))?;
+    if symbol.contains([',', '\n', '\r']) {
+        return Err(CodecError::Csv(format!(
+            "symbol {symbol:?} cannot be written as csv"
+        )));
+    }
+
+    let mut rows: Vec<(Side, Price, Qty)> = Vec::new();
+    let record_type = match event {
+        MarketEvent::L2Delta { updates, .. } => {
+            rows.extend(updates.iter().map(|u| (u.side, u.price, u.qty)));
+            "delta"
+        }
+        MarketEvent::L2Snapshot { bids, asks, .. } => {
+            rows.extend(bids.iter().map(|&(price, qty)| (Side::Bid, price, qty)));
+            rows.extend(asks.iter().map(|&(price, qty)| (Side::Ask, price, qty)));
+            "snapshot"
+        }
+        MarketEvent::Trade {
+            price,
+            qty,
+            aggressor_side,
+            ..
+        } => {
+            rows.push((*aggressor_side, *price, *qty));
+            "trade"
+        }
+    };
+
+    let ts_ns = event.ts_ns();
+    if rows.is_empty() {
+        return Ok(format!("{record_type},{ts_ns},{symbol},,,,0"));
+    }
+    let mut out = String::with_capacity(rows.len() * 48);
+    let last = rows.len() - 1;
+    for (index, (side, price, qty)) in rows.into_iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        let side = match side {
+            Side::Bid => "bid",
+            Side::Ask => "ask",
+        };
+        out.push_str(&format!(
+            "{record_type},{ts_ns},{symbol},{side},{},{},{}",
+            price.ticks(),
+            qty.lots(),
+            last - index
+        ));
+    }
+    Ok(out)
+}
+
+/// Decodes the full output of `encode_event_csv_line` (all rows of one event).
+pub fn decode_event_csv_line(
+    text: &str,
+    symbols: &mut SymbolTable,
+) -> Result<MarketEvent, CodecError> {
+    let mut decoder = CsvEventDecoder::new();
+    let mut decoded = None;
+    for line in text.lines() {
+        if decoded.is_some() {
+            return Err(CodecError::Csv("rows after end of event".to_string()));
+        }
+        decoded = decoder.push_line(line, symbols)?;
+    }
+    match decoded {
+        Some(event) => Ok(event),
+        None if text.trim().is_empty() => Err(CodecError::EmptyLine),
+        None => Err(CodecError::Csv("incomplete event".to_string())),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CsvRecordType {
+    Delta,
+    Snapshot,
+    Trade,
+}
+
+#[derive(Debug)]
+struct PendingCsvEvent {
+    record_type: CsvRecordType,
+    ts_ns: u64,
+    symbol: SymbolId,
+    remaining: u64,
+    levels: Vec<LevelUpdate>,
+}
+
+/// Reassembles multi-row CSV events one line at a time.
+#[derive(Debug, Default)]
+pub struct CsvEventDecoder {
+    pending: Option<PendingCsvEvent>,
+}
+
+impl CsvEventDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True when no event is partially assembled, i.e. it is safe to stop reading.
+    pub fn is_idle(&self) -> bool {
+        self.pending.is_none()
+    }
+
+    /// Returns the event once its last row (`remaining == 0`) arrives; header lines yield `None`.
+    pub fn push_line(
+        &mut self,
+        line: &str,
+        symbols: &mut SymbolTable,
+    ) -> Result<Option<MarketEvent>, CodecError> {
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.is_empty() {
+            return Err(CodecError::EmptyLine);
+        }
+        if line == CSV_HEADER {
+            return Ok(None);
+        }
+
+        let fields: Vec<&str> = line.split(',').collect();
+        let [record_type, ts_ns, symbol, side, price, qty, remaining] = fields[..] else {
+            return Err(CodecError::Csv(format!(
+                "expected 7 fields, got {}",
+                fields.len()
+            )));
+        };
+        let record_type = match record_type {
+            "delta" => CsvRecordType::Delta,
+            "snapshot" => CsvRecordType::Snapshot,
+            "trade" => CsvRecordType::Trade,
+            other => return Err(CodecError::Csv(format!("unknown record type {other:?}"))),
+        };
+        let ts_ns: u64 = parse_csv_field("ts_ns", ts_ns)?;
+        let symbol = symbols.try_intern(symbol)?;
+        let remaining: u64 = parse_csv_field("remaining", remaining)?;
+        let level = if side.is_empty() && price.is_empty() && qty.is_empty() {
+            None
+        } else {
+            let side = match side {
+                "bid" => Side::Bid,
+                "ask" => Side::Ask,
+                other => return Err(CodecError::Csv(format!("invalid side {other:?}"))),
+            };
+            Some(LevelUpdate {
+                side,
+                price: Price::new(parse_csv_field("price", price)?)?,
+                qty: Qty::new(parse_csv_field("qty", qty)?)?,
+            })
+        };
+
+        let mut pending = match self.pending.take() {
+            Some(pending) => {
+                if pending.record_type != record_type
+                    || pending.ts_ns != ts_ns
+                    || pending.symbol != symbol
+                    || pending.remaining != remaining + 1
+                {
+                    return Err(CodecError::Csv(
+                        "row does not continue the pending event".to_string(),
+                    ));
+                }
+                pending
+            }
+            None => PendingCsvEvent {
+                record_type,
+                ts_ns,
+                symbol,
+                remaining,
+                levels: Vec::new(),
+            },
+        };
+        pending.remaining = remaining;
+        if let Some(level) = level {
+            pending.levels.push(level);
+        }
+
+        if remaining > 0 {
+            self.pending = Some(pending);
+            return Ok(None);
+        }
+        pending.into_event().map(Some)
+    }
+}
+
+impl PendingCsvEvent {
+    fn into_event(self) -> Result<MarketEvent, CodecError> {
+        match self.record_type {
+            CsvRecordType::Delta => Ok(MarketEvent::L2Delta {
+                ts_ns: self.ts_ns,
+                symbol: self.symbol,
+                updates: self.levels,
+            }),
+            CsvRecordType::Snapshot => {
+                let mut bids = Vec::new();
+                let mut asks = Vec::new();
+                for level in self.levels {
+                    match level.side {
+                        Side::Bid => bids.push((level.price, level.qty)),
+                        Side::Ask => asks.push((level.price, level.qty)),
+                    }
+                }
+                Ok(MarketEvent::L2Snapshot {
+                    ts_ns: self.ts_ns,
+                    symbol: self.symbol,
+                    bids,
+                    asks,
+                })
+            }
+            CsvRecordType::Trade => {
+                let [level] = self.levels[..] else {
+                    return Err(CodecError::Csv("trade needs exactly one row".to_string()));
+                };
+                Ok(MarketEvent::Trade {
+                    ts_ns: self.ts_ns,
+                    symbol: self.symbol,
+                    price: level.price,
+                    qty: level.qty,
+                    aggressor_side: level.side,
+                })
+            }
+        }
+    }
+}
+
+fn parse_csv_field<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, CodecError> {
+    value
+        .parse()
+        .map_err(|_| CodecError::Csv(format!("invalid {name} {value:?}")))
+}
+
 pub fn encode_event_bin_record(
     event: &MarketEvent,
     symbols: &SymbolTable,
 ) -> Result<Vec<u8>, CodecError> {
     #[cfg(feature = "bin")]
     {
-        let payload = bincode::serialize(&BinMarketEventRef::from_core(event, symbols)?)?;
-        let len = u32::try_from(payload.len())
-            .map_err(|_| CodecError::BinaryLengthOverflow(payload.len()))?;
-        let checksum = crc32fast::hash(&payload);
-
-        let mut out = Vec::with_capacity(BIN_RECORD_HEADER_LEN + payload.len());
-        out.extend_from_slice(&BIN_RECORD_MAGIC);
-        out.push(BIN_RECORD_VERSION);
-        out.extend_from_slice(&len.to_le_bytes());
-        out.extend_from_slice(&checksum.to_le_bytes());
-        out.extend_from_slice(&payload);
+        let mut out = Vec::new();
+        let wire = BinMarketEventRef::from_core(event, symbols)?;
+        append_bin_record(BIN_RECORD_MAGIC, BIN_RECORD_VERSION, &wire, &mut out)?;
         Ok(out)
     }
     #[cfg(not(feature = "bin"))]
@@ -192,30 +641,267 @@ pub fn encode_event_bin_record(
     }
 }
 
-pub fn decode_event_bin_record(
-    record: &[u8],
-    symbols: &mut SymbolTable,
-) -> Result<MarketEvent, CodecError> {
+/// Appends framed binary records for `events` to `out`, reusing its allocation.
+/// Returns the number of bytes written; on error `out` is truncated back to its
+/// original length.
+pub fn encode_events_bin<'a, I>(
+    events: I,
+    symbols: &SymbolTable,
+    out: &mut Vec<u8>,
+) -> Result<usize, CodecError>
+where
+    I: IntoIterator<Item = &'a MarketEvent>,
+{
     #[cfg(feature = "bin")]
     {
-        let header = decode_event_bin_header(record)?;
-        let actual = record.len().saturating_sub(BIN_RECORD_HEADER_LEN);
-        if actual != header.payload_len {
-            return Err(CodecError::BinaryLengthMismatch {
-                expected: header.payload_len,
-                actual,
+        let start = out.len();
+        for event in events {
+            let appended = BinMarketEventRef::from_core(event, symbols).and_then(|wire| {
+                append_bin_record(BIN_RECORD_MAGIC, BIN_RECORD_VERSION, &wire, out)
             });
+            if let Err(err) = appended {
+                out.truncate(start);
+                return Err(err);
+            }
         }
+        Ok(out.len() - start)
+    }
+    #[cfg(not(feature = "bin"))]
+    {
+        let _ = events;
+        let _ = symbols;
+        let _ = out;
+        Err(CodecError::BinaryUnsupported)
+    }
+}
 
-        let payload = &record[BIN_RECORD_HEADER_LEN..];
-        let actual_checksum = crc32fast::hash(payload);
-        if actual_checksum != header.checksum {
-            return Err(CodecError::BinaryChecksumMismatch {
-                expected: header.checksum,
-                actual: actual_checksum,
-            });
+/// Like `encode_events_bin`, with the record layout chosen by `encoding`. For
+/// `BinEncoding::DeltaTimestamps` the output is a complete file: header first, with the
+/// first event's timestamp as base.
+pub fn encode_events_bin_with<'a, I>(
+    events: I,
+    symbols: &SymbolTable,
+    encoding: BinEncoding,
+    out: &mut Vec<u8>,
+) -> Result<usize, CodecError>
+where
+    I: IntoIterator<Item = &'a MarketEvent>,
+{
+    match encoding {
+        BinEncoding::Standard => encode_events_bin(events, symbols, out),
+        #[cfg(feature = "bin")]
+        BinEncoding::DeltaTimestamps => {
+            let start = out.len();
+            let result = append_delta_ts_file(events, symbols, out);
+            if result.is_err() {
+                out.truncate(start);
+            }
+            result.map(|()| out.len() - start)
         }
+        #[cfg(not(feature = "bin"))]
+        BinEncoding::DeltaTimestamps => {
+            let _ = events;
+            let _ = out;
+            Err(CodecError::BinaryUnsupported)
+        }
+    }
+}
+
+#[cfg(feature = "bin")]
+fn append_delta_ts_file<'a, I>(
+    events: I,
+    symbols: &SymbolTable,
+    out: &mut Vec<u8>,
+) -> Result<(), CodecError>
+where
+    I: IntoIterator<Item = &'a MarketEvent>,
+{
+    use bincode::Options;
 
+    let mut events = events.into_iter().peekable();
+    let base_ts_ns = events.peek().map_or(0, |event| event.ts_ns());
+    let header = BinFileHeader {
+        symbols: symbol_names(symbols),
+        base_ts_ns,
+    };
+    append_bin_record(BIN_FILE_MAGIC, BIN_FILE_VERSION_BASE_TS, &header, out)?;
+
+    let mut prev_ts_ns = base_ts_ns;
+    for event in events {
+        let mut wire = BinMarketEventRef::from_core_with(event, |id| Ok(id.as_u32()))?;
+        *wire.ts_ns_mut() = event.ts_ns().wrapping_sub(prev_ts_ns);
+        prev_ts_ns = event.ts_ns();
+        append_framed(BIN_RECORD_MAGIC, BIN_RECORD_VERSION_DELTA_TS, out, |out| {
+            bincode::DefaultOptions::new().serialize_into(out, &wire)
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "bin")]
+fn symbol_names(symbols: &SymbolTable) -> Vec<&str> {
+    (0..symbols.len() as u32)
+        .filter_map(|raw| symbols.try_resolve(SymbolId::from_u32(raw)))
+        .collect()
+}
+
+#[cfg(feature = "bin")]
+#[derive(Serialize, Deserialize)]
+struct BinFileHeader<S> {
+    symbols: Vec<S>,
+    base_ts_ns: u64,
+}
+
+/// Frames `symbols` in id order as the leading block of a binary file, so the records
+/// after it can be written with `encode_event_bin_id_record`.
+pub fn encode_bin_file_header(symbols: &SymbolTable) -> Result<Vec<u8>, CodecError> {
+    #[cfg(feature = "bin")]
+    {
+        let mut out = Vec::new();
+        append_bin_record(
+            BIN_FILE_MAGIC,
+            BIN_FILE_VERSION,
+            &symbol_names(symbols),
+            &mut out,
+        )?;
+        Ok(out)
+    }
+    #[cfg(not(feature = "bin"))]
+    {
+        let _ = symbols;
+        Err(CodecError::BinaryUnsupported)
+    }
+}
+
+/// Parses the fixed-size frame of a file header block to learn its payload length.
+pub fn decode_bin_file_frame(header: &[u8]) -> Result<BinRecordHeader, CodecError> {
+    #[cfg(feature = "bin")]
+    {
+        decode_record_header(
+            header,
+            BIN_FILE_MAGIC,
+            BIN_FILE_VERSION..=BIN_FILE_VERSION_BASE_TS,
+        )
+    }
+    #[cfg(not(feature = "bin"))]
+    {
+        let _ = header;
+        Err(CodecError::BinaryUnsupported)
+    }
+}
+
+/// Interns the file's symbols into `symbols`. Each must land on the id it had when the
+/// file was written, so predeclared symbols have to match the file's leading entries.
+/// Returns the base timestamp for delta-encoded records when the header carries one.
+pub fn decode_bin_file_header(
+    block: &[u8],
+    symbols: &mut SymbolTable,
+) -> Result<Option<u64>, CodecError> {
+    #[cfg(feature = "bin")]
+    {
+        let header = decode_bin_file_frame(block)?;
+        let payload = unframe_record(block, BIN_FILE_MAGIC, header.version..=header.version)?;
+        let (names, base_ts_ns) = if header.version == BIN_FILE_VERSION_BASE_TS {
+            let header: BinFileHeader<String> = bincode::deserialize(payload)?;
+            (header.symbols, Some(header.base_ts_ns))
+        } else {
+            (bincode::deserialize::<Vec<String>>(payload)?, None)
+        };
+        for (expected, name) in names.iter().enumerate() {
+            let expected = expected as u32;
+            let actual = symbols.try_intern(name)?.as_u32();
+            if actual != expected {
+                return Err(CodecError::SymbolIdConflict {
+                    symbol: name.clone(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(base_ts_ns)
+    }
+    #[cfg(not(feature = "bin"))]
+    {
+        let _ = block;
+        let _ = symbols;
+        Err(CodecError::BinaryUnsupported)
+    }
+}
+
+/// Like `encode_event_bin_record`, but stores the raw `SymbolId` instead of its text.
+/// Only meaningful after a header from `encode_bin_file_header`.
+pub fn encode_event_bin_id_record(event: &MarketEvent) -> Result<Vec<u8>, CodecError> {
+    #[cfg(feature = "bin")]
+    {
+        let mut out = Vec::new();
+        let wire = BinMarketEventRef::from_core_with(event, |id| Ok(id.as_u32()))?;
+        append_bin_record(
+            BIN_RECORD_MAGIC,
+            BIN_RECORD_VERSION_SYMBOL_IDS,
+            &wire,
+            &mut out,
+        )?;
+        Ok(out)
+    }
+    #[cfg(not(feature = "bin"))]
+    {
+        let _ = event;
+        Err(CodecError::BinaryUnsupported)
+    }
+}
+
+#[cfg(feature = "bin")]
+fn append_bin_record<T: Serialize + ?Sized>(
+    magic: [u8; 4],
+    version: u8,
+    wire: &T,
+    out: &mut Vec<u8>,
+) -> Result<(), CodecError> {
+    append_framed(magic, version, out, |out| {
+        bincode::serialize_into(out, wire)
+    })
+}
+
+/// Serializes straight into `out` after a placeholder header, then patches length and CRC.
+#[cfg(feature = "bin")]
+fn append_framed(
+    magic: [u8; 4],
+    version: u8,
+    out: &mut Vec<u8>,
+    serialize: impl FnOnce(&mut Vec<u8>) -> bincode::Result<()>,
+) -> Result<(), CodecError> {
+    let start = out.len();
+    out.extend_from_slice(&magic);
+    out.push(version);
+    out.extend_from_slice(&[0u8; 8]);
+    let payload_start = out.len();
+    if let Err(err) = serialize(out) {
+        out.truncate(start);
+        return Err(err.into());
+    }
+
+    let payload_len = out.len() - payload_start;
+    let Ok(len) = u32::try_from(payload_len) else {
+        out.truncate(start);
+        return Err(CodecError::BinaryLengthOverflow(payload_len));
+    };
+    let checksum = crc32fast::hash(&out[payload_start..]);
+    out[start + 5..start + 9].copy_from_slice(&len.to_le_bytes());
+    out[start + 9..start + 13].copy_from_slice(&checksum.to_le_bytes());
+    Ok(())
+}
+
+pub fn decode_event_bin_record(
+    record: &[u8],
+    symbols: &mut SymbolTable,
+) -> Result<MarketEvent, CodecError> {
+    #[cfg(feature = "bin")]
+    {
+        let payload = unframe_record(
+            record,
+            BIN_RECORD_MAGIC,
+            BIN_RECORD_VERSION..=BIN_RECORD_VERSION,
+        )?;
         decode_event_bin_payload(payload, symbols)
     }
     #[cfg(not(feature = "bin"))]
@@ -226,29 +912,114 @@ pub fn decode_event_bin_record(
     }
 }
 
-pub fn decode_event_bin_header(header: &[u8]) -> Result<BinRecordHeader, CodecError> {
+/// Decodes a record from `encode_event_bin_id_record`; ids must already be in `symbols`.
+pub fn decode_event_bin_id_record(
+    record: &[u8],
+    symbols: &SymbolTable,
+) -> Result<MarketEvent, CodecError> {
     #[cfg(feature = "bin")]
     {
-        if header.len() < BIN_RECORD_HEADER_LEN {
-            return Err(CodecError::BinaryRecordTooShort);
-        }
+        let payload = unframe_record(
+            record,
+            BIN_RECORD_MAGIC,
+            BIN_RECORD_VERSION_SYMBOL_IDS..=BIN_RECORD_VERSION_SYMBOL_IDS,
+        )?;
+        decode_bin_payload_v2(payload, symbols)
+    }
+    #[cfg(not(feature = "bin"))]
+    {
+        let _ = record;
+        let _ = symbols;
+        Err(CodecError::BinaryUnsupported)
+    }
+}
 
-        let magic = [header[0], header[1], header[2], header[3]];
-        if magic != BIN_RECORD_MAGIC {
-            return Err(CodecError::BinaryMagicMismatch(magic));
-        }
+/// Decodes a record of any version up to `BIN_RECORD_VERSION_MAX`, dispatching on the
+/// header version. v1 interns symbol text into `symbols`; v2 resolves ids against it.
+/// Delta-timestamp records need stream context; use `decode_event_bin_record_in_stream`.
+pub fn decode_event_bin_record_versioned(
+    record: &[u8],
+    symbols: &mut SymbolTable,
+) -> Result<MarketEvent, CodecError> {
+    decode_event_bin_record_in_stream(record, symbols, &mut None)
+}
 
-        let version = header[4];
-        if version != BIN_RECORD_VERSION {
-            return Err(CodecError::BinaryUnsupportedVersion(version));
-        }
+/// Versioned decode that tracks the previous record's timestamp in `last_ts_ns`, seeded
+/// from `decode_bin_file_header`, so delta-timestamp records decode to absolute `ts_ns`.
+pub fn decode_event_bin_record_in_stream(
+    record: &[u8],
+    symbols: &mut SymbolTable,
+    last_ts_ns: &mut Option<u64>,
+) -> Result<MarketEvent, CodecError> {
+    #[cfg(feature = "bin")]
+    {
+        let header = decode_event_bin_header(record)?;
+        let payload = unframe_record(record, BIN_RECORD_MAGIC, header.version..=header.version)?;
+        let event = match header.version {
+            BIN_RECORD_VERSION => decode_event_bin_payload(payload, symbols)?,
+            BIN_RECORD_VERSION_SYMBOL_IDS => decode_bin_payload_v2(payload, symbols)?,
+            BIN_RECORD_VERSION_DELTA_TS => {
+                let prev_ts_ns = last_ts_ns.ok_or(CodecError::BinaryMissingTimestampBase)?;
+                decode_bin_payload_v3(payload, symbols, prev_ts_ns)?
+            }
+            got => {
+                return Err(CodecError::BinaryVersionUnsupported {
+                    got,
+                    max_supported: BIN_RECORD_VERSION_MAX,
+                })
+            }
+        };
+        *last_ts_ns = Some(event.ts_ns());
+        Ok(event)
+    }
+    #[cfg(not(feature = "bin"))]
+    {
+        let _ = record;
+        let _ = symbols;
+        let _ = last_ts_ns;
+        Err(CodecError::BinaryUnsupported)
+    }
+}
 
-        let payload_len = u32::from_le_bytes([header[5], header[6], header[7], header[8]]) as usize;
-        let checksum = u32::from_le_bytes([header[9], header[10], header[11], header[12]]);
-        Ok(BinRecordHeader {
-            payload_len,
-            checksum,
-        })
+#[cfg(feature = "bin")]
+fn decode_bin_payload_v2(payload: &[u8], symbols: &SymbolTable) -> Result<MarketEvent, CodecError> {
+    let event: BinMarketEventOwned<u32> = bincode::deserialize(payload)?;
+    event.into_core_with(|raw| resolve_symbol_id(raw, symbols))
+}
+
+#[cfg(feature = "bin")]
+fn decode_bin_payload_v3(
+    payload: &[u8],
+    symbols: &SymbolTable,
+    prev_ts_ns: u64,
+) -> Result<MarketEvent, CodecError> {
+    use bincode::Options;
+
+    let mut event: BinMarketEventOwned<u32> =
+        bincode::DefaultOptions::new().deserialize(payload)?;
+    let ts_ns = event.ts_ns_mut();
+    *ts_ns = prev_ts_ns.wrapping_add(*ts_ns);
+    event.into_core_with(|raw| resolve_symbol_id(raw, symbols))
+}
+
+#[cfg(feature = "bin")]
+fn resolve_symbol_id(raw: u32, symbols: &SymbolTable) -> Result<SymbolId, CodecError> {
+    if (raw as usize) < symbols.len() {
+        Ok(SymbolId::from_u32(raw))
+    } else {
+        Err(CodecError::UnknownSymbolId(raw))
+    }
+}
+
+/// Accepts every record version up to `BIN_RECORD_VERSION_MAX`.
+pub fn decode_event_bin_header(header: &[u8]) -> Result<BinRecordHeader, CodecError> {
+    #[cfg(feature = "bin")]
+    {
+        decode_record_header(
+            header,
+            BIN_RECORD_MAGIC,
+            BIN_RECORD_VERSION..=BIN_RECORD_VERSION_MAX,
+        )
     }
     #[cfg(not(feature = "bin"))]
     {
@@ -257,6 +1028,80 @@ pub fn decode_event_bin_header(header: &[u8]) -> Result<BinRecordHeader, CodecEr
     }
 }
 
+#[cfg(any(feature = "bin", feature = "msgpack"))]
+fn decode_record_header(
+    header: &[u8],
+    magic: [u8; 4],
+    versions: std::ops::RangeInclusive<u8>,
+) -> Result<BinRecordHeader, CodecError> {
+    if header.len() < BIN_RECORD_HEADER_LEN {
+        return Err(CodecError::BinaryRecordTooShort);
+    }
+
+    let actual_magic = [header[0], header[1], header[2], header[3]];
+    if actual_magic != magic {
+        return Err(CodecError::BinaryMagicMismatch(actual_magic));
+    }
+
+    let version = header[4];
+    if !versions.contains(&version) {
+        return Err(CodecError::BinaryVersionUnsupported {
+            got: version,
+            max_supported: *versions.end(),
+        });
+    }
+
+    let payload_len = u32::from_le_bytes([header[5], header[6], header[7], header[8]]) as usize;
+    let checksum = u32::from_le_bytes([header[9], header[10], header[11], header[12]]);
+    Ok(BinRecordHeader {
+        version,
+        payload_len,
+        checksum,
+    })
+}
+
+#[cfg(feature = "msgpack")]
+fn frame_record(magic: [u8; 4], payload: &[u8]) -> Result<Vec<u8>, CodecError> {
+    let len = u32::try_from(payload.len())
+        .map_err(|_| CodecError::BinaryLengthOverflow(payload.len()))?;
+    let checksum = crc32fast::hash(payload);
+
+    let mut out = Vec::with_capacity(BIN_RECORD_HEADER_LEN + payload.len());
+    out.extend_from_slice(&magic);
+    out.push(BIN_RECORD_VERSION);
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&checksum.to_le_bytes());
+    out.extend_from_slice(payload);
+    Ok(out)
+}
+
+/// Validates length and CRC and returns the payload slice.
+#[cfg(any(feature = "bin", feature = "msgpack"))]
+fn unframe_record(
+    record: &[u8],
+    magic: [u8; 4],
+    versions: std::ops::RangeInclusive<u8>,
+) -> Result<&[u8], CodecError> {
+    let header = decode_record_header(record, magic, versions)?;
+    let actual = record.len().saturating_sub(BIN_RECORD_HEADER_LEN);
+    if actual != header.payload_len {
+        return Err(CodecError::BinaryLengthMismatch {
+            expected: header.payload_len,
+            actual,
+        });
+    }
+
+    let payload = &record[BIN_RECORD_HEADER_LEN..];
+    let actual_checksum = crc32fast::hash(payload);
+    if actual_checksum != header.checksum {
+        return Err(CodecError::BinaryChecksumMismatch {
+            expected: header.checksum,
+            actual: actual_checksum,
+        });
+    }
+    Ok(payload)
+}
+
 pub fn decode_event_bin_payload(
     payload: &[u8],
     symbols: &mut SymbolTable,
@@ -274,79 +1119,196 @@ pub fn decode_event_bin_payload(
     }
 }
 
-#[cfg(feature = "bin")]
+/// Same header and CRC framing as binary records, with a MessagePack payload using named
+/// fields so other languages can decode it.
+pub fn encode_event_msgpack(
+    event: &MarketEvent,
+    symbols: &SymbolTable,
+) -> Result<Vec<u8>, CodecError> {
+    #[cfg(feature = "msgpack")]
+    {
+        let payload = rmp_serde::to_vec_named(&BinMarketEventRef::from_core(event, symbols)?)?;
+        frame_record(MSGPACK_RECORD_MAGIC, &payload)
+    }
+    #[cfg(not(feature = "msgpack"))]
+    {
+        let _ = event;
+        let _ = symbols;
+        Err(CodecError::MsgPackUnsupported)
+    }
+}
+
+pub fn decode_event_msgpack(
+    record: &[u8],
+    symbols: &mut SymbolTable,
+) -> Result<MarketEvent, CodecError> {
+    #[cfg(feature = "msgpack")]
+    {
+        let payload = unframe_record(
+            record,
+            MSGPACK_RECORD_MAGIC,
+            BIN_RECORD_VERSION..=BIN_RECORD_VERSION,
+        )?;
+        let event: BinMarketEventOwned = rmp_serde::from_slice(payload)?;
+        event.into_core(symbols)
+    }
+    #[cfg(not(feature = "msgpack"))]
+    {
+        let _ = record;
+        let _ = symbols;
+        Err(CodecError::MsgPackUnsupported)
+    }
+}
+
+pub fn decode_event_msgpack_header(header: &[u8]) -> Result<BinRecordHeader, CodecError> {
+    #[cfg(feature = "msgpack")]
+    {
+        decode_record_header(
+            header,
+            MSGPACK_RECORD_MAGIC,
+            BIN_RECORD_VERSION..=BIN_RECORD_VERSION,
+        )
+    }
+    #[cfg(not(feature = "msgpack"))]
+    {
+        let _ = header;
+        Err(CodecError::MsgPackUnsupported)
+    }
+}
+
+/// Symbols travel as text (`S = String`) or, after a file header, as raw ids (`S = u32`).
+#[cfg(any(feature = "bin", feature = "msgpack"))]
 #[derive(Debug, Serialize, Deserialize)]
-enum BinMarketEventOwned {
+enum BinMarketEventOwned<S = String> {
     L2Delta {
         ts_ns: u64,
-        symbol: String,
+        symbol: S,
         updates: Vec<LevelUpdate>,
     },
     L2Snapshot {
         ts_ns: u64,
-        symbol: String,
+        symbol: S,
         bids: Vec<(Price, Qty)>,
         asks: Vec<(Price, Qty)>,
     },
+    Trade {
+        ts_ns: u64,
+        symbol: S,
+        price: Price,
+        qty: Qty,
+        aggressor_side: Side,
+    },
 }
 
-#[cfg(feature = "bin")]
+#[cfg(any(feature = "bin", feature = "msgpack"))]
 #[derive(Debug, Serialize)]
-enum BinMarketEventRef<'a> {
+enum BinMarketEventRef<'a, S = &'a str> {
     L2Delta {
         ts_ns: u64,
-        symbol: &'a str,
+        symbol: S,
         updates: &'a [LevelUpdate],
     },
     L2Snapshot {
         ts_ns: u64,
-        symbol: &'a str,
+        symbol: S,
         bids: &'a [(Price, Qty)],
         asks: &'a [(Price, Qty)],
     },
+    Trade {
+        ts_ns: u64,
+        symbol: S,
+        price: Price,
+        qty: Qty,
+        aggressor_side: Side,
+    },
 }
 
-#[cfg(feature = "bin")]
+#[cfg(any(feature = "bin", feature = "msgpack"))]
 impl<'a> BinMarketEventRef<'a> {
     fn from_core(event: &'a MarketEvent, symbols: &'a SymbolTable) -> Result<Self, CodecError> {
+        Self::from_core_with(event, |symbol| {
+            symbols
+                .try_resolve(symbol)
+                .ok_or(CodecError::UnknownSymbolId(symbol.as_u32()))
+        })
+    }
+}
+
+#[cfg(any(feature = "bin", feature = "msgpack"))]
+impl<'a, S> BinMarketEventRef<'a, S> {
+    #[cfg(feature = "bin")]
+    fn ts_ns_mut(&mut self) -> &mut u64 {
+        match self {
+            Self::L2Delta { ts_ns, .. }
+            | Self::L2Snapshot { ts_ns, .. }
+            | Self::Trade { ts_ns, .. } => ts_ns,
+        }
+    }
+
+    fn from_core_with(
+        event: &'a MarketEvent,
+        encode_symbol: impl FnOnce(SymbolId) -> Result<S, CodecError>,
+    ) -> Result<Self, CodecError> {
         match event {
             MarketEvent::L2Delta {
                 ts_ns,
                 symbol,
                 updates,
-            } => {
-                let symbol = symbols
-                    .try_resolve(*symbol)
-                    .ok_or(CodecError::UnknownSymbolId(symbol.as_u32()))?;
-                Ok(Self::L2Delta {
-                    ts_ns: *ts_ns,
-                    symbol,
-                    updates,
-                })
-            }
+            } => Ok(Self::L2Delta {
+                ts_ns: *ts_ns,
+                symbol: encode_symbol(*symbol)?,
+                updates,
+            }),
             MarketEvent::L2Snapshot {
                 ts_ns,
                 symbol,
                 bids,
                 asks,
-            } => {
-                let symbol = symbols
-                    .try_resolve(*symbol)
-                    .ok_or(CodecError::UnknownSymbolId(symbol.as_u32()))?;
-                Ok(Self::L2Snapshot {
-                    ts_ns: *ts_ns,
-                    symbol,
-                    bids,
-                    asks,
-                })
-            }
+            } => Ok(Self::L2Snapshot {
+                ts_ns: *ts_ns,
+                symbol: encode_symbol(*symbol)?,
+                bids,
+                asks,
+            }),
+            MarketEvent::Trade {
+                ts_ns,
+                symbol,
+                price,
+                qty,
+                aggressor_side,
+            } => Ok(Self::Trade {
+                ts_ns: *ts_ns,
+                symbol: encode_symbol(*symbol)?,
+                price: *price,
+                qty: *qty,
+                aggressor_side: *aggressor_side,
+            }),
         }
     }
 }
 
-#[cfg(feature = "bin")]
+#[cfg(any(feature = "bin", feature = "msgpack"))]
 impl BinMarketEventOwned {
     fn into_core(self, symbols: &mut SymbolTable) -> Result<MarketEvent, CodecError> {
+        self.into_core_with(|symbol| Ok(symbols.try_intern(&symbol)?))
+    }
+}
+
+#[cfg(any(feature = "bin", feature = "msgpack"))]
+impl<S> BinMarketEventOwned<S> {
+    #[cfg(feature = "bin")]
+    fn ts_ns_mut(&mut self) -> &mut u64 {
+        match self {
+            Self::L2Delta { ts_ns, .. }
+            | Self::L2Snapshot { ts_ns, .. }
+            | Self::Trade { ts_ns, .. } => ts_ns,
+        }
+    }
+
+    fn into_core_with(
+        self,
+        decode_symbol: impl FnOnce(S) -> Result<SymbolId, CodecError>,
+    ) -> Result<MarketEvent, CodecError> {
         match self {
             BinMarketEventOwned::L2Delta {
                 ts_ns,
@@ -354,7 +1316,7 @@ impl BinMarketEventOwned {
                 updates,
             } => Ok(MarketEvent::L2Delta {
                 ts_ns,
-                symbol: symbols.try_intern(&symbol)?,
+                symbol: decode_symbol(symbol)?,
                 updates,
             }),
             BinMarketEventOwned::L2Snapshot {
@@ -364,10 +1326,23 @@ impl BinMarketEventOwned {
                 asks,
             } => Ok(MarketEvent::L2Snapshot {
                 ts_ns,
-                symbol: symbols.try_intern(&symbol)?,
+                symbol: decode_symbol(symbol)?,
                 bids,
                 asks,
             }),
+            BinMarketEventOwned::Trade {
+                ts_ns,
+                symbol,
+                price,
+                qty,
+                aggressor_side,
+            } => Ok(MarketEvent::Trade {
+                ts_ns,
+                symbol: decode_symbol(symbol)?,
+                price,
+                qty,
+                aggressor_side,
+            }),
         }
     }
 }
@@ -400,6 +1375,148 @@ mod tests {
         assert_eq!(decoded, event);
     }
 
+    #[test]
+    fn checksummed_json_line_detects_tampering() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("BTC-USD").unwrap();
+        let event = sample_event(symbol);
+
+        let plain = encode_event_json_line(&event, &symbols).unwrap();
+        let line =
+            encode_event_json_line_with(&event, &symbols, JsonEncoding::Checksummed).unwrap();
+        assert_eq!(
+            encode_event_json_line_with(&event, &symbols, JsonEncoding::Plain).unwrap(),
+            plain
+        );
+        assert!(line.starts_with(&plain[..plain.len() - 1]));
+        assert!(line.ends_with(&format!(",\"crc\":{}}}", crc32fast::hash(plain.as_bytes()))));
+        assert_eq!(decode_event_json_line(&line, &mut symbols).unwrap(), event);
+
+        let tampered = line.replacen("\"ts_ns\":", "\"ts_ns\":9", 1);
+        assert!(matches!(
+            decode_event_json_line(&tampered, &mut symbols),
+            Err(CodecError::JsonChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            decode_sequenced_event_json_line(&tampered, &mut symbols),
+            Err(CodecError::JsonChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn checksummed_json_line_rejects_tampered_crc() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("BTC-USD").unwrap();
+        let event = sample_event(symbol);
+        let line =
+            encode_event_json_line_with(&event, &symbols, JsonEncoding::Checksummed).unwrap();
+        let (body, crc) = line.rsplit_once(",\"crc\":").unwrap();
+        let crc = crc.strip_suffix('}').unwrap();
+
+        let wrong_digits = format!("{body},\"crc\":{}}}", crc.parse::<u32>().unwrap() ^ 1);
+        assert!(matches!(
+            decode_event_json_line(&wrong_digits, &mut symbols),
+            Err(CodecError::JsonChecksumMismatch { .. })
+        ));
+
+        // Not JSON at all any more, so the event parse rejects it.
+        let non_digit = format!("{body},\"crc\":{}x}}", &crc[..crc.len() - 1]);
+        assert!(matches!(
+            decode_event_json_line(&non_digit, &mut symbols),
+            Err(CodecError::Json(_))
+        ));
+
+        let spaced = format!("{body},\"crc\": {crc}}}");
+        let trailing_space = format!("{line} ");
+        let quoted = format!("{body},\"crc\":\"{crc}\"}}");
+        let overflowed = format!("{body},\"crc\":{crc}0000000000}}");
+        let not_last = format!("{{\"crc\":{crc},{}", &body[1..]) + "}";
+        let escaped_key = format!("{body},\"\\u0063rc\":{crc} }}");
+        for malformed in [
+            spaced,
+            trailing_space,
+            quoted,
+            overflowed,
+            not_last,
+            escaped_key,
+        ] {
+            assert!(
+                matches!(
+                    decode_event_json_line(&malformed, &mut symbols),
+                    Err(CodecError::JsonChecksumMalformed)
+                ),
+                "{malformed}"
+            );
+            assert!(
+                matches!(
+                    decode_sequenced_event_json_line(&malformed, &mut symbols),
+                    Err(CodecError::JsonChecksumMalformed)
+                ),
+                "{malformed}"
+            );
+        }
+        assert_eq!(decode_event_json_line(&line, &mut symbols).unwrap(), event);
+    }
+
+    #[test]
+    fn checksummed_json_line_round_trips_escaped_symbols() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("BTC\"USD\\perp\u{e9}").unwrap();
+        let event = sample_event(symbol);
+
+        let line =
+            encode_event_json_line_with(&event, &symbols, JsonEncoding::Checksummed).unwrap();
+        assert!(line.contains("BTC\\\"USD\\\\perp"), "{line}");
+        assert_eq!(decode_event_json_line(&line, &mut symbols).unwrap(), event);
+        assert_eq!(
+            decode_sequenced_event_json_line(&line, &mut symbols)
+                .unwrap()
+                .event,
+            event
+        );
+
+        // The crc covers the bytes as written, so an equivalent but differently escaped
+        // line checks out against its own hash, not the encoder's canonical form.
+        let plain = encode_event_json_line(&event, &symbols)
+            .unwrap()
+            .replace('\u{e9}', "\\u00e9");
+        let crc = crc32fast::hash(plain.as_bytes());
+        let line = format!("{},\"crc\":{crc}}}", &plain[..plain.len() - 1]);
+        assert_eq!(decode_event_json_line(&line, &mut symbols).unwrap(), event);
+    }
+
+    #[test]
+    fn round_trip_sequenced_json_line() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("BTC-USD").unwrap();
+        let event = SequencedEvent {
+            seq: Some(17),
+            event: sample_event(symbol),
+        };
+
+        let line = encode_sequenced_event_json_line(&event, &symbols).unwrap();
+        assert!(line.ends_with(",\"seq\":17}"));
+        let decoded = decode_sequenced_event_json_line(&line, &mut symbols).unwrap();
+        assert_eq!(decoded, event);
+        // Readers that don't know about `seq` still decode the event.
+        assert_eq!(
+            decode_event_json_line(&line, &mut symbols).unwrap(),
+            event.event
+        );
+
+        let unsequenced = SequencedEvent {
+            seq: None,
+            event: sample_event(symbol),
+        };
+        let line = encode_sequenced_event_json_line(&unsequenced, &symbols).unwrap();
+        assert_eq!(
+            line,
+            encode_event_json_line(&unsequenced.event, &symbols).unwrap()
+        );
+        let decoded = decode_sequenced_event_json_line(&line, &mut symbols).unwrap();
+        assert_eq!(decoded, unsequenced);
+    }
+
     #[test]
     fn round_trip_json_line_snapshot() {
         let mut symbols = SymbolTable::new();
@@ -422,6 +1539,85 @@ mod tests {
         assert_eq!(decoded, event);
     }
 
+    fn sample_trade(symbol: SymbolId) -> MarketEvent {
+        MarketEvent::Trade {
+            ts_ns: 9,
+            symbol,
+            price: Price::new(101).unwrap(),
+            qty: Qty::new(4).unwrap(),
+            aggressor_side: Side::Ask,
+        }
+    }
+
+    #[test]
+    fn round_trip_json_line_trade() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("SOL-USD").unwrap();
+        let event = sample_trade(symbol);
+
+        let line = encode_event_json_line(&event, &symbols).unwrap();
+        assert!(line.contains("\"type\":\"trade\""));
+        let decoded = decode_event_json_line(&line, &mut symbols).unwrap();
+        assert_eq!(decoded, event);
+    }
+
+    #[test]
+    fn round_trip_csv_snapshot_and_deltas() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("ETH-USD").unwrap();
+        let events = vec![
+            MarketEvent::L2Snapshot {
+                ts_ns: 7,
+                symbol,
+                bids: vec![
+                    (Price::new(100).unwrap(), Qty::new(2).unwrap()),
+                    (Price::new(99).unwrap(), Qty::new(1).unwrap()),
+                ],
+                asks: vec![(Price::new(101).unwrap(), Qty::new(3).unwrap())],
+            },
+            sample_event(symbol),
+            MarketEvent::L2Delta {
+                ts_ns: 43,
+                symbol,
+                updates: vec![],
+            },
+            sample_trade(symbol),
+        ];
+
+        let mut log = String::from(CSV_HEADER);
+        for event in &events {
+            let rows = encode_event_csv_line(event, &symbols).unwrap();
+            assert_eq!(decode_event_csv_line(&rows, &mut symbols).unwrap(), *event);
+            log.push('\n');
+            log.push_str(&rows);
+        }
+        assert!(log.contains("snapshot,7,ETH-USD,bid,100,2,2"));
+        assert!(log.contains("snapshot,7,ETH-USD,ask,101,3,0"));
+
+        let mut decoder = CsvEventDecoder::new();
+        let mut decoded = Vec::new();
+        for line in log.lines() {
+            if let Some(event) = decoder.push_line(line, &mut symbols).unwrap() {
+                decoded.push(event);
+            }
+        }
+        assert!(decoder.is_idle());
+        assert_eq!(decoded, events);
+    }
+
+    #[test]
+    fn csv_rejects_broken_grouping() {
+        let mut symbols = SymbolTable::new();
+        assert!(decode_event_csv_line("snapshot,1,BTC-USD,bid,100,1,1", &mut symbols).is_err());
+        assert!(decode_event_csv_line(
+            "snapshot,1,BTC-USD,bid,100,1,1\ndelta,1,BTC-USD,ask,101,1,0",
+            &mut symbols
+        )
+        .is_err());
+        assert!(decode_event_csv_line("trade,1,BTC-USD,,,,0", &mut symbols).is_err());
+        assert!(decode_event_csv_line("delta,1,BTC-USD,up,100,1,0", &mut symbols).is_err());
+    }
+
     #[test]
     fn invalid_line_returns_error() {
         let mut symbols = SymbolTable::new();
@@ -444,6 +1640,18 @@ mod tests {
         assert_eq!(decoded, event);
     }
 
+    #[cfg(feature = "bin")]
+    #[test]
+    fn round_trip_bin_record_trade() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("SOL-USD").unwrap();
+        let event = sample_trade(symbol);
+
+        let record = encode_event_bin_record(&event, &symbols).unwrap();
+        let decoded = decode_event_bin_record(&record, &mut symbols).unwrap();
+        assert_eq!(decoded, event);
+    }
+
     #[cfg(feature = "bin")]
     #[test]
     fn bin_record_crc_mismatch_is_rejected() {
@@ -458,4 +1666,167 @@ mod tests {
         let err = decode_event_bin_record(&record, &mut symbols).unwrap_err();
         assert!(matches!(err, CodecError::BinaryChecksumMismatch { .. }));
     }
+
+    #[cfg(feature = "bin")]
+    #[test]
+    fn file_header_lets_records_carry_symbol_ids() {
+        let mut writer_symbols = SymbolTable::new();
+        let btc = writer_symbols.try_intern("BTC-USD").unwrap();
+        let eth = writer_symbols.try_intern("ETH-USD").unwrap();
+        let header = encode_bin_file_header(&writer_symbols).unwrap();
+        assert_eq!(&header[..4], &BIN_FILE_MAGIC);
+        let frame = decode_bin_file_frame(&header).unwrap();
+        assert_eq!(frame.payload_len, header.len() - BIN_RECORD_HEADER_LEN);
+
+        let mut symbols = SymbolTable::try_from_symbols(["BTC-USD"]).unwrap();
+        decode_bin_file_header(&header, &mut symbols).unwrap();
+        assert_eq!(symbols.try_resolve(eth), Some("ETH-USD"));
+
+        for event in [sample_event(eth), sample_trade(btc)] {
+            let record = encode_event_bin_id_record(&event).unwrap();
+            let with_text = encode_event_bin_record(&event, &writer_symbols).unwrap();
+            assert!(record.len() < with_text.len());
+            let header = decode_event_bin_header(&record).unwrap();
+            assert_eq!(header.version, BIN_RECORD_VERSION_SYMBOL_IDS);
+            assert_eq!(
+                decode_event_bin_id_record(&record, &symbols).unwrap(),
+                event
+            );
+        }
+
+        let unknown = encode_event_bin_id_record(&sample_event(SymbolId::from_u32(7))).unwrap();
+        assert!(matches!(
+            decode_event_bin_id_record(&unknown, &symbols),
+            Err(CodecError::UnknownSymbolId(7))
+        ));
+
+        let mut conflicting = SymbolTable::try_from_symbols(["ETH-USD"]).unwrap();
+        assert!(matches!(
+            decode_bin_file_header(&header, &mut conflicting),
+            Err(CodecError::SymbolIdConflict {
+                expected: 0,
+                actual: 1,
+                ..
+            })
+        ));
+    }
+
+    #[cfg(feature = "bin")]
+    #[test]
+    fn versioned_decode_dispatches_on_record_version() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("BTC-USD").unwrap();
+        let event = sample_event(symbol);
+
+        let v1 = encode_event_bin_record(&event, &symbols).unwrap();
+        assert_eq!(
+            decode_event_bin_record_versioned(&v1, &mut symbols).unwrap(),
+            event
+        );
+        let v2 = encode_event_bin_id_record(&event).unwrap();
+        assert_eq!(
+            decode_event_bin_record_versioned(&v2, &mut symbols).unwrap(),
+            event
+        );
+        assert!(matches!(
+            decode_event_bin_record(&v2, &mut symbols),
+            Err(CodecError::BinaryVersionUnsupported {
+                got: BIN_RECORD_VERSION_SYMBOL_IDS,
+                max_supported: BIN_RECORD_VERSION,
+            })
+        ));
+
+        let mut file = Vec::new();
+        encode_events_bin_with([&event], &symbols, BinEncoding::DeltaTimestamps, &mut file)
+            .unwrap();
+        let header_len = BIN_RECORD_HEADER_LEN + decode_bin_file_frame(&file).unwrap().payload_len;
+        let v3 = &file[header_len..];
+        assert!(matches!(
+            decode_event_bin_record_versioned(v3, &mut symbols),
+            Err(CodecError::BinaryMissingTimestampBase)
+        ));
+        let mut last_ts_ns = decode_bin_file_header(&file[..header_len], &mut symbols).unwrap();
+        assert_eq!(last_ts_ns, Some(event.ts_ns()));
+        assert_eq!(
+            decode_event_bin_record_in_stream(v3, &mut symbols, &mut last_ts_ns).unwrap(),
+            event
+        );
+
+        let mut future = v1.clone();
+        future[4] = BIN_RECORD_VERSION_MAX + 1;
+        assert!(matches!(
+            decode_event_bin_record_versioned(&future, &mut symbols),
+            Err(CodecError::BinaryVersionUnsupported {
+                got,
+                max_supported: BIN_RECORD_VERSION_MAX,
+            }) if got == BIN_RECORD_VERSION_MAX + 1
+        ));
+    }
+
+    #[cfg(all(feature = "bin", feature = "msgpack"))]
+    #[test]
+    fn msgpack_round_trip_matches_bin_path() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("BTC-USD").unwrap();
+        let events = [
+            sample_event(symbol),
+            sample_trade(symbol),
+            MarketEvent::L2Snapshot {
+                ts_ns: 3,
+                symbol,
+                bids: vec![(Price::new(100).unwrap(), Qty::new(2).unwrap())],
+                asks: vec![],
+            },
+        ];
+
+        for event in &events {
+            let record = encode_event_msgpack(event, &symbols).unwrap();
+            assert_eq!(&record[..4], &MSGPACK_RECORD_MAGIC);
+            let header = decode_event_msgpack_header(&record).unwrap();
+            assert_eq!(header.payload_len, record.len() - BIN_RECORD_HEADER_LEN);
+
+            let via_msgpack = decode_event_msgpack(&record, &mut symbols).unwrap();
+            let bin = encode_event_bin_record(event, &symbols).unwrap();
+            let via_bin = decode_event_bin_record(&bin, &mut symbols).unwrap();
+            assert_eq!(via_msgpack, via_bin);
+            assert_eq!(via_msgpack, *event);
+        }
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn msgpack_detects_corruption() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("BTC-USD").unwrap();
+        let mut record = encode_event_msgpack(&sample_event(symbol), &symbols).unwrap();
+        let last = record.len() - 1;
+        record[last] ^= 0xFF;
+        assert!(matches!(
+            decode_event_msgpack(&record, &mut symbols),
+            Err(CodecError::BinaryChecksumMismatch { .. })
+        ));
+    }
+
+    #[cfg(feature = "bin")]
+    #[test]
+    fn batch_bin_encoding_matches_per_record_framing() {
+        let mut symbols = SymbolTable::new();
+        let symbol = symbols.try_intern("BTC-USD").unwrap();
+        let events = [sample_event(symbol), sample_trade(symbol)];
+
+        let mut expected = Vec::new();
+        for event in &events {
+            expected.extend_from_slice(&encode_event_bin_record(event, &symbols).unwrap());
+        }
+
+        let mut out = b"prefix".to_vec();
+        let written = encode_events_bin(&events, &symbols, &mut out).unwrap();
+        assert_eq!(written, expected.len());
+        assert_eq!(&out[6..], expected.as_slice());
+
+        let unknown = sample_event(SymbolId::from_u32(99));
+        let err = encode_events_bin([&events[0], &unknown], &symbols, &mut out);
+        assert!(matches!(err, Err(CodecError::UnknownSymbolId(99))));
+        assert_eq!(out.len(), 6 + expected.len());
+    }
 }
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
@@ -16,6 +17,14 @@ pub enum CoreError {
     InvalidPrice(i64),
     #[error("qty must be non-negative, got {0}")]
     InvalidQty(i64),
+    #[error("tick size must be finite and positive, got {0}")]
+    InvalidTickSize(String),
+    #[error("price not representable in ticks: {0}")]
+    UnrepresentablePrice(String),
+    #[error("symbol table full: limit of {max} symbols reached")]
+    SymbolTableFull { max: usize },
+    #[error("price offset overflows: {ticks} + {delta_ticks}")]
+    PriceOverflow { ticks: i64, delta_ticks: i64 },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -55,6 +64,11 @@ impl FromStr for Side {
     }
 }
 
+/// Dense index into the `SymbolTable` that issued it.
+///
+/// Ids are only meaningful relative to that table: each table assigns ids from 0 in
+/// first-seen order and never reuses or renumbers them. There is no process-wide
+/// interner, so separate tables (e.g. per test or per backtest) never share ids.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct SymbolId(u32);
@@ -69,10 +83,40 @@ impl SymbolId {
     }
 }
 
+/// Case folding applied to symbol text before interning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NormPolicy {
+    /// Trim only; `btc-usd` and `BTC-USD` are distinct symbols.
+    #[default]
+    TrimOnly,
+    UpperCase,
+    LowerCase,
+}
+
+impl NormPolicy {
+    /// Trims, then folds case; the result is what gets interned and resolved.
+    pub fn apply(self, value: &str) -> Cow<'_, str> {
+        let trimmed = value.trim();
+        match self {
+            NormPolicy::TrimOnly => Cow::Borrowed(trimmed),
+            NormPolicy::UpperCase if !trimmed.chars().any(char::is_lowercase) => {
+                Cow::Borrowed(trimmed)
+            }
+            NormPolicy::LowerCase if !trimmed.chars().any(char::is_uppercase) => {
+                Cow::Borrowed(trimmed)
+            }
+            NormPolicy::UpperCase => Cow::Owned(trimmed.to_uppercase()),
+            NormPolicy::LowerCase => Cow::Owned(trimmed.to_lowercase()),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct SymbolTable {
     by_text: HashMap<Arc<str>, SymbolId>,
     by_id: Vec<Arc<str>>,
+    max_symbols: Option<usize>,
+    norm_policy: NormPolicy,
 }
 
 impl SymbolTable {
@@ -84,9 +128,24 @@ impl SymbolTable {
         Self {
             by_text: HashMap::with_capacity(symbol_capacity),
             by_id: Vec::with_capacity(symbol_capacity),
+            max_symbols: None,
+            norm_policy: NormPolicy::default(),
         }
     }
 
+    /// Caps distinct symbols accepted by `try_intern`, bounding memory on malformed feeds.
+    pub fn with_max_symbols(mut self, max_symbols: usize) -> Self {
+        self.max_symbols = Some(max_symbols);
+        self
+    }
+
+    /// Folds case on every intern so differently-cased feeds share one `SymbolId`.
+    /// Normalization runs before the empty check in `try_intern`.
+    pub fn with_norm_policy(mut self, norm_policy: NormPolicy) -> Self {
+        self.norm_policy = norm_policy;
+        self
+    }
+
     pub fn try_from_symbols<I, S>(symbols: I) -> Result<Self, CoreError>
     where
         I: IntoIterator<Item = S>,
@@ -112,13 +171,14 @@ impl SymbolTable {
         self.by_id.reserve(additional);
     }
 
+    /// Infallible interning; does not enforce `with_max_symbols` (use `try_intern`).
     pub fn intern(&mut self, value: &str) -> SymbolId {
-        let trimmed = value.trim();
-        if let Some(symbol_id) = self.by_text.get(trimmed).copied() {
+        let normalized = self.norm_policy.apply(value);
+        if let Some(symbol_id) = self.by_text.get(normalized.as_ref()).copied() {
             return symbol_id;
         }
 
-        let interned: Arc<str> = Arc::from(trimmed);
+        let interned: Arc<str> = Arc::from(normalized.as_ref());
         let symbol_id = SymbolId(self.by_id.len() as u32);
         self.by_text.insert(Arc::clone(&interned), symbol_id);
         self.by_id.push(interned);
@@ -126,11 +186,18 @@ impl SymbolTable {
     }
 
     pub fn try_intern(&mut self, value: &str) -> Result<SymbolId, CoreError> {
-        let trimmed = value.trim();
-        if trimmed.is_empty() {
+        let normalized = self.norm_policy.apply(value);
+        if normalized.is_empty() {
             return Err(CoreError::InvalidSymbol(value.to_string()));
         }
-        Ok(self.intern(trimmed))
+        if let Some(symbol_id) = self.by_text.get(normalized.as_ref()).copied() {
+            return Ok(symbol_id);
+        }
+        let max = self.max_symbols.unwrap_or(u32::MAX as usize);
+        if self.by_id.len() >= max {
+            return Err(CoreError::SymbolTableFull { max });
+        }
+        Ok(self.intern(value))
     }
 
     pub fn try_resolve(&self, id: SymbolId) -> Option<&str> {
@@ -159,13 +226,90 @@ impl Price {
     pub fn ticks(self) -> i64 {
         self.0
     }
+
+    /// Shifts the price by `delta_ticks`, rejecting overflow and negative results.
+    pub fn offset(&self, delta_ticks: i64) -> Result<Price, CoreError> {
+        let ticks = self
+            .0
+            .checked_add(delta_ticks)
+            .ok_or(CoreError::PriceOverflow {
+                ticks: self.0,
+                delta_ticks,
+            })?;
+        Price::new(ticks)
+    }
+
+    /// Like [`Price::offset`], but returns `None` instead of an error.
+    pub fn checked_add_ticks(&self, delta_ticks: i64) -> Option<Price> {
+        self.offset(delta_ticks).ok()
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TickRounding {
+    #[default]
+    Nearest,
+    Down,
+    Up,
+}
+
+/// Converts between integer ticks and human prices for one instrument.
+/// `Price` stays in raw ticks; scaling only happens at the edges (reporting, input parsing).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickScale {
+    tick_size: f64,
+    rounding: TickRounding,
+}
+
+impl TickScale {
+    pub fn new(tick_size: f64, rounding: TickRounding) -> Result<Self, CoreError> {
+        if !tick_size.is_finite() || tick_size <= 0.0 {
+            return Err(CoreError::InvalidTickSize(tick_size.to_string()));
+        }
+        Ok(Self {
+            tick_size,
+            rounding,
+        })
+    }
+
+    pub fn tick_size(&self) -> f64 {
+        self.tick_size
+    }
+
+    pub fn rounding(&self) -> TickRounding {
+        self.rounding
+    }
+
+    pub fn ticks_to_f64(&self, price: Price) -> f64 {
+        price.ticks() as f64 * self.tick_size
+    }
+
+    pub fn f64_to_ticks(&self, value: f64) -> Result<Price, CoreError> {
+        if !value.is_finite() {
+            return Err(CoreError::UnrepresentablePrice(value.to_string()));
+        }
+        let raw = value / self.tick_size;
+        let ticks = match self.rounding {
+            TickRounding::Nearest => raw.round(),
+            TickRounding::Down => raw.floor(),
+            TickRounding::Up => raw.ceil(),
+        };
+        if ticks < i64::MIN as f64 || ticks >= i64::MAX as f64 {
+            return Err(CoreError::UnrepresentablePrice(value.to_string()));
+        }
+        Price::new(ticks as i64)
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 #[serde(transparent)]
 pub struct Qty(i64);
 
 impl Qty {
+    pub const ZERO: Qty = Qty(0);
+
     /// Integer lots avoid floating-point rounding for size updates.
     pub fn new(lots: i64) -> Result<Self, CoreError> {
         if lots < 0 {
@@ -182,6 +326,23 @@ impl Qty {
     pub fn is_zero(self) -> bool {
         self.0 == 0
     }
+
+    /// Returns `None` on overflow.
+    pub fn checked_add(self, other: Qty) -> Option<Qty> {
+        self.0.checked_add(other.0).map(Qty)
+    }
+
+    /// Returns `None` when the result would be negative.
+    pub fn checked_sub(self, other: Qty) -> Option<Qty> {
+        self.0
+            .checked_sub(other.0)
+            .and_then(|lots| Qty::new(lots).ok())
+    }
+
+    /// Subtracts, clamping at zero.
+    pub fn saturating_sub(self, other: Qty) -> Qty {
+        self.checked_sub(other).unwrap_or(Qty::ZERO)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -211,6 +372,39 @@ pub enum MarketEvent {
         bids: Vec<(Price, Qty)>,
         asks: Vec<(Price, Qty)>,
     },
+    /// Executed trade print; informational only, it does not modify L2 levels.
+    Trade {
+        ts_ns: u64,
+        symbol: SymbolId,
+        price: Price,
+        qty: Qty,
+        aggressor_side: Side,
+    },
+}
+
+impl MarketEvent {
+    pub fn ts_ns(&self) -> u64 {
+        match self {
+            MarketEvent::L2Delta { ts_ns, .. }
+            | MarketEvent::L2Snapshot { ts_ns, .. }
+            | MarketEvent::Trade { ts_ns, .. } => *ts_ns,
+        }
+    }
+
+    pub fn symbol(&self) -> SymbolId {
+        match self {
+            MarketEvent::L2Delta { symbol, .. }
+            | MarketEvent::L2Snapshot { symbol, .. }
+            | MarketEvent::Trade { symbol, .. } => *symbol,
+        }
+    }
+}
+
+/// A market event with the feed's sequence number, when the feed carries one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencedEvent {
+    pub seq: Option<u64>,
+    pub event: MarketEvent,
 }
 
 #[cfg(test)]
@@ -267,6 +461,55 @@ mod tests {
         assert_eq!(table.resolve(SymbolId::from_u32(1)), "BTC-USD");
     }
 
+    #[test]
+    fn symbol_tables_assign_ids_independently() {
+        let mut first = SymbolTable::new();
+        let mut second = SymbolTable::new();
+        first.try_intern("BTC-USD").unwrap();
+
+        assert_eq!(second.try_intern("ETH-USD"), Ok(SymbolId::from_u32(0)));
+        assert_eq!(first.try_intern("ETH-USD"), Ok(SymbolId::from_u32(1)));
+        assert_eq!(second.try_resolve(SymbolId::from_u32(1)), None);
+    }
+
+    #[test]
+    fn norm_policy_maps_casings_to_one_id() {
+        let mut table = SymbolTable::new().with_norm_policy(NormPolicy::UpperCase);
+        let upper = table.try_intern("BTC-USD").unwrap();
+        let lower = table.try_intern(" btc-usd ").unwrap();
+        assert_eq!(upper, lower);
+        assert_eq!(table.resolve(lower), "BTC-USD");
+        assert_eq!(table.len(), 1);
+
+        let mut lower_table = SymbolTable::new().with_norm_policy(NormPolicy::LowerCase);
+        assert_eq!(
+            lower_table.try_intern("Eth-Usd").unwrap(),
+            lower_table.intern("ETH-usd")
+        );
+        assert_eq!(lower_table.resolve(SymbolId::from_u32(0)), "eth-usd");
+
+        let mut plain = SymbolTable::new();
+        assert_ne!(plain.try_intern("BTC-USD"), plain.try_intern("btc-usd"));
+    }
+
+    #[test]
+    fn symbol_table_rejects_new_symbols_past_max() {
+        let mut table = SymbolTable::new().with_max_symbols(2);
+        let btc = table.try_intern("BTC-USD").unwrap();
+        table.try_intern("ETH-USD").unwrap();
+
+        assert_eq!(
+            table.try_intern("SOL-USD"),
+            Err(CoreError::SymbolTableFull { max: 2 })
+        );
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.try_intern("BTC-USD"), Ok(btc));
+        assert_eq!(
+            SymbolTable::new().with_max_symbols(0).try_intern("BTC-USD"),
+            Err(CoreError::SymbolTableFull { max: 0 })
+        );
+    }
+
     #[test]
     fn symbol_table_reserve_and_is_empty() {
         let mut table = SymbolTable::with_capacity(4);
@@ -277,9 +520,68 @@ mod tests {
         assert!(!table.is_empty());
     }
 
+    #[test]
+    fn tick_scale_converts_with_rounding_mode() {
+        let nearest = TickScale::new(0.01, TickRounding::Nearest).unwrap();
+        assert_eq!(
+            nearest.f64_to_ticks(101.234).unwrap(),
+            Price::new(10123).unwrap()
+        );
+        assert!((nearest.ticks_to_f64(Price::new(10123).unwrap()) - 101.23).abs() < 1e-9);
+
+        let down = TickScale::new(0.5, TickRounding::Down).unwrap();
+        assert_eq!(down.f64_to_ticks(10.9).unwrap(), Price::new(21).unwrap());
+        let up = TickScale::new(0.5, TickRounding::Up).unwrap();
+        assert_eq!(up.f64_to_ticks(10.1).unwrap(), Price::new(21).unwrap());
+
+        assert!(TickScale::new(0.0, TickRounding::Nearest).is_err());
+        assert!(TickScale::new(f64::NAN, TickRounding::Nearest).is_err());
+        assert!(nearest.f64_to_ticks(-1.0).is_err());
+        assert!(nearest.f64_to_ticks(f64::INFINITY).is_err());
+    }
+
     #[test]
     fn price_and_qty_validate_non_negative() {
         assert!(Price::new(-1).is_err());
         assert!(Qty::new(-10).is_err());
     }
+
+    #[test]
+    fn qty_checked_ops_keep_lots_non_negative() {
+        let zero = Qty::ZERO;
+        let one = Qty::new(1).unwrap();
+        let max = Qty::new(i64::MAX).unwrap();
+
+        assert_eq!(zero.checked_add(one), Some(one));
+        assert_eq!(max.checked_add(zero), Some(max));
+        assert_eq!(max.checked_add(one), None);
+
+        assert_eq!(one.checked_sub(one), Some(zero));
+        assert_eq!(zero.checked_sub(one), None);
+        assert_eq!(max.checked_sub(max), Some(zero));
+
+        assert_eq!(zero.saturating_sub(one), zero);
+        assert_eq!(one.saturating_sub(max), zero);
+        assert_eq!(max.saturating_sub(one), Qty::new(i64::MAX - 1).unwrap());
+    }
+
+    #[test]
+    fn price_offset_rejects_overflow_and_underflow() {
+        let max = Price::new(i64::MAX).unwrap();
+        assert_eq!(
+            max.offset(1),
+            Err(CoreError::PriceOverflow {
+                ticks: i64::MAX,
+                delta_ticks: 1
+            })
+        );
+        assert_eq!(max.offset(-1), Ok(Price::new(i64::MAX - 1).unwrap()));
+        assert_eq!(max.checked_add_ticks(i64::MAX), None);
+
+        let zero = Price::new(0).unwrap();
+        assert_eq!(zero.offset(0), Ok(zero));
+        assert_eq!(zero.offset(-1), Err(CoreError::InvalidPrice(-1)));
+        assert_eq!(zero.checked_add_ticks(i64::MIN), None);
+        assert_eq!(Price::new(100).unwrap().checked_add_ticks(-100), Some(zero));
+    }
 }
@@ -1,22 +1,349 @@
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
 use std::rc::Rc;
 use std::time::Instant;
 
-use lob_core::{MarketEvent, SymbolId};
+use lob_core::{MarketEvent, Price, Qty, Side, SymbolId};
 use metrics::LatencyStats;
 use oms::Oms;
-use orderbook::OrderBook;
+use orderbook::{MultiBook, OrderBook};
 use portfolio::Portfolio;
 use risk::{RiskAction, RiskEngine};
-use strategy_api::{ContextSnapshot, Strategy};
-use trading_types::{ExecutionReport, Intent};
+use serde::{Deserialize, Serialize};
+use strategy_api::{ContextSnapshot, Strategy, TimerId};
+use trading_types::{ExecutionReport, Intent, OrderStatus, PegRef, TimeInForce};
 use venue::ExecutionVenue;
 
-const MAX_INTENT_STEPS: usize = 1024;
+const MAX_INTENT_STEPS_DEFAULT: usize = 1024;
+const TRADE_VOLUME_WINDOW_NS_DEFAULT: u64 = 1_000_000_000;
+const MAX_TIMER_TICKS_DEFAULT: usize = 1024;
+
+/// Engine settings fixed at construction time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EngineConfig {
+    /// Upper bound on intents processed per event/timer, guarding against churn loops.
+    pub max_intent_steps: usize,
+    /// When false, `on_market_event` skips wall-clock timing entirely.
+    pub record_latency: bool,
+    /// Length of the rolling window behind `ContextSnapshot::recent_trade_volume`.
+    /// Trades with `ts_ns` in `(now - window, now]` are counted.
+    pub trade_volume_window_ns: u64,
+    /// Spacing of the timer boundaries fired by `advance_to`, as `TimerId::DEFAULT`; `None`
+    /// disables scheduled timers. `EngineCore::with_timer_schedule` overrides and clears it.
+    pub timer_interval_ns: Option<u64>,
+    /// Upper bound on timer ticks fired by a single `advance_to` call, counted across all
+    /// scheduled timers (one per boundary of any timer, not per distinct timestamp).
+    /// Past the cap the remaining boundaries are skipped; see `timer_overflow_count`.
+    pub max_timer_ticks: usize,
+    /// Drops a `Cancel`/`Replace` when the next queued intent is the same kind for the
+    /// same order, so only the last of a burst reaches the venue.
+    pub coalesce_intents: bool,
+    /// Levels per side in `ContextSnapshot::bid_depth`/`ask_depth` (fewer if the book is
+    /// thinner); 0 leaves both empty.
+    pub context_depth: usize,
+}
+
+/// An intent drain cut short by `EngineConfig::max_intent_steps`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntentOverflow {
+    pub ts_ns: u64,
+    pub symbol: SymbolId,
+    /// Intents left in the queue, including the one that hit the cap.
+    pub dropped_intents: usize,
+}
+
+/// Callback installed with `EngineCore::with_overflow_handler`.
+pub type OverflowHandler = Box<dyn FnMut(&IntentOverflow)>;
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            max_intent_steps: MAX_INTENT_STEPS_DEFAULT,
+            record_latency: true,
+            trade_volume_window_ns: TRADE_VOLUME_WINDOW_NS_DEFAULT,
+            timer_interval_ns: None,
+            max_timer_ticks: MAX_TIMER_TICKS_DEFAULT,
+            coalesce_intents: false,
+            context_depth: 1,
+        }
+    }
+}
+
+/// Timers fired by `advance_to`, each on its own interval from a shared anchor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimerSchedule {
+    timers: Vec<(TimerId, u64)>,
+}
+
+impl TimerSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a timer firing every `interval_ns` (at least 1); ticks due at the same
+    /// time fire in the order timers were added.
+    pub fn with_timer(mut self, id: TimerId, interval_ns: u64) -> Self {
+        self.timers.push((id, interval_ns.max(1)));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct TradeVolumeWindow {
+    trades: VecDeque<(u64, i64)>,
+    total_lots: i64,
+}
+
+impl TradeVolumeWindow {
+    fn record(&mut self, ts_ns: u64, qty: Qty) {
+        self.trades.push_back((ts_ns, qty.lots()));
+        self.total_lots = self.total_lots.saturating_add(qty.lots());
+    }
+
+    fn evict(&mut self, now_ns: u64, window_ns: u64) {
+        while let Some(&(ts_ns, lots)) = self.trades.front() {
+            if now_ns.saturating_sub(ts_ns) < window_ns {
+                break;
+            }
+            self.trades.pop_front();
+            self.total_lots = self.total_lots.saturating_sub(lots);
+        }
+    }
+
+    fn total(&self) -> Qty {
+        match Qty::new(self.total_lots.max(0)) {
+            Ok(qty) => qty,
+            Err(_) => unreachable!("non-negative qty must be valid"),
+        }
+    }
+}
+
+/// Audit hook receiving every step of the engine pipeline in processing order.
+/// All methods default to no-ops so recorders can pick the callbacks they need.
+pub trait EngineRecorder {
+    fn on_market_event(&mut self, _ts_ns: u64, _event: &MarketEvent) {}
+
+    fn on_intent(&mut self, _ts_ns: u64, _intent: &Intent) {}
+
+    fn on_risk_decision(&mut self, _ts_ns: u64, _decision: &RiskAction) {}
+
+    fn on_order_request(&mut self, _ts_ns: u64, _request: &oms::OrderRequest) {}
+
+    fn on_execution_report(&mut self, _ts_ns: u64, _report: &ExecutionReport) {}
+
+    /// Pushes buffered output to its destination and reports any error seen so far;
+    /// called by `EngineCore::finish_recording`.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum AuditRecord<'a> {
+    MarketEvent {
+        ts_ns: u64,
+        event: &'a MarketEvent,
+    },
+    Intent {
+        ts_ns: u64,
+        intent: &'a Intent,
+    },
+    RiskDecision {
+        ts_ns: u64,
+        decision: &'a RiskAction,
+    },
+    OrderRequest {
+        ts_ns: u64,
+        request: &'a oms::OrderRequest,
+    },
+    ExecutionReport {
+        ts_ns: u64,
+        report: &'a ExecutionReport,
+    },
+}
+
+/// Writes one JSON object per recorder callback, tagged by `kind`.
+pub struct JsonlRecorder<W: Write> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> JsonlRecorder<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    /// Flushes and returns the writer, or the first write error seen while recording.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write(&mut self, record: &AuditRecord<'_>) {
+        if self.error.is_some() {
+            return;
+        }
+        let result = serde_json::to_writer(&mut self.writer, record)
+            .map_err(io::Error::from)
+            .and_then(|()| self.writer.write_all(b"\n"));
+        if let Err(err) = result {
+            self.error = Some(err);
+        }
+    }
+}
+
+impl<W: Write> EngineRecorder for JsonlRecorder<W> {
+    fn on_market_event(&mut self, ts_ns: u64, event: &MarketEvent) {
+        self.write(&AuditRecord::MarketEvent { ts_ns, event });
+    }
 
-pub struct Engine {
-    book: Rc<RefCell<OrderBook>>,
+    fn on_intent(&mut self, ts_ns: u64, intent: &Intent) {
+        self.write(&AuditRecord::Intent { ts_ns, intent });
+    }
+
+    fn on_risk_decision(&mut self, ts_ns: u64, decision: &RiskAction) {
+        self.write(&AuditRecord::RiskDecision { ts_ns, decision });
+    }
+
+    fn on_order_request(&mut self, ts_ns: u64, request: &oms::OrderRequest) {
+        self.write(&AuditRecord::OrderRequest { ts_ns, request });
+    }
+
+    fn on_execution_report(&mut self, ts_ns: u64, report: &ExecutionReport) {
+        self.write(&AuditRecord::ExecutionReport { ts_ns, report });
+    }
+
+    /// The first write error stays recorded, so later records are never written after a
+    /// gap and every flush keeps reporting it.
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(err) = &self.error {
+            return Err(io::Error::new(err.kind(), err.to_string()));
+        }
+        self.writer.flush()
+    }
+}
+
+type TopOfBook = (Option<(Price, Qty)>, Option<(Price, Qty)>);
+
+/// Book state an engine applies market events to and builds context from.
+pub trait EngineBook {
+    /// When true, per-symbol context fields (open orders) are scoped to the event symbol.
+    const MULTI_SYMBOL: bool;
+
+    fn apply(&mut self, event: &MarketEvent) -> bool;
+
+    fn best_levels(&self, symbol: SymbolId) -> TopOfBook;
+
+    /// Up to `levels` levels on `side`, best first. Defaults to the top of book only.
+    fn depth(&self, symbol: SymbolId, side: Side, levels: usize) -> Vec<(Price, Qty)> {
+        let (best_bid, best_ask) = self.best_levels(symbol);
+        let best = match side {
+            Side::Bid => best_bid,
+            Side::Ask => best_ask,
+        };
+        best.into_iter().take(levels).collect()
+    }
+
+    /// Owned copy of the book captured by `EngineCore::save_state`.
+    type State: Clone + Serialize + for<'de> Deserialize<'de>;
+
+    fn save(&self) -> Self::State;
+
+    /// Replaces the book contents in place, so handles shared with a venue see the change.
+    fn restore(&mut self, state: Self::State);
+}
+
+impl EngineBook for Rc<RefCell<OrderBook>> {
+    const MULTI_SYMBOL: bool = false;
+
+    fn apply(&mut self, event: &MarketEvent) -> bool {
+        self.borrow_mut().apply(event)
+    }
+
+    fn best_levels(&self, _symbol: SymbolId) -> TopOfBook {
+        let book = self.borrow();
+        (book.best_bid(), book.best_ask())
+    }
+
+    fn depth(&self, _symbol: SymbolId, side: Side, levels: usize) -> Vec<(Price, Qty)> {
+        self.borrow().depth(side, levels)
+    }
+
+    type State = OrderBook;
+
+    fn save(&self) -> OrderBook {
+        self.borrow().clone()
+    }
+
+    fn restore(&mut self, state: OrderBook) {
+        *self.borrow_mut() = state;
+    }
+}
+
+impl EngineBook for Rc<RefCell<MultiBook>> {
+    const MULTI_SYMBOL: bool = true;
+
+    fn apply(&mut self, event: &MarketEvent) -> bool {
+        self.borrow_mut().apply(event)
+    }
+
+    fn best_levels(&self, symbol: SymbolId) -> TopOfBook {
+        let books = self.borrow();
+        books
+            .book(symbol)
+            .map_or((None, None), |book| (book.best_bid(), book.best_ask()))
+    }
+
+    fn depth(&self, symbol: SymbolId, side: Side, levels: usize) -> Vec<(Price, Qty)> {
+        self.borrow()
+            .book(symbol)
+            .map_or_else(Vec::new, |book| book.depth(side, levels))
+    }
+
+    type State = MultiBook;
+
+    fn save(&self) -> MultiBook {
+        self.borrow().clone()
+    }
+
+    fn restore(&mut self, state: MultiBook) {
+        *self.borrow_mut() = state;
+    }
+}
+
+/// Serializable engine state for warm-up-once, fork-many backtests.
+///
+/// Covers the book, portfolio, OMS (order states and next client order id), trade
+/// volume windows and timer schedule. Strategy and venue are not captured: restore
+/// into an engine built with fresh instances, and latency stats start empty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineState<S> {
+    book: S,
+    portfolio: Portfolio,
+    oms: Oms,
+    trade_volume: HashMap<SymbolId, TradeVolumeWindow>,
+    /// Last boundary fired per scheduled timer, in schedule order.
+    timer_ticks: Vec<Option<u64>>,
+}
+
+pub struct EngineCore<B: EngineBook> {
+    book: B,
     portfolio: Portfolio,
     oms: Oms,
     risk: RiskEngine,
@@ -26,8 +353,23 @@ pub struct Engine {
     intent_queue: VecDeque<Intent>,
     intent_buffer: Vec<Intent>,
     report_buffer: Vec<ExecutionReport>,
+    config: EngineConfig,
+    trade_volume: HashMap<SymbolId, TradeVolumeWindow>,
+    timer_schedule: TimerSchedule,
+    timer_ticks: Vec<Option<u64>>,
+    recorder: Option<Box<dyn EngineRecorder>>,
+    overflow_handler: Option<OverflowHandler>,
+    intent_overflows: u64,
+    timer_overflows: u64,
+    dropped_pegs: u64,
 }
 
+/// Single-book engine; context always reads the one shared `OrderBook`.
+pub type Engine = EngineCore<Rc<RefCell<OrderBook>>>;
+
+/// Basket engine over a shared `MultiBook`; context reads the event symbol's book.
+pub type MultiEngine = EngineCore<Rc<RefCell<MultiBook>>>;
+
 impl Engine {
     pub fn new(
         book: OrderBook,
@@ -55,6 +397,95 @@ impl Engine {
         strategy: Box<dyn Strategy>,
         venue: Box<dyn ExecutionVenue>,
     ) -> Self {
+        Self::with_config(
+            book,
+            portfolio,
+            oms,
+            risk,
+            strategy,
+            venue,
+            EngineConfig::default(),
+        )
+    }
+
+    pub fn with_config(
+        book: Rc<RefCell<OrderBook>>,
+        portfolio: Portfolio,
+        oms: Oms,
+        risk: RiskEngine,
+        strategy: Box<dyn Strategy>,
+        venue: Box<dyn ExecutionVenue>,
+        config: EngineConfig,
+    ) -> Self {
+        Self::from_parts(book, portfolio, oms, risk, strategy, venue, config)
+    }
+}
+
+impl MultiEngine {
+    pub fn new(
+        books: MultiBook,
+        portfolio: Portfolio,
+        oms: Oms,
+        risk: RiskEngine,
+        strategy: Box<dyn Strategy>,
+        venue: Box<dyn ExecutionVenue>,
+    ) -> Self {
+        Self::with_shared_book(
+            Rc::new(RefCell::new(books)),
+            portfolio,
+            oms,
+            risk,
+            strategy,
+            venue,
+        )
+    }
+
+    pub fn with_shared_book(
+        books: Rc<RefCell<MultiBook>>,
+        portfolio: Portfolio,
+        oms: Oms,
+        risk: RiskEngine,
+        strategy: Box<dyn Strategy>,
+        venue: Box<dyn ExecutionVenue>,
+    ) -> Self {
+        Self::with_config(
+            books,
+            portfolio,
+            oms,
+            risk,
+            strategy,
+            venue,
+            EngineConfig::default(),
+        )
+    }
+
+    pub fn with_config(
+        books: Rc<RefCell<MultiBook>>,
+        portfolio: Portfolio,
+        oms: Oms,
+        risk: RiskEngine,
+        strategy: Box<dyn Strategy>,
+        venue: Box<dyn ExecutionVenue>,
+        config: EngineConfig,
+    ) -> Self {
+        Self::from_parts(books, portfolio, oms, risk, strategy, venue, config)
+    }
+}
+
+impl<B: EngineBook> EngineCore<B> {
+    fn from_parts(
+        book: B,
+        portfolio: Portfolio,
+        oms: Oms,
+        risk: RiskEngine,
+        strategy: Box<dyn Strategy>,
+        venue: Box<dyn ExecutionVenue>,
+        config: EngineConfig,
+    ) -> Self {
+        let timer_schedule = match config.timer_interval_ns {
+            Some(interval_ns) => TimerSchedule::new().with_timer(TimerId::DEFAULT, interval_ns),
+            None => TimerSchedule::new(),
+        };
         Self {
             book,
             portfolio,
@@ -66,10 +497,59 @@ impl Engine {
             intent_queue: VecDeque::new(),
             intent_buffer: Vec::new(),
             report_buffer: Vec::new(),
+            config,
+            trade_volume: HashMap::new(),
+            timer_ticks: vec![None; timer_schedule.len()],
+            timer_schedule,
+            recorder: None,
+            overflow_handler: None,
+            intent_overflows: 0,
+            timer_overflows: 0,
+            dropped_pegs: 0,
+        }
+    }
+
+    /// Attaches an audit recorder; recording never changes engine decisions.
+    pub fn with_recorder(mut self, recorder: Box<dyn EngineRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Flushes the attached recorder, returning its first write or flush error. Call once
+    /// the run is over; a no-op without a recorder.
+    pub fn finish_recording(&mut self) -> io::Result<()> {
+        match &mut self.recorder {
+            Some(recorder) => recorder.flush(),
+            None => Ok(()),
         }
     }
 
+    /// Detaches and returns the recorder, e.g. to recover a concrete recorder's output.
+    pub fn take_recorder(&mut self) -> Option<Box<dyn EngineRecorder>> {
+        self.recorder.take()
+    }
+
+    /// Replaces the timers from `EngineConfig::timer_interval_ns`, which is cleared so
+    /// `config()` reflects that only `schedule` fires; each timer fires
+    /// `Strategy::on_timer_id` with its own id.
+    pub fn with_timer_schedule(mut self, schedule: TimerSchedule) -> Self {
+        self.config.timer_interval_ns = None;
+        self.timer_ticks = vec![None; schedule.len()];
+        self.timer_schedule = schedule;
+        self
+    }
+
+    /// Called each time an intent drain stops at `EngineConfig::max_intent_steps`.
+    pub fn with_overflow_handler(mut self, handler: OverflowHandler) -> Self {
+        self.overflow_handler = Some(handler);
+        self
+    }
+
     pub fn on_market_event(&mut self, event: &MarketEvent) -> bool {
+        if !self.config.record_latency {
+            return self.on_market_event_deterministic(event);
+        }
+
         // Measures book apply + strategy decision + routing/venue response handling.
         let start = Instant::now();
         let applied = self.on_market_event_deterministic(event);
@@ -83,15 +563,23 @@ impl Engine {
     }
 
     pub fn on_market_event_deterministic(&mut self, event: &MarketEvent) -> bool {
-        let applied = self.book.borrow_mut().apply(event);
+        let applied = self.book.apply(event);
         if !applied {
             return false;
         }
 
-        let (ts_ns, symbol) = match event {
-            MarketEvent::L2Delta { ts_ns, symbol, .. } => (*ts_ns, *symbol),
-            MarketEvent::L2Snapshot { ts_ns, symbol, .. } => (*ts_ns, *symbol),
-        };
+        let ts_ns = event.ts_ns();
+        let symbol = event.symbol();
+        if let Some(recorder) = &mut self.recorder {
+            recorder.on_market_event(ts_ns, event);
+        }
+        if let MarketEvent::Trade { qty, .. } = event {
+            self.trade_volume
+                .entry(symbol)
+                .or_default()
+                .record(ts_ns, *qty);
+        }
+        self.evict_trade_volume(ts_ns);
 
         let mut queue = std::mem::take(&mut self.intent_queue);
         let mut intents = std::mem::take(&mut self.intent_buffer);
@@ -101,12 +589,15 @@ impl Engine {
         intents.clear();
         reports.clear();
 
+        self.venue.on_market_event(event);
         self.venue.on_book_update(&mut reports);
         self.process_reports(&mut reports, &mut queue, &mut intents);
 
         let ctx = self.build_context(ts_ns, symbol);
-        self.strategy.on_market_event(&ctx, event, &mut intents);
-        queue.extend(intents.drain(..));
+        if self.strategy.is_ready(&ctx) {
+            self.strategy.on_market_event(&ctx, event, &mut intents);
+        }
+        self.enqueue_intents(ts_ns, &mut queue, &mut intents);
         self.handle_intent_queue(ts_ns, symbol, &mut queue, &mut reports, &mut intents);
 
         self.intent_queue = queue;
@@ -115,7 +606,14 @@ impl Engine {
         true
     }
 
+    /// Cancels stale orders (see `Oms::with_max_age_ns`), then runs the strategy timer.
     pub fn on_timer(&mut self, ts_ns: u64, symbol: SymbolId) {
+        self.on_timer_id(ts_ns, symbol, TimerId::DEFAULT);
+    }
+
+    /// Like `on_timer`, telling the strategy which timer fired.
+    pub fn on_timer_id(&mut self, ts_ns: u64, symbol: SymbolId, timer: TimerId) {
+        self.evict_trade_volume(ts_ns);
         let mut queue = std::mem::take(&mut self.intent_queue);
         let mut intents = std::mem::take(&mut self.intent_buffer);
         let mut reports = std::mem::take(&mut self.report_buffer);
@@ -124,9 +622,20 @@ impl Engine {
         intents.clear();
         reports.clear();
 
+        for request in self.oms.expire_stale(ts_ns) {
+            if let Some(recorder) = &mut self.recorder {
+                recorder.on_order_request(ts_ns, &request);
+            }
+            reports.clear();
+            self.venue.submit(&request, &mut reports);
+            self.process_reports(&mut reports, &mut queue, &mut intents);
+        }
+
         let ctx = self.build_context(ts_ns, symbol);
-        self.strategy.on_timer(&ctx, &mut intents);
-        queue.extend(intents.drain(..));
+        if self.strategy.is_ready(&ctx) {
+            self.strategy.on_timer_id(timer, &ctx, &mut intents);
+        }
+        self.enqueue_intents(ts_ns, &mut queue, &mut intents);
         self.handle_intent_queue(ts_ns, symbol, &mut queue, &mut reports, &mut intents);
 
         self.intent_queue = queue;
@@ -134,6 +643,110 @@ impl Engine {
         self.report_buffer = reports;
     }
 
+    /// Closes out `symbol` at end of run: cancels its open orders, then sends an IOC market
+    /// order for the whole position, bypassing strategy and risk. Intents the strategy
+    /// emits in response are dropped. Returns the position left, nonzero when the venue
+    /// could not fill it all.
+    pub fn flatten(&mut self, ts_ns: u64, symbol: SymbolId) -> i64 {
+        let mut queue = std::mem::take(&mut self.intent_queue);
+        let mut intents = std::mem::take(&mut self.intent_buffer);
+        let mut reports = std::mem::take(&mut self.report_buffer);
+
+        let mut requests = self.oms.cancel_by_symbol(symbol, ts_ns);
+        let position_lots = self.portfolio.position_lots(symbol);
+        let qty = Qty::new(position_lots.saturating_abs()).ok();
+        if let Some(qty) = qty.filter(|qty| !qty.is_zero()) {
+            let side = if position_lots > 0 {
+                Side::Ask
+            } else {
+                Side::Bid
+            };
+            let intent = Intent::PlaceMarket {
+                symbol,
+                side,
+                qty,
+                tif: TimeInForce::Ioc,
+                tag: None,
+            };
+            if let Some(recorder) = &mut self.recorder {
+                recorder.on_intent(ts_ns, &intent);
+            }
+            requests.extend(self.oms.apply_intent(intent, ts_ns));
+        }
+        for request in requests {
+            if let Some(recorder) = &mut self.recorder {
+                recorder.on_order_request(ts_ns, &request);
+            }
+            reports.clear();
+            self.venue.submit(&request, &mut reports);
+            self.process_reports(&mut reports, &mut queue, &mut intents);
+        }
+        self.oms.expire_immediate_orders();
+
+        queue.clear();
+        self.intent_queue = queue;
+        self.intent_buffer = intents;
+        self.report_buffer = reports;
+        self.portfolio.position_lots(symbol)
+    }
+
+    /// Fires every scheduled timer at each of its boundaries in `(last boundary, ts_ns]`,
+    /// earliest first. The first call only anchors the schedule at `ts_ns`.
+    pub fn advance_to(&mut self, ts_ns: u64, symbol: SymbolId) {
+        self.advance_to_symbols(ts_ns, &[symbol]);
+    }
+
+    /// Like `advance_to`, firing each boundary once per symbol in `symbols` order.
+    pub fn advance_to_symbols(&mut self, ts_ns: u64, symbols: &[SymbolId]) {
+        if self.timer_ticks.iter().any(Option::is_none) {
+            self.timer_ticks.fill(Some(ts_ns));
+            return;
+        }
+
+        let mut ticks_processed = 0usize;
+        while let Some((index, timer, tick)) = self.next_due_timer(ts_ns) {
+            if ticks_processed >= self.config.max_timer_ticks {
+                self.timer_overflows += 1;
+                self.skip_timers_to(ts_ns);
+                break;
+            }
+            self.timer_ticks[index] = Some(tick);
+            for &symbol in symbols {
+                self.on_timer_id(tick, symbol, timer);
+            }
+            ticks_processed += 1;
+        }
+    }
+
+    /// Moves every timer to its latest boundary at or before `ts_ns` without firing it, so
+    /// a long feed gap is not replayed tick by tick on later calls.
+    fn skip_timers_to(&mut self, ts_ns: u64) {
+        for (&(_, interval_ns), last_tick) in
+            self.timer_schedule.timers.iter().zip(&mut self.timer_ticks)
+        {
+            if let Some(last) = last_tick.as_mut() {
+                let elapsed = ts_ns.saturating_sub(*last);
+                *last += elapsed - elapsed % interval_ns;
+            }
+        }
+    }
+
+    /// The scheduled timer with the earliest boundary at or before `ts_ns`, as
+    /// `(index, id, boundary)`; ties go to the timer scheduled first.
+    fn next_due_timer(&self, ts_ns: u64) -> Option<(usize, TimerId, u64)> {
+        self.timer_schedule
+            .timers
+            .iter()
+            .zip(&self.timer_ticks)
+            .enumerate()
+            .filter_map(|(index, (&(timer, interval_ns), last_tick))| {
+                let last_tick = (*last_tick)?;
+                (ts_ns.saturating_sub(last_tick) >= interval_ns)
+                    .then(|| (index, timer, last_tick.saturating_add(interval_ns)))
+            })
+            .min_by_key(|&(index, _, tick)| (tick, index))
+    }
+
     fn handle_intent_queue(
         &mut self,
         ts_ns: u64,
@@ -142,21 +755,60 @@ impl Engine {
         reports: &mut Vec<ExecutionReport>,
         intents: &mut Vec<Intent>,
     ) {
+        let max_intent_steps = self.config.max_intent_steps;
         let mut processed_steps = 0usize;
 
         while let Some(intent) = queue.pop_front() {
-            if processed_steps >= MAX_INTENT_STEPS {
-                debug_assert!(
-                    false,
-                    "intent processing exceeded MAX_INTENT_STEPS; stopping to prevent churn"
-                );
+            if self.config.coalesce_intents
+                && queue
+                    .front()
+                    .is_some_and(|next| is_superseded_by(&intent, next))
+            {
+                continue;
+            }
+            if processed_steps >= max_intent_steps {
+                self.intent_overflows += 1;
+                if let Some(handler) = &mut self.overflow_handler {
+                    handler(&IntentOverflow {
+                        ts_ns,
+                        symbol,
+                        dropped_intents: queue.len() + 1,
+                    });
+                }
                 break;
             }
             processed_steps += 1;
 
-            let intent_ctx = self.build_context(ts_ns, symbol);
-            let decision = self.risk.evaluate(&intent_ctx, &intent);
-            let intent = match decision {
+            let intent = match intent {
+                Intent::PlacePegged { symbol, .. } => {
+                    let peg_ctx = self.build_context(ts_ns, symbol);
+                    match resolve_peg(intent, &peg_ctx) {
+                        Some(intent) => intent,
+                        None => {
+                            self.dropped_pegs += 1;
+                            continue;
+                        }
+                    }
+                }
+                intent => intent,
+            };
+            // Risk sees the intent's own symbol, which a basket strategy may not share
+            // with the event being handled.
+            let intent_symbol = match &intent {
+                Intent::PlaceLimit { symbol, .. }
+                | Intent::PlaceMarket { symbol, .. }
+                | Intent::PlacePegged { symbol, .. } => *symbol,
+                Intent::Cancel { client_order_id }
+                | Intent::Replace {
+                    client_order_id, ..
+                } => self.oms.order_symbol(*client_order_id).unwrap_or(symbol),
+            };
+            let intent_ctx = self.build_context(ts_ns, intent_symbol);
+            let decision = self.risk.evaluate(&intent_ctx, &intent);
+            if let Some(recorder) = &mut self.recorder {
+                recorder.on_risk_decision(ts_ns, &decision);
+            }
+            let intent = match decision {
                 RiskAction::Allow(intent) | RiskAction::Transform(intent) => intent,
                 RiskAction::Reject { .. } => continue,
             };
@@ -164,9 +816,13 @@ impl Engine {
             let Some(request) = self.oms.apply_intent(intent, ts_ns) else {
                 continue;
             };
+            if let Some(recorder) = &mut self.recorder {
+                recorder.on_order_request(ts_ns, &request);
+            }
             reports.clear();
             self.venue.submit(&request, reports);
             self.process_reports(reports, queue, intents);
+            self.oms.expire_immediate_orders();
         }
     }
 
@@ -176,32 +832,142 @@ impl Engine {
         queue: &mut VecDeque<Intent>,
         intents: &mut Vec<Intent>,
     ) {
-        for report in reports.drain(..) {
+        for mut report in reports.drain(..) {
+            if report.tag.is_none() {
+                report.tag = self.oms.order_tag(report.client_order_id).cloned();
+            }
+            if let Some(recorder) = &mut self.recorder {
+                recorder.on_execution_report(report.ts_ns, &report);
+            }
             self.oms.on_execution_report(&report);
             self.portfolio.on_execution_report(&report);
             let report_ctx = self.build_context(report.ts_ns, report.symbol);
             intents.clear();
             self.strategy
                 .on_execution_report(&report_ctx, &report, intents);
-            queue.extend(intents.drain(..));
+            match report.status {
+                OrderStatus::Accepted => {
+                    self.strategy
+                        .on_order_accepted(&report_ctx, &report, intents);
+                }
+                OrderStatus::Rejected => {
+                    self.strategy
+                        .on_order_rejected(&report_ctx, &report, intents);
+                }
+                _ => {}
+            }
+            self.enqueue_intents(report.ts_ns, queue, intents);
+        }
+    }
+
+    fn enqueue_intents(
+        &mut self,
+        ts_ns: u64,
+        queue: &mut VecDeque<Intent>,
+        intents: &mut Vec<Intent>,
+    ) {
+        if let Some(recorder) = &mut self.recorder {
+            for intent in intents.iter() {
+                recorder.on_intent(ts_ns, intent);
+            }
+        }
+        queue.extend(intents.drain(..));
+    }
+
+    fn evict_trade_volume(&mut self, now_ns: u64) {
+        let window_ns = self.config.trade_volume_window_ns;
+        for window in self.trade_volume.values_mut() {
+            window.evict(now_ns, window_ns);
         }
     }
 
     fn build_context(&self, ts_ns: u64, symbol: SymbolId) -> ContextSnapshot {
-        let (best_bid, best_ask) = {
-            let book = self.book.borrow();
-            (book.best_bid(), book.best_ask())
-        };
+        let (best_bid, best_ask) = self.book.best_levels(symbol);
         let position_lots = self.portfolio.position_lots(symbol);
-        let open_orders = self.oms.open_orders();
-        ContextSnapshot::new(
+        let open_orders = if B::MULTI_SYMBOL {
+            self.oms.open_orders_for(symbol)
+        } else {
+            self.oms.open_orders()
+        };
+        let recent_trade_volume = self.trade_volume.get(&symbol).map_or_else(
+            || TradeVolumeWindow::default().total(),
+            TradeVolumeWindow::total,
+        );
+        let unrealized_pnl_ticks = match (best_bid, best_ask) {
+            (Some(_), Some(_)) => Some(
+                self.portfolio
+                    .mark_to_mid(symbol, best_bid, best_ask)
+                    .unwrap_or(0),
+            ),
+            _ => None,
+        };
+        let ctx = ContextSnapshot::new(
             ts_ns,
             symbol,
             best_bid,
             best_ask,
             position_lots,
             open_orders,
-        )
+        );
+        // `new` already carries the top of book, the default depth.
+        let ctx = match self.config.context_depth {
+            0 => ctx.with_depth(Vec::new(), Vec::new()),
+            1 => ctx,
+            levels => ctx.with_depth(
+                self.book.depth(symbol, Side::Bid, levels),
+                self.book.depth(symbol, Side::Ask, levels),
+            ),
+        };
+        ctx.with_recent_trade_volume(recent_trade_volume)
+            .with_open_quotes(
+                self.oms.best_open_order(symbol, Side::Bid),
+                self.oms.best_open_order(symbol, Side::Ask),
+            )
+            .with_pnl(
+                self.portfolio.realized_pnl_ticks(symbol),
+                unrealized_pnl_ticks,
+            )
+    }
+
+    pub fn save_state(&self) -> EngineState<B::State> {
+        EngineState {
+            book: self.book.save(),
+            portfolio: self.portfolio.clone(),
+            oms: self.oms.clone(),
+            trade_volume: self.trade_volume.clone(),
+            timer_ticks: self.timer_ticks.clone(),
+        }
+    }
+
+    /// Replaces engine state with `state`; strategy, venue and config are kept as built.
+    pub fn restore_state(&mut self, state: EngineState<B::State>) {
+        self.book.restore(state.book);
+        self.portfolio = state.portfolio;
+        self.oms = state.oms;
+        self.trade_volume = state.trade_volume;
+        self.timer_ticks = state.timer_ticks;
+        self.timer_ticks.resize(self.timer_schedule.len(), None);
+    }
+
+    pub fn config(&self) -> &EngineConfig {
+        &self.config
+    }
+
+    /// Number of intent drains cut short by `EngineConfig::max_intent_steps`.
+    pub fn intent_overflow_count(&self) -> u64 {
+        self.intent_overflows
+    }
+
+    /// Number of `advance_to` calls that stopped at `EngineConfig::max_timer_ticks` and
+    /// skipped the remaining boundaries.
+    pub fn timer_overflow_count(&self) -> u64 {
+        self.timer_overflows
+    }
+
+    /// Pegged intents dropped because their reference level was missing or the offset
+    /// left the valid price range.
+    pub fn dropped_peg_count(&self) -> u64 {
+        self.dropped_pegs
     }
 
     pub fn latency_stats(&self) -> &LatencyStats {
@@ -219,15 +985,70 @@ impl Engine {
     pub fn fees_paid_ticks(&self, symbol: SymbolId) -> i128 {
         self.portfolio.fees_paid_ticks(symbol)
     }
+
+    pub fn rebates_received_ticks(&self, symbol: SymbolId) -> i128 {
+        self.portfolio.rebates_received_ticks(symbol)
+    }
+}
+
+/// Turns a `PlacePegged` into the equivalent `PlaceLimit` at the context's current levels.
+fn resolve_peg(intent: Intent, ctx: &ContextSnapshot) -> Option<Intent> {
+    let Intent::PlacePegged {
+        symbol,
+        side,
+        reference,
+        offset_ticks,
+        qty,
+        tif,
+        tag,
+    } = intent
+    else {
+        return Some(intent);
+    };
+    let reference_price = match reference {
+        PegRef::BestBid => ctx.best_bid.map(|(price, _)| price),
+        PegRef::BestAsk => ctx.best_ask.map(|(price, _)| price),
+        PegRef::Mid => ctx.mid_price,
+    }?;
+    Some(Intent::PlaceLimit {
+        symbol,
+        side,
+        price: reference_price.checked_add_ticks(offset_ticks)?,
+        qty,
+        tif,
+        tag,
+    })
+}
+
+fn is_superseded_by(intent: &Intent, next: &Intent) -> bool {
+    match (intent, next) {
+        (
+            Intent::Cancel { client_order_id },
+            Intent::Cancel {
+                client_order_id: next_id,
+            },
+        )
+        | (
+            Intent::Replace {
+                client_order_id, ..
+            },
+            Intent::Replace {
+                client_order_id: next_id,
+                ..
+            },
+        ) => client_order_id == next_id,
+        _ => false,
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
     use std::collections::HashMap;
 
     use super::*;
     use lob_core::{LevelUpdate, Price, Qty, Side};
-    use trading_types::{ClientOrderId, ExecutionReport, OrderStatus, TimeInForce};
+    use trading_types::{ClientOrderId, ExecutionReport, OrderTag, TimeInForce};
 
     struct DummyStrategy {
         placed: bool,
@@ -285,6 +1106,8 @@ mod tests {
                 ts_ns: 1,
                 symbol,
                 side,
+                reason: None,
+                tag: None,
             });
             out.push(ExecutionReport {
                 client_order_id: order.client_order_id,
@@ -295,6 +1118,8 @@ mod tests {
                 ts_ns: 2,
                 symbol,
                 side,
+                reason: None,
+                tag: None,
             });
         }
     }
@@ -334,30 +1159,31 @@ mod tests {
             let oms::OrderRequest::Place(order) = req else {
                 return;
             };
-            let Some(limit_price) = order.price else {
-                return;
-            };
 
             let (best_bid, best_ask) = {
                 let book = self.book.borrow();
                 (book.best_bid(), book.best_ask())
             };
+            // Market orders (no price) cross whatever the opposite touch is.
             let crossing_price = match order.side {
                 Side::Bid => best_ask.and_then(|(ask, _)| {
-                    if limit_price.ticks() >= ask.ticks() {
+                    if order.price.is_none_or(|limit| limit.ticks() >= ask.ticks()) {
                         Some(ask)
                     } else {
                         None
                     }
                 }),
                 Side::Ask => best_bid.and_then(|(bid, _)| {
-                    if limit_price.ticks() <= bid.ticks() {
+                    if order.price.is_none_or(|limit| limit.ticks() <= bid.ticks()) {
                         Some(bid)
                     } else {
                         None
                     }
                 }),
             };
+            let Some(limit_price) = order.price.or(crossing_price) else {
+                return;
+            };
 
             out.push(ExecutionReport {
                 client_order_id: order.client_order_id,
@@ -368,6 +1194,8 @@ mod tests {
                 ts_ns: self.next_ts(),
                 symbol: order.symbol,
                 side: order.side,
+                reason: None,
+                tag: None,
             });
 
             if let Some(fill_price) = crossing_price {
@@ -380,6 +1208,8 @@ mod tests {
                     ts_ns: self.next_ts(),
                     symbol: order.symbol,
                     side: order.side,
+                    reason: None,
+                    tag: None,
                 });
             } else {
                 self.live_orders.insert(
@@ -437,6 +1267,8 @@ mod tests {
                     ts_ns: self.next_ts(),
                     symbol: order.symbol,
                     side: order.side,
+                    reason: None,
+                    tag: None,
                 });
             }
         }
@@ -577,85 +1409,1345 @@ mod tests {
         }
     }
 
+    struct PriceRecordingVenue {
+        prices: Rc<RefCell<Vec<Option<Price>>>>,
+    }
+
+    impl ExecutionVenue for PriceRecordingVenue {
+        fn submit(&mut self, req: &oms::OrderRequest, out: &mut Vec<ExecutionReport>) {
+            if let oms::OrderRequest::Place(order) = req {
+                self.prices.borrow_mut().push(order.price);
+            }
+            AckOnlyVenue.submit(req, out);
+        }
+    }
+
+    /// Sends one bid pegged to `reference` on every market event.
+    struct PeggedBidStrategy {
+        reference: PegRef,
+        offset_ticks: i64,
+    }
+
+    impl Strategy for PeggedBidStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            out.push(Intent::PlacePegged {
+                symbol: ctx.symbol,
+                side: Side::Bid,
+                reference: self.reference,
+                offset_ticks: self.offset_ticks,
+                qty: Qty::new(1).unwrap(),
+                tif: TimeInForce::Gtc,
+                tag: None,
+            });
+        }
+    }
+
     #[test]
-    fn snapshot_then_delta_triggers_fill_and_position() {
+    fn pegged_bid_resolves_against_best_bid() {
         let symbol = SymbolId::from_u32(1);
+        let prices = Rc::new(RefCell::new(Vec::new()));
         let mut engine = Engine::new(
             OrderBook::new(symbol),
             Portfolio::new(),
             Oms::new(),
             RiskEngine::new(),
-            Box::new(DummyStrategy::new()),
-            Box::new(DummyVenue),
+            Box::new(PeggedBidStrategy {
+                reference: PegRef::BestBid,
+                offset_ticks: -1,
+            }),
+            Box::new(PriceRecordingVenue {
+                prices: prices.clone(),
+            }),
         );
 
-        let snapshot = MarketEvent::L2Snapshot {
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
             ts_ns: 1,
             symbol,
-            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            bids: vec![],
             asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
-        };
-        assert!(engine.on_market_event(&snapshot));
-
-        let delta = MarketEvent::L2Delta {
+        }));
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
             ts_ns: 2,
             symbol,
-            updates: vec![LevelUpdate {
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        }));
+
+        assert_eq!(*prices.borrow(), vec![Some(Price::new(99).unwrap())]);
+        assert_eq!(engine.dropped_peg_count(), 1);
+    }
+
+    type OpenQuote = Option<(ClientOrderId, Price, Qty)>;
+
+    /// Rests one bid on the first event and records the context's open quotes on each.
+    struct QuoteObservingStrategy {
+        placed: bool,
+        seen: Rc<RefCell<Vec<(OpenQuote, OpenQuote)>>>,
+    }
+
+    impl Strategy for QuoteObservingStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            self.seen.borrow_mut().push((ctx.open_bid, ctx.open_ask));
+            if !self.placed {
+                self.placed = true;
+                out.push(Intent::PlaceLimit {
+                    symbol: ctx.symbol,
+                    side: Side::Bid,
+                    price: Price::new(98).unwrap(),
+                    qty: Qty::new(3).unwrap(),
+                    tif: TimeInForce::Gtc,
+                    tag: None,
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn context_reports_resting_order_price() {
+        let symbol = SymbolId::from_u32(1);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new(
+            OrderBook::new(symbol),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(QuoteObservingStrategy {
+                placed: false,
+                seen: seen.clone(),
+            }),
+            Box::new(AckOnlyVenue),
+        );
+
+        for ts_ns in 1..=2 {
+            assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+                ts_ns,
+                symbol,
+                bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+                asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+            }));
+        }
+
+        let resting = Some((
+            ClientOrderId(1),
+            Price::new(98).unwrap(),
+            Qty::new(3).unwrap(),
+        ));
+        assert_eq!(*seen.borrow(), vec![(None, None), (resting, None)]);
+    }
+
+    type SeenTag = (OrderStatus, Option<OrderTag>);
+
+    /// Lifts the ask once with a tagged order and records the tag on every report.
+    struct TaggedTakerStrategy {
+        placed: bool,
+        seen: Rc<RefCell<Vec<SeenTag>>>,
+    }
+
+    impl Strategy for TaggedTakerStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            let Some((ask, _)) = ctx.best_ask else {
+                return;
+            };
+            if std::mem::replace(&mut self.placed, true) {
+                return;
+            }
+            out.push(Intent::PlaceLimit {
+                symbol: ctx.symbol,
                 side: Side::Bid,
-                price: Price::new(100).unwrap(),
-                qty: Qty::new(2).unwrap(),
-            }],
-        };
-        assert!(engine.on_market_event(&delta));
+                price: ask,
+                qty: Qty::new(1).unwrap(),
+                tif: TimeInForce::Gtc,
+                tag: Some(OrderTag("alpha".to_string())),
+            });
+        }
 
-        assert_eq!(engine.position_lots(symbol), 1);
+        fn on_execution_report(
+            &mut self,
+            _ctx: &ContextSnapshot,
+            report: &ExecutionReport,
+            _out: &mut Vec<Intent>,
+        ) {
+            self.seen
+                .borrow_mut()
+                .push((report.status, report.tag.clone()));
+        }
     }
 
     #[test]
-    fn execution_report_follow_up_intents_are_processed() {
+    fn tagged_place_propagates_tag_to_reports() {
         let symbol = SymbolId::from_u32(1);
+        let seen = Rc::new(RefCell::new(Vec::new()));
         let mut engine = Engine::new(
             OrderBook::new(symbol),
             Portfolio::new(),
             Oms::new(),
             RiskEngine::new(),
-            Box::new(ReactiveFollowUpStrategy::new()),
+            Box::new(TaggedTakerStrategy {
+                placed: false,
+                seen: seen.clone(),
+            }),
             Box::new(DummyVenue),
         );
 
-        let snapshot = MarketEvent::L2Snapshot {
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
             ts_ns: 1,
             symbol,
             bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
             asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        }));
+
+        let tag = Some(OrderTag("alpha".to_string()));
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                (OrderStatus::Accepted, tag.clone()),
+                (OrderStatus::Filled, tag)
+            ]
+        );
+    }
+
+    /// Sends one market buy on the first two-sided book.
+    struct MarketBuyStrategy {
+        sent: bool,
+        qty: i64,
+    }
+
+    impl Strategy for MarketBuyStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            if self.sent || ctx.best_ask.is_none() {
+                return;
+            }
+            self.sent = true;
+            out.push(Intent::PlaceMarket {
+                symbol: ctx.symbol,
+                side: Side::Bid,
+                qty: Qty::new(self.qty).unwrap(),
+                tif: TimeInForce::Ioc,
+                tag: None,
+            });
+        }
+    }
+
+    struct VolumeRecordingStrategy {
+        seen: Rc<RefCell<Vec<i64>>>,
+    }
+
+    impl Strategy for VolumeRecordingStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            _out: &mut Vec<Intent>,
+        ) {
+            self.seen.borrow_mut().push(ctx.recent_trade_volume.lots());
+        }
+    }
+
+    type DepthPair = (Vec<(Price, Qty)>, Vec<(Price, Qty)>);
+
+    struct DepthRecordingStrategy {
+        seen: Rc<RefCell<Vec<DepthPair>>>,
+    }
+
+    impl Strategy for DepthRecordingStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            _out: &mut Vec<Intent>,
+        ) {
+            self.seen
+                .borrow_mut()
+                .push((ctx.bid_depth.clone(), ctx.ask_depth.clone()));
+        }
+    }
+
+    #[test]
+    fn context_depth_carries_levels_beyond_the_touch() {
+        let symbol = SymbolId::from_u32(1);
+        let level = |price, qty| (Price::new(price).unwrap(), Qty::new(qty).unwrap());
+        let snapshot = MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![level(100, 1), level(99, 2), level(98, 3)],
+            asks: vec![level(101, 4)],
         };
-        assert!(engine.on_market_event(&snapshot));
-        assert_eq!(engine.position_lots(symbol), 2);
+
+        for (context_depth, expected) in [
+            (0, (vec![], vec![])),
+            (1, (vec![level(100, 1)], vec![level(101, 4)])),
+            (2, (vec![level(100, 1), level(99, 2)], vec![level(101, 4)])),
+        ] {
+            let seen = Rc::new(RefCell::new(Vec::new()));
+            let mut engine = Engine::with_config(
+                Rc::new(RefCell::new(OrderBook::new(symbol))),
+                Portfolio::new(),
+                Oms::new(),
+                RiskEngine::new(),
+                Box::new(DepthRecordingStrategy { seen: seen.clone() }),
+                Box::new(DummyVenue),
+                EngineConfig {
+                    context_depth,
+                    ..EngineConfig::default()
+                },
+            );
+            assert!(engine.on_market_event(&snapshot));
+            assert_eq!(*seen.borrow(), vec![expected]);
+        }
     }
 
     #[test]
-    fn timer_tick_routes_strategy_intents() {
+    fn recent_trade_volume_rolls_over_configured_window() {
         let symbol = SymbolId::from_u32(1);
-        let mut engine = Engine::new(
-            OrderBook::new(symbol),
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::with_config(
+            Rc::new(RefCell::new(OrderBook::new(symbol))),
             Portfolio::new(),
             Oms::new(),
             RiskEngine::new(),
-            Box::new(TimerOnlyStrategy::new()),
+            Box::new(VolumeRecordingStrategy { seen: seen.clone() }),
             Box::new(DummyVenue),
+            EngineConfig {
+                trade_volume_window_ns: 15,
+                ..EngineConfig::default()
+            },
         );
 
-        let snapshot = MarketEvent::L2Snapshot {
-            ts_ns: 1,
+        let trade = |ts_ns, lots| MarketEvent::Trade {
+            ts_ns,
             symbol,
-            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
-            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+            price: Price::new(100).unwrap(),
+            qty: Qty::new(lots).unwrap(),
+            aggressor_side: Side::Bid,
         };
-        assert!(engine.on_market_event(&snapshot));
-        assert_eq!(engine.position_lots(symbol), 0);
+        assert!(engine.on_market_event(&trade(10, 2)));
+        assert!(engine.on_market_event(&trade(20, 3)));
+        assert!(engine.on_market_event(&trade(30, 1)));
+        assert!(engine.on_market_event(&MarketEvent::L2Delta {
+            ts_ns: 50,
+            symbol,
+            updates: vec![],
+        }));
 
-        engine.on_timer(2, symbol);
-        assert_eq!(engine.position_lots(symbol), 1);
+        assert_eq!(*seen.borrow(), vec![2, 5, 4, 0]);
+    }
+
+    struct AckOnlyVenue;
+
+    impl ExecutionVenue for AckOnlyVenue {
+        fn submit(&mut self, req: &oms::OrderRequest, out: &mut Vec<ExecutionReport>) {
+            let oms::OrderRequest::Place(order) = req else {
+                return;
+            };
+            out.push(ExecutionReport {
+                client_order_id: order.client_order_id,
+                status: OrderStatus::Accepted,
+                filled_qty: Qty::new(0).unwrap(),
+                last_fill_price: order.price.unwrap_or_else(|| Price::new(0).unwrap()),
+                fee_ticks: 0,
+                ts_ns: 1,
+                symbol: order.symbol,
+                side: order.side,
+                reason: None,
+                tag: None,
+            });
+        }
+    }
+
+    struct CountingAckVenue {
+        cancels: Rc<Cell<usize>>,
+    }
+
+    impl ExecutionVenue for CountingAckVenue {
+        fn submit(&mut self, req: &oms::OrderRequest, out: &mut Vec<ExecutionReport>) {
+            if let oms::OrderRequest::Cancel { .. } = req {
+                self.cancels.set(self.cancels.get() + 1);
+            }
+            AckOnlyVenue.submit(req, out);
+        }
+    }
+
+    /// Rests one bid, then cancels it twice in the same burst.
+    struct DoubleCancelStrategy {
+        resting: RestingBidStrategy,
+        order: Option<ClientOrderId>,
+        canceled: bool,
+    }
+
+    impl Strategy for DoubleCancelStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            let Some(client_order_id) = self.order else {
+                self.resting.on_market_event(ctx, event, out);
+                return;
+            };
+            if !std::mem::replace(&mut self.canceled, true) {
+                out.push(Intent::Cancel { client_order_id });
+                out.push(Intent::Cancel { client_order_id });
+            }
+        }
+
+        fn on_execution_report(
+            &mut self,
+            _ctx: &ContextSnapshot,
+            report: &ExecutionReport,
+            _out: &mut Vec<Intent>,
+        ) {
+            self.order.get_or_insert(report.client_order_id);
+        }
+    }
+
+    fn cancels_sent(coalesce_intents: bool) -> usize {
+        let symbol = SymbolId::from_u32(1);
+        let cancels = Rc::new(Cell::new(0));
+        let mut engine = Engine::with_config(
+            Rc::new(RefCell::new(OrderBook::new(symbol))),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(DoubleCancelStrategy {
+                resting: RestingBidStrategy::new(),
+                order: None,
+                canceled: false,
+            }),
+            Box::new(CountingAckVenue {
+                cancels: cancels.clone(),
+            }),
+            EngineConfig {
+                coalesce_intents,
+                ..EngineConfig::default()
+            },
+        );
+        for ts_ns in 1..=2 {
+            assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+                ts_ns,
+                symbol,
+                bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+                asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+            }));
+        }
+        cancels.get()
+    }
+
+    #[test]
+    fn coalescing_collapses_duplicate_cancels() {
+        assert_eq!(cancels_sent(false), 2);
+        assert_eq!(cancels_sent(true), 1);
+    }
+
+    /// Places another bid on every report, so acks feed back into the queue forever.
+    struct RunawayStrategy {
+        resting: RestingBidStrategy,
+    }
+
+    impl Strategy for RunawayStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            self.resting.on_market_event(ctx, event, out);
+        }
+
+        fn on_execution_report(
+            &mut self,
+            ctx: &ContextSnapshot,
+            report: &ExecutionReport,
+            out: &mut Vec<Intent>,
+        ) {
+            out.push(Intent::PlaceLimit {
+                symbol: ctx.symbol,
+                side: Side::Bid,
+                price: report.last_fill_price,
+                qty: Qty::new(1).unwrap(),
+                tif: TimeInForce::Gtc,
+                tag: None,
+            });
+        }
+    }
+
+    #[test]
+    fn runaway_feedback_loop_counts_overflow() {
+        let overflows_seen = Rc::new(Cell::new(0usize));
+        let seen = Rc::clone(&overflows_seen);
+        let symbol = SymbolId::from_u32(1);
+        let mut engine = Engine::with_config(
+            Rc::new(RefCell::new(OrderBook::new(symbol))),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(RunawayStrategy {
+                resting: RestingBidStrategy::new(),
+            }),
+            Box::new(AckOnlyVenue),
+            EngineConfig {
+                max_intent_steps: 8,
+                ..EngineConfig::default()
+            },
+        )
+        .with_overflow_handler(Box::new(move |overflow| {
+            assert_eq!(overflow.dropped_intents, 1);
+            seen.set(seen.get() + 1);
+        }));
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        }));
+
+        assert_eq!(engine.intent_overflow_count(), 1);
+        assert_eq!(engine.oms.open_orders(), 8);
+        assert_eq!(overflows_seen.get(), 1);
+    }
+
+    type SeenContext = (SymbolId, Option<(Price, Qty)>, usize);
+
+    struct BasketRecordingStrategy {
+        seen: Rc<RefCell<Vec<SeenContext>>>,
+    }
+
+    impl Strategy for BasketRecordingStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            self.seen
+                .borrow_mut()
+                .push((ctx.symbol, ctx.best_bid, ctx.open_orders));
+            let Some((bid, _)) = ctx.best_bid else {
+                return;
+            };
+            out.push(Intent::PlaceLimit {
+                symbol: ctx.symbol,
+                side: Side::Bid,
+                price: bid,
+                qty: Qty::new(1).unwrap(),
+                tif: TimeInForce::Gtc,
+                tag: None,
+            });
+        }
+    }
+
+    #[test]
+    fn multi_engine_builds_context_from_event_symbol_book() {
+        let btc = SymbolId::from_u32(0);
+        let eth = SymbolId::from_u32(1);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = MultiEngine::new(
+            MultiBook::new(),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(BasketRecordingStrategy { seen: seen.clone() }),
+            Box::new(AckOnlyVenue),
+        );
+
+        let snapshot = |symbol, bid| MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(bid).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(bid + 1).unwrap(), Qty::new(1).unwrap())],
+        };
+        assert!(engine.on_market_event(&snapshot(btc, 100)));
+        assert!(engine.on_market_event(&snapshot(eth, 200)));
+        assert!(engine.on_market_event(&snapshot(btc, 101)));
+
+        let bid = |ticks| Some((Price::new(ticks).unwrap(), Qty::new(1).unwrap()));
+        assert_eq!(
+            *seen.borrow(),
+            vec![(btc, bid(100), 0), (eth, bid(200), 0), (btc, bid(101), 1)]
+        );
+    }
+
+    /// On any other symbol's event, bids 2 lots and then 1 lot on `target`.
+    struct CrossSymbolStrategy {
+        target: SymbolId,
+    }
+
+    impl Strategy for CrossSymbolStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            if ctx.symbol == self.target {
+                return;
+            }
+            for lots in [2, 1] {
+                out.push(Intent::PlaceLimit {
+                    symbol: self.target,
+                    side: Side::Bid,
+                    price: Price::new(200).unwrap(),
+                    qty: Qty::new(lots).unwrap(),
+                    tif: TimeInForce::Gtc,
+                    tag: None,
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn multi_engine_risk_checks_intents_against_their_own_symbol() {
+        let btc = SymbolId::from_u32(0);
+        let eth = SymbolId::from_u32(1);
+        let limits = HashMap::from([(btc, 10), (eth, 1)]);
+        let mut engine = MultiEngine::new(
+            MultiBook::new(),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new().with_policy(risk::MaxPositionPolicy::per_symbol(limits, 0)),
+            Box::new(CrossSymbolStrategy { target: eth }),
+            Box::new(AckOnlyVenue),
+        );
+
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol: btc,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        }));
+
+        // ETH's 1-lot limit applies, not BTC's 10: only the 1-lot bid gets through.
+        assert_eq!(engine.oms.open_orders_for(eth), 1);
+        assert_eq!(engine.oms.open_orders_for(btc), 0);
+    }
+
+    #[test]
+    fn snapshot_then_delta_triggers_fill_and_position() {
+        let symbol = SymbolId::from_u32(1);
+        let mut engine = Engine::new(
+            OrderBook::new(symbol),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(DummyStrategy::new()),
+            Box::new(DummyVenue),
+        );
+
+        let snapshot = MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        };
+        assert!(engine.on_market_event(&snapshot));
+
+        let delta = MarketEvent::L2Delta {
+            ts_ns: 2,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Bid,
+                price: Price::new(100).unwrap(),
+                qty: Qty::new(2).unwrap(),
+            }],
+        };
+        assert!(engine.on_market_event(&delta));
+
+        assert_eq!(engine.position_lots(symbol), 1);
+    }
+
+    type PnlSeen = Rc<RefCell<Vec<(i128, Option<i128>)>>>;
+
+    struct PnlRecordingStrategy {
+        inner: DummyStrategy,
+        seen: PnlSeen,
+    }
+
+    impl Strategy for PnlRecordingStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            self.seen
+                .borrow_mut()
+                .push((ctx.realized_pnl_ticks, ctx.unrealized_pnl_ticks));
+            self.inner.on_market_event(ctx, event, out);
+        }
+    }
+
+    #[test]
+    fn context_carries_realized_and_unrealized_pnl() {
+        let symbol = SymbolId::from_u32(1);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new(
+            OrderBook::new(symbol),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(PnlRecordingStrategy {
+                inner: DummyStrategy::new(),
+                seen: seen.clone(),
+            }),
+            Box::new(DummyVenue),
+        );
+
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(102).unwrap(), Qty::new(1).unwrap())],
+        }));
+        assert!(engine.on_market_event(&MarketEvent::L2Delta {
+            ts_ns: 2,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Bid,
+                price: Price::new(104).unwrap(),
+                qty: Qty::new(1).unwrap(),
+            }],
+        }));
+        assert!(engine.on_market_event(&MarketEvent::L2Delta {
+            ts_ns: 3,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Ask,
+                price: Price::new(102).unwrap(),
+                qty: Qty::new(0).unwrap(),
+            }],
+        }));
+
+        // Bought one lot at 102; mid is (104 + 102) / 2 = 103, then one-sided.
+        assert_eq!(*seen.borrow(), vec![(0, Some(0)), (0, Some(1)), (0, None)]);
+    }
+
+    #[test]
+    fn disabling_latency_recording_skips_timing() {
+        let symbol = SymbolId::from_u32(1);
+        let mut engine = Engine::with_config(
+            Rc::new(RefCell::new(OrderBook::new(symbol))),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(DummyStrategy::new()),
+            Box::new(DummyVenue),
+            EngineConfig {
+                record_latency: false,
+                ..EngineConfig::default()
+            },
+        );
+
+        let snapshot = MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        };
+        assert!(engine.on_market_event(&snapshot));
+        assert_eq!(engine.position_lots(symbol), 1);
+        assert_eq!(engine.latency_stats().count(), 0);
+    }
+
+    #[test]
+    fn execution_report_follow_up_intents_are_processed() {
+        let symbol = SymbolId::from_u32(1);
+        let mut engine = Engine::new(
+            OrderBook::new(symbol),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(ReactiveFollowUpStrategy::new()),
+            Box::new(DummyVenue),
+        );
+
+        let snapshot = MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        };
+        assert!(engine.on_market_event(&snapshot));
+        assert_eq!(engine.position_lots(symbol), 2);
+    }
+
+    struct RejectFirstVenue {
+        inner: DummyVenue,
+        rejected: bool,
+    }
+
+    impl ExecutionVenue for RejectFirstVenue {
+        fn submit(&mut self, req: &oms::OrderRequest, out: &mut Vec<ExecutionReport>) {
+            let oms::OrderRequest::Place(order) = req else {
+                return;
+            };
+            if self.rejected {
+                self.inner.submit(req, out);
+                return;
+            }
+            self.rejected = true;
+            out.push(ExecutionReport {
+                client_order_id: order.client_order_id,
+                status: OrderStatus::Rejected,
+                filled_qty: Qty::new(0).unwrap(),
+                last_fill_price: order.price.unwrap_or_else(|| Price::new(0).unwrap()),
+                fee_ticks: 0,
+                ts_ns: 1,
+                symbol: order.symbol,
+                side: order.side,
+                reason: None,
+                tag: None,
+            });
+        }
+    }
+
+    struct RetryOnRejectStrategy {
+        inner: DummyStrategy,
+        calls: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl Strategy for RetryOnRejectStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            self.inner.on_market_event(ctx, event, out);
+        }
+
+        fn on_execution_report(
+            &mut self,
+            _ctx: &ContextSnapshot,
+            _report: &ExecutionReport,
+            _out: &mut Vec<Intent>,
+        ) {
+            self.calls.borrow_mut().push("report");
+        }
+
+        fn on_order_accepted(
+            &mut self,
+            _ctx: &ContextSnapshot,
+            _report: &ExecutionReport,
+            _out: &mut Vec<Intent>,
+        ) {
+            self.calls.borrow_mut().push("accepted");
+        }
+
+        fn on_order_rejected(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _report: &ExecutionReport,
+            out: &mut Vec<Intent>,
+        ) {
+            self.calls.borrow_mut().push("rejected");
+            self.inner.placed = false;
+            self.inner.on_market_event(
+                ctx,
+                &MarketEvent::L2Delta {
+                    ts_ns: ctx.ts_ns,
+                    symbol: ctx.symbol,
+                    updates: vec![],
+                },
+                out,
+            );
+        }
+    }
+
+    #[test]
+    fn accepted_and_rejected_callbacks_follow_generic_report() {
+        let symbol = SymbolId::from_u32(1);
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new(
+            OrderBook::new(symbol),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(RetryOnRejectStrategy {
+                inner: DummyStrategy::new(),
+                calls: calls.clone(),
+            }),
+            Box::new(RejectFirstVenue {
+                inner: DummyVenue,
+                rejected: false,
+            }),
+        );
+
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        }));
+
+        assert_eq!(
+            *calls.borrow(),
+            vec!["report", "rejected", "report", "accepted", "report"]
+        );
+        assert_eq!(engine.position_lots(symbol), 1);
+    }
+
+    #[test]
+    fn timer_tick_routes_strategy_intents() {
+        let symbol = SymbolId::from_u32(1);
+        let mut engine = Engine::new(
+            OrderBook::new(symbol),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(TimerOnlyStrategy::new()),
+            Box::new(DummyVenue),
+        );
+
+        let snapshot = MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        };
+        assert!(engine.on_market_event(&snapshot));
+        assert_eq!(engine.position_lots(symbol), 0);
+
+        engine.on_timer(2, symbol);
+        assert_eq!(engine.position_lots(symbol), 1);
+    }
+
+    struct TimerRecordingStrategy {
+        fired: Rc<RefCell<Vec<u64>>>,
+    }
+
+    impl Strategy for TimerRecordingStrategy {
+        fn on_market_event(
+            &mut self,
+            _ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            _out: &mut Vec<Intent>,
+        ) {
+        }
+
+        fn on_timer(&mut self, ctx: &ContextSnapshot, _out: &mut Vec<Intent>) {
+            self.fired.borrow_mut().push(ctx.ts_ns);
+        }
+    }
+
+    #[test]
+    fn advance_to_fires_timers_at_interval_boundaries() {
+        let symbol = SymbolId::from_u32(1);
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        let config = EngineConfig {
+            timer_interval_ns: Some(10),
+            ..EngineConfig::default()
+        };
+        let mut engine = Engine::with_config(
+            Rc::new(RefCell::new(OrderBook::new(symbol))),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(TimerRecordingStrategy {
+                fired: fired.clone(),
+            }),
+            Box::new(DummyVenue),
+            config,
+        );
+
+        engine.advance_to(5, symbol);
+        assert!(fired.borrow().is_empty());
+        engine.advance_to(14, symbol);
+        assert!(fired.borrow().is_empty());
+        engine.advance_to(15, symbol);
+        engine.advance_to(47, symbol);
+        assert_eq!(*fired.borrow(), vec![15, 25, 35, 45]);
+    }
+
+    struct TimerIdRecordingStrategy {
+        fired: Rc<RefCell<Vec<(TimerId, u64)>>>,
+    }
+
+    impl Strategy for TimerIdRecordingStrategy {
+        fn on_market_event(
+            &mut self,
+            _ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            _out: &mut Vec<Intent>,
+        ) {
+        }
+
+        fn on_timer_id(&mut self, timer: TimerId, ctx: &ContextSnapshot, _out: &mut Vec<Intent>) {
+            self.fired.borrow_mut().push((timer, ctx.ts_ns));
+        }
+    }
+
+    #[test]
+    fn timer_schedule_fires_each_interval_with_its_id() {
+        let symbol = SymbolId::from_u32(1);
+        let (fast, slow) = (TimerId(1), TimerId(2));
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::with_config(
+            Rc::new(RefCell::new(OrderBook::new(symbol))),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(TimerIdRecordingStrategy {
+                fired: fired.clone(),
+            }),
+            Box::new(DummyVenue),
+            EngineConfig {
+                timer_interval_ns: Some(7),
+                ..EngineConfig::default()
+            },
+        )
+        .with_timer_schedule(
+            TimerSchedule::new()
+                .with_timer(fast, 100)
+                .with_timer(slow, 1_000),
+        );
+        assert_eq!(engine.config().timer_interval_ns, None);
+
+        engine.advance_to(0, symbol);
+        for ts_ns in (50..=3_050).step_by(50) {
+            engine.advance_to(ts_ns, symbol);
+        }
+
+        let fired = fired.borrow();
+        let ticks = |timer| fired.iter().filter(|(id, _)| *id == timer).count();
+        assert_eq!(ticks(fast), 30);
+        assert_eq!(ticks(slow), 3);
+        assert_eq!(ticks(TimerId::DEFAULT), 0);
+        // Boundaries fire in time order; at a shared boundary the fast timer goes first.
+        assert!(fired.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        let at_1000: Vec<TimerId> = fired
+            .iter()
+            .filter(|(_, ts_ns)| *ts_ns == 1_000)
+            .map(|(id, _)| *id)
+            .collect();
+        assert_eq!(at_1000, vec![fast, slow]);
+    }
+
+    #[test]
+    fn advance_to_skips_ahead_past_max_timer_ticks() {
+        let symbol = SymbolId::from_u32(1);
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::with_config(
+            Rc::new(RefCell::new(OrderBook::new(symbol))),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(TimerRecordingStrategy {
+                fired: fired.clone(),
+            }),
+            Box::new(DummyVenue),
+            EngineConfig {
+                timer_interval_ns: Some(10),
+                max_timer_ticks: 3,
+                ..EngineConfig::default()
+            },
+        );
+
+        engine.advance_to(0, symbol);
+        engine.advance_to(105, symbol);
+        assert_eq!(*fired.borrow(), vec![10, 20, 30]);
+        assert_eq!(engine.timer_overflow_count(), 1);
+
+        // The schedule caught up to 100, so only later boundaries fire.
+        engine.advance_to(109, symbol);
+        engine.advance_to(110, symbol);
+        assert_eq!(*fired.borrow(), vec![10, 20, 30, 110]);
+        assert_eq!(engine.timer_overflow_count(), 1);
+    }
+
+    #[test]
+    fn advance_to_is_noop_without_timer_interval() {
+        let symbol = SymbolId::from_u32(1);
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new(
+            OrderBook::new(symbol),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(TimerRecordingStrategy {
+                fired: fired.clone(),
+            }),
+            Box::new(DummyVenue),
+        );
+
+        engine.advance_to(1, symbol);
+        engine.advance_to(1_000_000_000_000, symbol);
+        assert!(fired.borrow().is_empty());
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn jsonl_recorder_traces_snapshot_then_passive_fill() {
+        let symbol = SymbolId::from_u32(1);
+        let shared_book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let venue = PassiveFillVenue::new(shared_book.clone());
+        let buffer = SharedBuffer::default();
+        let mut engine = Engine::with_shared_book(
+            shared_book,
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(RestingBidStrategy::new()),
+            Box::new(venue),
+        )
+        .with_recorder(Box::new(JsonlRecorder::new(buffer.clone())));
+
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        }));
+        assert!(engine.on_market_event(&MarketEvent::L2Delta {
+            ts_ns: 2,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Ask,
+                price: Price::new(100).unwrap(),
+                qty: Qty::new(1).unwrap(),
+            }],
+        }));
+        assert_eq!(engine.position_lots(symbol), 1);
+
+        let output = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        let records: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let kinds: Vec<&str> = records
+            .iter()
+            .map(|record| record["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "market_event",
+                "intent",
+                "risk_decision",
+                "order_request",
+                "execution_report",
+                "market_event",
+                "execution_report",
+            ]
+        );
+        assert_eq!(records[0]["ts_ns"], 1);
+        assert_eq!(records[5]["ts_ns"], 2);
+        assert_eq!(records[6]["report"]["status"], "Filled");
+    }
+
+    #[test]
+    fn finish_recording_flushes_the_audit_file() {
+        let symbol = SymbolId::from_u32(1);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let file = io::BufWriter::new(std::fs::File::create(&path).unwrap());
+        let mut engine = Engine::new(
+            OrderBook::new(symbol),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(TimerOnlyStrategy::new()),
+            Box::new(DummyVenue),
+        )
+        .with_recorder(Box::new(JsonlRecorder::new(file)));
+
+        for ts_ns in 1..=3 {
+            assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+                ts_ns,
+                symbol,
+                bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+                asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+            }));
+        }
+        // Nothing reaches the file until the buffered writer is flushed.
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+        engine.finish_recording().unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 3);
+        assert!(contents
+            .lines()
+            .all(|line| line.contains("\"market_event\"")));
+        assert!(engine.take_recorder().is_some());
+        engine.finish_recording().unwrap();
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn finish_recording_reports_write_errors() {
+        let symbol = SymbolId::from_u32(1);
+        let mut engine = Engine::new(
+            OrderBook::new(symbol),
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(TimerOnlyStrategy::new()),
+            Box::new(DummyVenue),
+        )
+        .with_recorder(Box::new(JsonlRecorder::new(FailingWriter)));
+
+        assert!(engine.on_market_event(&MarketEvent::Trade {
+            ts_ns: 1,
+            symbol,
+            price: Price::new(100).unwrap(),
+            qty: Qty::new(1).unwrap(),
+            aggressor_side: Side::Bid,
+        }));
+        let err = engine.finish_recording().unwrap_err();
+        assert_eq!(err.to_string(), "disk full");
+    }
+
+    #[test]
+    fn save_and_restore_state_round_trips_position_and_pnl() {
+        let symbol = SymbolId::from_u32(1);
+        let fresh_engine = || {
+            Engine::new(
+                OrderBook::new(symbol),
+                Portfolio::new(),
+                Oms::new(),
+                RiskEngine::new(),
+                Box::new(DummyStrategy::new()),
+                Box::new(DummyVenue),
+            )
+        };
+
+        let mut engine = fresh_engine();
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(101).unwrap(), Qty::new(1).unwrap())],
+        }));
+        assert_eq!(engine.position_lots(symbol), 1);
+
+        let json = serde_json::to_string(&engine.save_state()).unwrap();
+        let state: EngineState<OrderBook> = serde_json::from_str(&json).unwrap();
+
+        let mut restored = fresh_engine();
+        restored.restore_state(state);
+        assert_eq!(restored.position_lots(symbol), 1);
+        assert_eq!(
+            restored.realized_pnl_ticks(symbol),
+            engine.realized_pnl_ticks(symbol)
+        );
+        assert_eq!(
+            restored.fees_paid_ticks(symbol),
+            engine.fees_paid_ticks(symbol)
+        );
+        assert_eq!(restored.save_state().book, engine.save_state().book);
+    }
+
+    #[test]
+    fn market_buy_fills_immediately_at_resting_ask() {
+        let symbol = SymbolId::from_u32(1);
+        let shared_book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let venue = PassiveFillVenue::new(shared_book.clone());
+        let mut engine = Engine::with_shared_book(
+            shared_book,
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new().with_policy(risk::PriceBandPolicy::new(0)),
+            Box::new(MarketBuyStrategy {
+                sent: false,
+                qty: 1,
+            }),
+            Box::new(venue),
+        );
+
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![(Price::new(103).unwrap(), Qty::new(1).unwrap())],
+        }));
+
+        assert_eq!(engine.position_lots(symbol), 1);
+        assert_eq!(engine.portfolio.cash_ticks(symbol), -103);
+        assert_eq!(engine.oms.open_orders(), 0);
+    }
+
+    #[test]
+    fn flatten_closes_position_and_realizes_pnl() {
+        let symbol = SymbolId::from_u32(1);
+        let shared_book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let venue = PassiveFillVenue::new(shared_book.clone());
+        let mut engine = Engine::with_shared_book(
+            shared_book,
+            Portfolio::new(),
+            Oms::new(),
+            RiskEngine::new(),
+            Box::new(MarketBuyStrategy {
+                sent: false,
+                qty: 5,
+            }),
+            Box::new(venue),
+        );
+
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).unwrap(), Qty::new(5).unwrap())],
+            asks: vec![(Price::new(103).unwrap(), Qty::new(5).unwrap())],
+        }));
+        assert!(engine.on_market_event(&MarketEvent::L2Snapshot {
+            ts_ns: 2,
+            symbol,
+            bids: vec![(Price::new(106).unwrap(), Qty::new(5).unwrap())],
+            asks: vec![(Price::new(107).unwrap(), Qty::new(5).unwrap())],
+        }));
+        assert_eq!(engine.position_lots(symbol), 5);
+        assert_eq!(engine.realized_pnl_ticks(symbol), 0);
+
+        assert_eq!(engine.flatten(3, symbol), 0);
+        assert_eq!(engine.position_lots(symbol), 0);
+        assert_eq!(engine.realized_pnl_ticks(symbol), 15);
+        assert_eq!(engine.oms.open_orders(), 0);
+        // Nothing left to close.
+        assert_eq!(engine.flatten(4, symbol), 0);
+        assert_eq!(engine.realized_pnl_ticks(symbol), 15);
     }
 
     #[test]
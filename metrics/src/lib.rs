@@ -1,6 +1,14 @@
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
+pub use hdrhistogram::CreationError;
 use hdrhistogram::Histogram;
+use serde::Serialize;
+
+/// Quantiles reported by `summary_string`.
+pub const DEFAULT_QUANTILES: [f64; 3] = [0.50, 0.95, 0.99];
 
 #[derive(Debug, Clone)]
 pub struct LatencyStats {
@@ -19,31 +27,147 @@ impl LatencyStats {
         Self { histogram }
     }
 
+    /// `sigfig` significant figures of precision. With a `max`, the histogram is fixed
+    /// size and larger values are recorded into its top bucket; without one it auto-resizes
+    /// like [`LatencyStats::new`]. Fails for `sigfig` above 5 or a `max` below 2.
+    pub fn with_config(sigfig: u8, max: Option<u64>) -> Result<Self, CreationError> {
+        let histogram = match max {
+            Some(max) => Histogram::<u64>::new_with_max(max, sigfig)?,
+            None => {
+                let mut histogram = Histogram::<u64>::new(sigfig)?;
+                histogram.auto(true);
+                histogram
+            }
+        };
+        Ok(Self {
+            histogram: Some(histogram),
+        })
+    }
+
     pub fn record(&mut self, ns: u64) {
         if let Some(histogram) = &mut self.histogram {
-            let _ = histogram.record(ns);
+            if histogram.is_auto_resize() {
+                let _ = histogram.record(ns);
+            } else {
+                histogram.saturating_record(ns);
+            }
         }
     }
 
-    pub fn summary_string(&self) -> String {
-        let Some(histogram) = &self.histogram else {
-            return "count=0 p50=0 p95=0 p99=0 max=0".to_string();
+    /// Drops all samples (including min/max); precision and bounds are kept.
+    pub fn reset(&mut self) {
+        if let Some(histogram) = &mut self.histogram {
+            histogram.reset();
+        }
+    }
+
+    /// Returns the samples recorded so far and resets, so reporting windows don't overlap.
+    pub fn take_snapshot(&mut self) -> LatencyStats {
+        let snapshot = self.clone();
+        self.reset();
+        snapshot
+    }
+
+    /// Adds `other`'s samples, as if they had been recorded here. Into a bounded histogram,
+    /// values above its `max` land in the top bucket, same as [`LatencyStats::record`].
+    pub fn merge(&mut self, other: &LatencyStats) {
+        let Some(other_histogram) = &other.histogram else {
+            return;
         };
+        match &mut self.histogram {
+            Some(histogram) => {
+                if histogram.add(other_histogram).is_err() {
+                    for value in other_histogram.iter_recorded() {
+                        histogram
+                            .saturating_record_n(value.value_iterated_to(), value.count_at_value());
+                    }
+                }
+            }
+            None => self.histogram = Some(other_histogram.clone()),
+        }
+    }
 
-        if histogram.is_empty() {
-            return "count=0 p50=0 p95=0 p99=0 max=0".to_string();
+    /// Combines per-shard stats into one.
+    pub fn merge_from_iter<'a>(stats: impl IntoIterator<Item = &'a LatencyStats>) -> Self {
+        let mut merged = Self::new();
+        for other in stats {
+            merged.merge(other);
         }
+        merged
+    }
 
-        let p50 = histogram.value_at_quantile(0.50);
-        let p95 = histogram.value_at_quantile(0.95);
-        let p99 = histogram.value_at_quantile(0.99);
-        let max = histogram.max();
-        let count = histogram.len();
+    pub fn summary_string(&self) -> String {
+        self.summary_with(&DEFAULT_QUANTILES)
+    }
 
-        format!(
-            "count={} p50={} p95={} p99={} max={}",
-            count, p50, p95, p99, max
-        )
+    /// `count=N`, then `pXX=value` per quantile (e.g. `p99.9` for 0.999), then `max=value`.
+    pub fn summary_with(&self, quantiles: &[f64]) -> String {
+        let mut out = format!("count={}", self.count());
+        for &q in quantiles {
+            let percent = (clamp_quantile(q) * 100.0 * 1e4).round() / 1e4;
+            out.push_str(&format!(" p{}={}", percent, self.value_at_quantile(q)));
+        }
+        out.push_str(&format!(" max={}", self.max()));
+        out
+    }
+
+    /// Value at quantile `q`, clamped into `[0, 1]`; zero when nothing was recorded.
+    pub fn value_at_quantile(&self, q: f64) -> u64 {
+        match &self.histogram {
+            Some(histogram) if !histogram.is_empty() => {
+                histogram.value_at_quantile(clamp_quantile(q))
+            }
+            _ => 0,
+        }
+    }
+
+    /// Prometheus text exposition: a `histogram` with power-of-two `le` buckets, plus
+    /// `{name}_quantile` and `{name}_max` gauges. `_sum` is derived from the histogram
+    /// mean, so it is approximate to the histogram's precision.
+    pub fn to_prometheus(&self, name: &str) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("# TYPE {name} histogram\n"));
+        let mut cumulative = 0u64;
+        let mut sum = 0u64;
+        if let Some(histogram) = self.histogram.as_ref().filter(|h| !h.is_empty()) {
+            for bucket in histogram.iter_log(1, 2.0) {
+                cumulative += bucket.count_since_last_iteration();
+                out.push_str(&format!(
+                    "{name}_bucket{{le=\"{}\"}} {cumulative}\n",
+                    bucket.value_iterated_to()
+                ));
+            }
+            sum = (histogram.mean() * histogram.len() as f64).round() as u64;
+        }
+        out.push_str(&format!("{name}_bucket{{le=\"+Inf\"}} {}\n", self.count()));
+        out.push_str(&format!("{name}_sum {sum}\n"));
+        out.push_str(&format!("{name}_count {}\n", self.count()));
+
+        out.push_str(&format!("# TYPE {name}_quantile gauge\n"));
+        for q in DEFAULT_QUANTILES {
+            out.push_str(&format!(
+                "{name}_quantile{{quantile=\"{q}\"}} {}\n",
+                self.value_at_quantile(q)
+            ));
+        }
+        out.push_str(&format!("# TYPE {name}_max gauge\n"));
+        out.push_str(&format!("{name}_max {}\n", self.max()));
+        out
+    }
+
+    fn max(&self) -> u64 {
+        self.histogram.as_ref().map_or(0, Histogram::max)
+    }
+
+    /// Count and percentiles in nanoseconds; all zero when nothing was recorded.
+    pub fn summary(&self) -> LatencySummary {
+        LatencySummary {
+            count: self.count(),
+            p50: self.value_at_quantile(0.50),
+            p95: self.value_at_quantile(0.95),
+            p99: self.value_at_quantile(0.99),
+            max: self.max(),
+        }
     }
 
     pub fn count(&self) -> u64 {
@@ -51,25 +175,132 @@ impl LatencyStats {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl fmt::Display for LatencySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "count={} p50={} p95={} p99={} max={}",
+            self.count, self.p50, self.p95, self.p99, self.max
+        )
+    }
+}
+
+fn clamp_quantile(q: f64) -> f64 {
+    if q.is_nan() {
+        0.0
+    } else {
+        q.clamp(0.0, 1.0)
+    }
+}
+
 impl Default for LatencyStats {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Nanosecond time source for metrics; only differences between readings matter.
+pub trait Clock: fmt::Debug {
+    fn now_ns(&self) -> u64;
+}
+
+/// Wall-clock time since the clock was created.
+#[derive(Debug, Clone, Copy)]
+pub struct WallClock {
+    origin: Instant,
+}
+
+impl WallClock {
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    pub fn starting_at(origin: Instant) -> Self {
+        Self { origin }
+    }
+}
+
+impl Default for WallClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for WallClock {
+    fn now_ns(&self) -> u64 {
+        self.origin.elapsed().as_nanos().min(u64::MAX as u128) as u64
+    }
+}
+
+/// Simulated time driven by event timestamps, so backtest metrics are reproducible.
+/// Clones share the same time, letting a driver advance the clock its trackers read.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualClock {
+    now_ns: Rc<Cell<u64>>,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves time forward to `ts_ns`; earlier timestamps leave the clock unchanged.
+    pub fn advance_to(&self, ts_ns: u64) {
+        self.now_ns.set(self.now_ns.get().max(ts_ns));
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now_ns(&self) -> u64 {
+        self.now_ns.get()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ThroughputTracker {
     window: Duration,
-    window_start: Instant,
+    clock: Rc<dyn Clock>,
+    /// Wall-clock instant matching `clock`'s zero, used to convert `tick` arguments;
+    /// `None` for trackers on a custom clock.
+    origin: Option<Instant>,
+    window_start_ns: u64,
     count: u64,
+    /// Share of earlier counts attributed to the partial window still open at `tick`.
+    carried: f64,
 }
 
 impl ThroughputTracker {
     pub fn new(window: Duration) -> Self {
+        let origin = Instant::now();
+        Self {
+            window,
+            clock: Rc::new(WallClock::starting_at(origin)),
+            origin: Some(origin),
+            window_start_ns: 0,
+            count: 0,
+            carried: 0.0,
+        }
+    }
+
+    /// Measures windows and rates in `clock` time, e.g. a `VirtualClock` for backtests.
+    pub fn new_with_clock(window: Duration, clock: Rc<dyn Clock>) -> Self {
+        let window_start_ns = clock.now_ns();
         Self {
             window,
-            window_start: Instant::now(),
+            clock,
+            origin: None,
+            window_start_ns,
             count: 0,
+            carried: 0.0,
         }
     }
 
@@ -78,22 +309,96 @@ impl ThroughputTracker {
     }
 
     pub fn events_per_sec(&mut self) -> Option<f64> {
-        let elapsed = self.window_start.elapsed();
+        let now_ns = self.clock.now_ns();
+        let elapsed = Duration::from_nanos(now_ns.saturating_sub(self.window_start_ns));
         if elapsed < self.window {
             return None;
         }
 
         let rate = self.count as f64 / elapsed.as_secs_f64();
-        self.window_start = Instant::now();
+        self.window_start_ns = now_ns;
         self.count = 0;
         Some(rate)
     }
+
+    /// Rate over the fixed windows completed since the last tick, on a grid anchored at
+    /// construction. Counts are split pro rata between completed windows and the open
+    /// partial window, whose share carries forward rather than being dropped. `None`
+    /// until a window completes. Use either this or `events_per_sec`, not both.
+    ///
+    /// `now` only applies to wall-clock trackers; a tracker built with `new_with_clock`
+    /// ignores it and ticks at its clock's current time.
+    pub fn tick(&mut self, now: Instant) -> Option<f64> {
+        let now_ns = match self.origin {
+            Some(origin) => {
+                let now_ns = now.saturating_duration_since(origin).as_nanos();
+                now_ns.min(u64::MAX as u128) as u64
+            }
+            None => self.clock.now_ns(),
+        };
+        self.tick_at_ns(now_ns)
+    }
+
+    /// `tick` with `now_ns` in this tracker's clock time.
+    pub fn tick_at_ns(&mut self, now_ns: u64) -> Option<f64> {
+        let elapsed_ns = now_ns.saturating_sub(self.window_start_ns);
+        let window_ns = (self.window.as_nanos().min(u64::MAX as u128) as u64).max(1);
+        let completed = elapsed_ns / window_ns;
+        if completed == 0 {
+            return None;
+        }
+
+        let completed_ns = window_ns * completed;
+        let total = self.count as f64 + self.carried;
+        let attributed = total * (completed_ns as f64 / elapsed_ns as f64);
+        self.carried = total - attributed;
+        self.count = 0;
+        self.window_start_ns += completed_ns;
+        Some(attributed / (completed_ns as f64 / 1e9))
+    }
+
+    /// Prometheus gauges for the current, still-open window; reading does not reset it.
+    pub fn to_prometheus(&self, name: &str) -> String {
+        let elapsed_ns = self.clock.now_ns().saturating_sub(self.window_start_ns);
+        let rate = if elapsed_ns > 0 {
+            self.count as f64 / (elapsed_ns as f64 / 1e9)
+        } else {
+            0.0
+        };
+        format!(
+            "# TYPE {name}_window_events gauge\n{name}_window_events {}\n\
+             # TYPE {name}_events_per_sec gauge\n{name}_events_per_sec {rate}\n",
+            self.count
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn bounded_stats_saturate_into_top_bucket() {
+        let mut stats = LatencyStats::with_config(2, Some(1_000)).unwrap();
+        stats.record(10);
+        stats.record(1_000_000);
+
+        assert_eq!(stats.count(), 2);
+        let top = stats.max();
+        assert!((1_000..1_000_000).contains(&top), "top={top}");
+        assert_eq!(stats.value_at_quantile(1.0), top);
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        assert!(matches!(
+            LatencyStats::with_config(6, None),
+            Err(CreationError::SigFigExceedsMax)
+        ));
+        assert!(LatencyStats::with_config(3, Some(1)).is_err());
+        assert!(LatencyStats::with_config(3, None).is_ok());
+    }
+
     #[test]
     fn summary_formatting_with_data() {
         let mut stats = LatencyStats::new();
@@ -107,6 +412,233 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tail_quantiles_on_skewed_distribution() {
+        let mut stats = LatencyStats::new();
+        for _ in 0..990 {
+            stats.record(100);
+        }
+        for _ in 0..10 {
+            stats.record(1_000_000);
+        }
+
+        assert_eq!(stats.value_at_quantile(0.99), 100);
+        let p999 = stats.value_at_quantile(0.999);
+        assert!((999_000..=1_001_000).contains(&p999), "p999={p999}");
+        assert_eq!(stats.value_at_quantile(-1.0), stats.value_at_quantile(0.0));
+        assert_eq!(stats.value_at_quantile(2.0), stats.value_at_quantile(1.0));
+
+        let summary = stats.summary_with(&[0.5, 0.999]);
+        assert!(
+            summary.starts_with("count=1000 p50=100 p99.9="),
+            "{summary}"
+        );
+        assert_eq!(
+            LatencyStats::new().summary_with(&[0.999]),
+            "count=0 p99.9=0 max=0"
+        );
+    }
+
+    #[test]
+    fn merged_stats_match_single_histogram() {
+        let mut left = LatencyStats::new();
+        let mut right = LatencyStats::new();
+        let mut combined = LatencyStats::new();
+        for value in 1..=2_000u64 {
+            let sample = value * value;
+            if value % 3 == 0 {
+                right.record(sample);
+            } else {
+                left.record(sample);
+            }
+            combined.record(sample);
+        }
+
+        let quantiles = [0.5, 0.9, 0.99, 0.999];
+        let mut merged = left.clone();
+        merged.merge(&right);
+        assert_eq!(
+            merged.summary_with(&quantiles),
+            combined.summary_with(&quantiles)
+        );
+
+        let from_iter = LatencyStats::merge_from_iter([&left, &right, &LatencyStats::new()]);
+        assert_eq!(from_iter.summary(), combined.summary());
+    }
+
+    #[test]
+    fn merge_into_bounded_stats_saturates_out_of_range_samples() {
+        let mut bounded = LatencyStats::with_config(3, Some(1_000)).unwrap();
+        bounded.record(10);
+        let mut wide = LatencyStats::new();
+        wide.record(20);
+        wide.record(50_000);
+        wide.record(50_000);
+
+        bounded.merge(&wide);
+        let summary = bounded.summary();
+        assert_eq!(summary.count, 4);
+        assert_eq!(bounded.value_at_quantile(0.25), 10);
+        assert_eq!(bounded.value_at_quantile(0.5), 20);
+        assert!(
+            (1_000..=1_001).contains(&summary.max),
+            "max={}",
+            summary.max
+        );
+    }
+
+    /// Checks `name{labels} value` shape and returns the metric names seen.
+    fn prometheus_metric_names(text: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        for line in text.lines() {
+            if line.starts_with("# TYPE ") {
+                assert_eq!(line.split(' ').count(), 4, "bad TYPE line: {line}");
+                continue;
+            }
+            let (series, value) = line.rsplit_once(' ').expect("metric line has a value");
+            assert!(value.parse::<f64>().is_ok(), "bad value in: {line}");
+            let name = match series.split_once('{') {
+                Some((name, labels)) => {
+                    assert!(labels.ends_with('}'), "unterminated labels: {line}");
+                    name
+                }
+                None => series,
+            };
+            assert!(
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':'),
+                "bad metric name in: {line}"
+            );
+            names.push(name.to_string());
+        }
+        names
+    }
+
+    #[test]
+    fn prometheus_exposition_is_well_formed() {
+        let mut stats = LatencyStats::new();
+        for value in [3, 5, 40, 900, 900] {
+            stats.record(value);
+        }
+        let text = stats.to_prometheus("apply_latency_ns");
+        let names = prometheus_metric_names(&text);
+        for expected in [
+            "apply_latency_ns_bucket",
+            "apply_latency_ns_sum",
+            "apply_latency_ns_count",
+            "apply_latency_ns_quantile",
+            "apply_latency_ns_max",
+        ] {
+            assert!(
+                names.iter().any(|name| name == expected),
+                "missing {expected}"
+            );
+        }
+        assert!(text.contains("# TYPE apply_latency_ns histogram"));
+        assert!(text.contains("apply_latency_ns_bucket{le=\"+Inf\"} 5\n"));
+        assert!(text.contains("apply_latency_ns_count 5\n"));
+        assert!(text.contains("apply_latency_ns_quantile{quantile=\"0.5\"} 40\n"));
+
+        let empty = LatencyStats::new().to_prometheus("empty");
+        prometheus_metric_names(&empty);
+        assert!(empty.contains("empty_count 0\n"));
+
+        let mut throughput = ThroughputTracker::new(Duration::from_secs(60));
+        throughput.record(7);
+        let text = throughput.to_prometheus("events");
+        assert_eq!(
+            prometheus_metric_names(&text),
+            vec!["events_window_events", "events_events_per_sec"]
+        );
+        assert!(text.contains("events_window_events 7\n"));
+    }
+
+    #[test]
+    fn take_snapshot_leaves_live_stats_empty() {
+        let mut stats = LatencyStats::new();
+        stats.record(100);
+        stats.record(5_000);
+
+        let snapshot = stats.take_snapshot();
+        assert_eq!(snapshot.count(), 2);
+        assert_eq!(snapshot.value_at_quantile(0.0), 100);
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.summary_string(), "count=0 p50=0 p95=0 p99=0 max=0");
+
+        // Auto-resize survives the reset, so large values are still recorded.
+        stats.record(u64::MAX / 4);
+        assert_eq!(stats.count(), 1);
+        assert_eq!(snapshot.count(), 2);
+
+        stats.reset();
+        assert_eq!(stats.count(), 0);
+    }
+
+    #[test]
+    fn tick_carries_partial_windows_for_slow_pollers() {
+        let mut tracker = ThroughputTracker::new(Duration::from_secs(1));
+        let start = tracker.origin.expect("wall-clock tracker");
+        let at = |secs: f64| start + Duration::from_secs_f64(secs);
+
+        assert_eq!(tracker.tick(at(0.5)), None);
+        // 100 events/sec, polled every 2.5 windows.
+        for poll in 1..=4 {
+            tracker.record(250);
+            let rate = tracker
+                .tick(at(2.5 * poll as f64))
+                .expect("completed window");
+            assert!((rate - 100.0).abs() < 1e-6, "poll {poll}: rate {rate}");
+        }
+        assert_eq!(tracker.tick(at(10.5)), None);
+        tracker.record(50);
+        let rate = tracker.tick(at(11.0)).expect("completed window");
+        assert!((rate - 50.0).abs() < 1e-6, "rate {rate}");
+    }
+
+    #[test]
+    fn virtual_clock_throughput_is_reproducible() {
+        let run = || {
+            let clock = VirtualClock::new();
+            let mut tracker =
+                ThroughputTracker::new_with_clock(Duration::from_secs(1), Rc::new(clock.clone()));
+            let mut rates = Vec::new();
+            for ts_ns in (0..5_000_000_000u64).step_by(2_000_000) {
+                clock.advance_to(ts_ns);
+                tracker.record(1);
+                if let Some(rate) = tracker.events_per_sec() {
+                    rates.push(rate);
+                }
+            }
+            rates
+        };
+
+        let first = run();
+        assert_eq!(first.len(), 4);
+        assert_eq!(first, run());
+        assert!(
+            first.iter().all(|rate| (rate - 500.0).abs() <= 1.0),
+            "{first:?}"
+        );
+    }
+
+    #[test]
+    fn tick_on_a_virtual_clock_uses_clock_time() {
+        let clock = VirtualClock::new();
+        clock.advance_to(5_000_000_000);
+        let mut tracker =
+            ThroughputTracker::new_with_clock(Duration::from_secs(1), Rc::new(clock.clone()));
+        tracker.record(100);
+        // A wall-clock instant far in the future must not complete any virtual window.
+        let wall_later = Instant::now() + Duration::from_secs(60);
+        assert_eq!(tracker.tick(wall_later), None);
+
+        clock.advance_to(6_000_000_000);
+        let rate = tracker.tick(Instant::now()).expect("completed window");
+        assert!((rate - 100.0).abs() < 1e-6, "rate {rate}");
+    }
+
     #[test]
     fn basic_recording_increments_count() {
         let mut stats = LatencyStats::new();
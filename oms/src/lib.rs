@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
-use lob_core::{Price, Qty};
+use lob_core::{Price, Qty, Side, SymbolId};
+use serde::{Deserialize, Serialize};
 use trading_types::{
-    ClientOrderId, ExecutionReport, Intent, OrderRequest as NewOrderRequest, OrderStatus, OrderType,
+    ClientOrderId, ExecutionReport, Intent, OrderRequest as NewOrderRequest, OrderStatus, OrderTag,
+    OrderType, TimeInForce,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderState {
     PendingNew,
     Live,
@@ -13,18 +15,20 @@ pub enum OrderState {
     Canceled,
     Filled,
     Rejected,
+    /// An IOC/FOK order whose unfilled remainder lapsed instead of resting.
+    Expired,
 }
 
 impl OrderState {
     fn is_terminal(self) -> bool {
         matches!(
             self,
-            OrderState::Canceled | OrderState::Filled | OrderState::Rejected
+            OrderState::Canceled | OrderState::Filled | OrderState::Rejected | OrderState::Expired
         )
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum OrderRequest {
     Place(NewOrderRequest),
     Cancel {
@@ -39,10 +43,18 @@ pub enum OrderRequest {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct OrderEntry {
+    symbol: SymbolId,
     state: OrderState,
     filled_qty: Qty,
+    placed_ts_ns: u64,
+    tif: TimeInForce,
+    #[serde(default)]
+    tag: Option<OrderTag>,
+    /// Side, working price and total qty of a limit order; `None` for market orders.
+    #[serde(default)]
+    quote: Option<(Side, Price, Qty)>,
 }
 
 fn zero_qty() -> Qty {
@@ -52,11 +64,19 @@ fn zero_qty() -> Qty {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Oms {
     next_id: u64,
     orders: HashMap<ClientOrderId, OrderEntry>,
     open_orders_count: usize,
+    open_orders_by_symbol: HashMap<SymbolId, usize>,
     orphan_reports: u64,
+    max_age_ns: Option<u64>,
+    /// IOC/FOK orders placed since the last `expire_immediate_orders`.
+    immediate_orders: Vec<ClientOrderId>,
+    /// Non-terminal limit orders per symbol, so quote lookups skip finished orders.
+    #[serde(default)]
+    open_quotes: HashMap<SymbolId, Vec<ClientOrderId>>,
 }
 
 impl Oms {
@@ -65,10 +85,68 @@ impl Oms {
             next_id: 1,
             orders: HashMap::new(),
             open_orders_count: 0,
+            open_orders_by_symbol: HashMap::new(),
             orphan_reports: 0,
+            max_age_ns: None,
+            immediate_orders: Vec::new(),
+            open_quotes: HashMap::new(),
         }
     }
 
+    /// Orders resting longer than `age_ns` since placement are canceled by `expire_stale`.
+    pub fn with_max_age_ns(mut self, age_ns: u64) -> Self {
+        self.max_age_ns = Some(age_ns);
+        self
+    }
+
+    fn allocate_id(&mut self) -> ClientOrderId {
+        let client_order_id = ClientOrderId(self.next_id);
+        self.next_id += 1;
+        client_order_id
+    }
+
+    /// Tracks a new order as open. Market orders never rest, so like IOC/FOK they are
+    /// expired by `expire_immediate_orders` if the venue leaves them open.
+    fn register_place(
+        &mut self,
+        request: NewOrderRequest,
+        tag: Option<OrderTag>,
+        ts_ns: u64,
+    ) -> OrderRequest {
+        let quote = request
+            .price
+            .map(|price| (request.side, price, request.qty));
+        if quote.is_some() {
+            self.open_quotes
+                .entry(request.symbol)
+                .or_default()
+                .push(request.client_order_id);
+        }
+        self.orders.insert(
+            request.client_order_id,
+            OrderEntry {
+                symbol: request.symbol,
+                state: OrderState::PendingNew,
+                filled_qty: zero_qty(),
+                placed_ts_ns: ts_ns,
+                tif: request.tif,
+                tag,
+                quote,
+            },
+        );
+        if request.tif != TimeInForce::Gtc || request.order_type == OrderType::Market {
+            self.immediate_orders.push(request.client_order_id);
+        }
+        self.open_orders_count = self.open_orders_count.saturating_add(1);
+        *self
+            .open_orders_by_symbol
+            .entry(request.symbol)
+            .or_default() += 1;
+        OrderRequest::Place(request)
+    }
+
+    /// `PlacePegged` carries no price yet and yields `None`; the engine resolves pegs into
+    /// `PlaceLimit` before they reach the OMS.
     pub fn apply_intent(&mut self, intent: Intent, ts_ns: u64) -> Option<OrderRequest> {
         match intent {
             Intent::PlaceLimit {
@@ -77,12 +155,10 @@ impl Oms {
                 price,
                 qty,
                 tif,
-                tag: _,
+                tag,
             } => {
-                let client_order_id = ClientOrderId(self.next_id);
-                self.next_id += 1;
                 let request = NewOrderRequest {
-                    client_order_id,
+                    client_order_id: self.allocate_id(),
                     symbol,
                     side,
                     order_type: OrderType::Limit,
@@ -90,15 +166,25 @@ impl Oms {
                     qty,
                     tif,
                 };
-                self.orders.insert(
-                    client_order_id,
-                    OrderEntry {
-                        state: OrderState::PendingNew,
-                        filled_qty: zero_qty(),
-                    },
-                );
-                self.open_orders_count = self.open_orders_count.saturating_add(1);
-                Some(OrderRequest::Place(request))
+                Some(self.register_place(request, tag, ts_ns))
+            }
+            Intent::PlaceMarket {
+                symbol,
+                side,
+                qty,
+                tif,
+                tag,
+            } => {
+                let request = NewOrderRequest {
+                    client_order_id: self.allocate_id(),
+                    symbol,
+                    side,
+                    order_type: OrderType::Market,
+                    price: None,
+                    qty,
+                    tif,
+                };
+                Some(self.register_place(request, tag, ts_ns))
             }
             Intent::Cancel { client_order_id } => {
                 if let Some(entry) = self.orders.get_mut(&client_order_id) {
@@ -120,6 +206,10 @@ impl Oms {
                 if let Some(entry) = self.orders.get_mut(&client_order_id) {
                     if !entry.state.is_terminal() {
                         entry.state = OrderState::PendingNew;
+                        if let Some((_, price, qty)) = entry.quote.as_mut() {
+                            *price = new_price;
+                            *qty = new_qty;
+                        }
                     }
                     return Some(OrderRequest::Replace {
                         client_order_id,
@@ -130,9 +220,82 @@ impl Oms {
                 }
                 None
             }
+            Intent::PlacePegged { .. } => None,
         }
     }
 
+    /// Cancels every non-terminal order, marking each `PendingCancel`; requests are in id order.
+    pub fn cancel_all(&mut self, ts_ns: u64) -> Vec<OrderRequest> {
+        self.cancel_matching(ts_ns, |_| true)
+    }
+
+    /// Like `cancel_all`, restricted to orders for `symbol`.
+    pub fn cancel_by_symbol(&mut self, symbol: SymbolId, ts_ns: u64) -> Vec<OrderRequest> {
+        self.cancel_matching(ts_ns, |entry| entry.symbol == symbol)
+    }
+
+    /// Marks IOC/FOK orders still live after their submission step as `Expired`.
+    /// Call once the venue's reports for a submission have been applied; orders with no
+    /// report yet stay pending until a later call.
+    pub fn expire_immediate_orders(&mut self) {
+        let mut pending = std::mem::take(&mut self.immediate_orders);
+        pending.retain(|client_order_id| {
+            let Some(entry) = self.orders.get_mut(client_order_id) else {
+                return false;
+            };
+            match entry.state {
+                OrderState::PendingNew => true,
+                OrderState::Live | OrderState::PendingCancel => {
+                    entry.state = OrderState::Expired;
+                    self.open_orders_count = self.open_orders_count.saturating_sub(1);
+                    let symbol_open = self.open_orders_by_symbol.entry(entry.symbol).or_default();
+                    *symbol_open = symbol_open.saturating_sub(1);
+                    if let Some(ids) = self.open_quotes.get_mut(&entry.symbol) {
+                        ids.retain(|id| id != client_order_id);
+                    }
+                    false
+                }
+                _ => false,
+            }
+        });
+        self.immediate_orders = pending;
+    }
+
+    /// Cancels open orders placed more than the configured max age before `now_ns`.
+    /// Orders already pending cancel are skipped; no-op without `with_max_age_ns`.
+    pub fn expire_stale(&mut self, now_ns: u64) -> Vec<OrderRequest> {
+        let Some(max_age_ns) = self.max_age_ns else {
+            return Vec::new();
+        };
+        self.cancel_matching(now_ns, |entry| {
+            entry.state != OrderState::PendingCancel
+                && now_ns.saturating_sub(entry.placed_ts_ns) > max_age_ns
+        })
+    }
+
+    fn cancel_matching(
+        &mut self,
+        ts_ns: u64,
+        matches: impl Fn(&OrderEntry) -> bool,
+    ) -> Vec<OrderRequest> {
+        let mut ids: Vec<ClientOrderId> = self
+            .orders
+            .iter_mut()
+            .filter(|(_, entry)| !entry.state.is_terminal() && matches(entry))
+            .map(|(&id, entry)| {
+                entry.state = OrderState::PendingCancel;
+                id
+            })
+            .collect();
+        ids.sort_by_key(|id| id.0);
+        ids.into_iter()
+            .map(|client_order_id| OrderRequest::Cancel {
+                client_order_id,
+                ts_ns,
+            })
+            .collect()
+    }
+
     pub fn on_execution_report(&mut self, report: &ExecutionReport) {
         let Some(entry) = self.orders.get_mut(&report.client_order_id) else {
             self.orphan_reports += 1;
@@ -158,10 +321,22 @@ impl Oms {
 
         let was_open = !entry.state.is_terminal();
         let is_open = !new_state.is_terminal();
+        let symbol_open = self.open_orders_by_symbol.entry(entry.symbol).or_default();
         if was_open && !is_open {
             self.open_orders_count = self.open_orders_count.saturating_sub(1);
+            *symbol_open = symbol_open.saturating_sub(1);
+            if let Some(ids) = self.open_quotes.get_mut(&entry.symbol) {
+                ids.retain(|id| *id != report.client_order_id);
+            }
         } else if !was_open && is_open {
             self.open_orders_count = self.open_orders_count.saturating_add(1);
+            *symbol_open = symbol_open.saturating_add(1);
+            if entry.quote.is_some() {
+                self.open_quotes
+                    .entry(entry.symbol)
+                    .or_default()
+                    .push(report.client_order_id);
+            }
         }
 
         entry.filled_qty = report.filled_qty;
@@ -176,11 +351,67 @@ impl Oms {
         self.open_orders_count
     }
 
-    #[cfg(test)]
-    fn order_state(&self, client_order_id: ClientOrderId) -> Option<OrderState> {
+    pub fn open_orders_for(&self, symbol: SymbolId) -> usize {
+        self.open_orders_by_symbol
+            .get(&symbol)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn order_state(&self, client_order_id: ClientOrderId) -> Option<OrderState> {
         self.orders.get(&client_order_id).map(|entry| entry.state)
     }
 
+    pub fn order_symbol(&self, client_order_id: ClientOrderId) -> Option<SymbolId> {
+        self.orders.get(&client_order_id).map(|entry| entry.symbol)
+    }
+
+    /// Tag the order was placed with, if any.
+    pub fn order_tag(&self, client_order_id: ClientOrderId) -> Option<&OrderTag> {
+        self.orders
+            .get(&client_order_id)
+            .and_then(|entry| entry.tag.as_ref())
+    }
+
+    /// The best-priced open limit order on `side` of `symbol` as `(id, price, unfilled
+    /// qty)`; ties go to the lower id. Prices include replaces not yet acknowledged.
+    pub fn best_open_order(
+        &self,
+        symbol: SymbolId,
+        side: Side,
+    ) -> Option<(ClientOrderId, Price, Qty)> {
+        let ids = self.open_quotes.get(&symbol)?;
+        ids.iter()
+            .filter_map(|id| {
+                let entry = self.orders.get(id)?;
+                let (order_side, price, qty) = entry.quote?;
+                if order_side != side || entry.state.is_terminal() {
+                    return None;
+                }
+                let unfilled = Qty::new((qty.lots() - entry.filled_qty.lots()).max(0)).ok()?;
+                Some((*id, price, unfilled))
+            })
+            .min_by_key(|(id, price, _)| {
+                let priority = match side {
+                    Side::Bid => -price.ticks(),
+                    Side::Ask => price.ticks(),
+                };
+                (priority, id.0)
+            })
+    }
+
+    /// Non-terminal orders as `(id, state, filled_qty)`, in client order id order.
+    pub fn iter_open(&self) -> impl Iterator<Item = (ClientOrderId, OrderState, Qty)> {
+        let mut open: Vec<_> = self
+            .orders
+            .iter()
+            .filter(|(_, entry)| !entry.state.is_terminal())
+            .map(|(&id, entry)| (id, entry.state, entry.filled_qty))
+            .collect();
+        open.sort_by_key(|(id, _, _)| id.0);
+        open.into_iter()
+    }
+
     #[cfg(test)]
     fn filled_qty(&self, client_order_id: ClientOrderId) -> Option<Qty> {
         self.orders
@@ -201,7 +432,8 @@ fn map_status(status: OrderStatus) -> OrderState {
         OrderStatus::Accepted | OrderStatus::Working | OrderStatus::PartiallyFilled => {
             OrderState::Live
         }
-        OrderStatus::Canceled | OrderStatus::Expired => OrderState::Canceled,
+        OrderStatus::Canceled => OrderState::Canceled,
+        OrderStatus::Expired => OrderState::Expired,
         OrderStatus::Filled => OrderState::Filled,
         OrderStatus::Rejected => OrderState::Rejected,
     }
@@ -230,9 +462,72 @@ mod tests {
             ts_ns,
             symbol,
             side,
+            reason: None,
+            tag: None,
         }
     }
 
+    #[test]
+    fn best_open_order_tracks_fills_and_replaces() {
+        let mut oms = Oms::new();
+        let symbol = SymbolId::from_u32(1);
+        for (price, qty) in [(100, 5), (101, 2)] {
+            oms.apply_intent(
+                Intent::PlaceLimit {
+                    symbol,
+                    side: Side::Bid,
+                    price: Price::new(price).unwrap(),
+                    qty: Qty::new(qty).unwrap(),
+                    tif: TimeInForce::Gtc,
+                    tag: None,
+                },
+                1,
+            );
+        }
+        let (low, high) = (ClientOrderId(1), ClientOrderId(2));
+        let best = |oms: &Oms| oms.best_open_order(symbol, Side::Bid);
+
+        oms.on_execution_report(&build_report(
+            high,
+            symbol,
+            Side::Bid,
+            OrderStatus::PartiallyFilled,
+            1,
+            2,
+        ));
+        assert_eq!(
+            best(&oms),
+            Some((high, Price::new(101).unwrap(), Qty::new(1).unwrap()))
+        );
+        assert_eq!(oms.best_open_order(symbol, Side::Ask), None);
+
+        oms.on_execution_report(&build_report(
+            high,
+            symbol,
+            Side::Bid,
+            OrderStatus::Filled,
+            2,
+            3,
+        ));
+        assert_eq!(
+            best(&oms),
+            Some((low, Price::new(100).unwrap(), Qty::new(5).unwrap()))
+        );
+
+        oms.apply_intent(
+            Intent::Replace {
+                client_order_id: low,
+                new_price: Price::new(99).unwrap(),
+                new_qty: Qty::new(4).unwrap(),
+            },
+            4,
+        );
+        assert_eq!(
+            best(&oms),
+            Some((low, Price::new(99).unwrap(), Qty::new(4).unwrap()))
+        );
+    }
+
     #[test]
     fn new_ack_fill_flow() {
         let mut oms = Oms::new();
@@ -278,6 +573,219 @@ mod tests {
         assert_eq!(oms.open_orders(), 0);
     }
 
+    #[test]
+    fn iter_open_lists_non_terminal_orders_in_id_order() {
+        let mut oms = Oms::new();
+        let symbol = SymbolId::from_u32(1);
+        let place = || Intent::PlaceLimit {
+            symbol,
+            side: Side::Bid,
+            price: Price::new(100).unwrap(),
+            qty: Qty::new(2).unwrap(),
+            tif: TimeInForce::Gtc,
+            tag: None,
+        };
+        let mut ids = Vec::new();
+        for ts_ns in 1..=3 {
+            let Some(OrderRequest::Place(order)) = oms.apply_intent(place(), ts_ns) else {
+                panic!("expected place request");
+            };
+            ids.push(order.client_order_id);
+        }
+
+        for (id, status, filled) in [
+            (ids[0], OrderStatus::PartiallyFilled, 1),
+            (ids[1], OrderStatus::Filled, 2),
+        ] {
+            oms.on_execution_report(&build_report(id, symbol, Side::Bid, status, filled, 4));
+        }
+
+        let open: Vec<_> = oms.iter_open().collect();
+        assert_eq!(
+            open,
+            vec![
+                (ids[0], OrderState::Live, Qty::new(1).unwrap()),
+                (ids[2], OrderState::PendingNew, Qty::new(0).unwrap()),
+            ]
+        );
+        assert_eq!(oms.order_state(ids[1]), Some(OrderState::Filled));
+        assert_eq!(oms.order_state(ClientOrderId(99)), None);
+    }
+
+    #[test]
+    fn cancel_all_and_by_symbol_skip_terminal_orders() {
+        let mut oms = Oms::new();
+        let btc = SymbolId::from_u32(1);
+        let eth = SymbolId::from_u32(2);
+        let mut ids = Vec::new();
+        for (ts_ns, symbol) in [(1, btc), (2, btc), (3, eth), (4, eth)] {
+            let intent = Intent::PlaceLimit {
+                symbol,
+                side: Side::Bid,
+                price: Price::new(100).unwrap(),
+                qty: Qty::new(1).unwrap(),
+                tif: TimeInForce::Gtc,
+                tag: None,
+            };
+            let Some(OrderRequest::Place(order)) = oms.apply_intent(intent, ts_ns) else {
+                panic!("expected place request");
+            };
+            ids.push(order.client_order_id);
+        }
+        oms.on_execution_report(&build_report(
+            ids[1],
+            btc,
+            Side::Bid,
+            OrderStatus::Filled,
+            1,
+            5,
+        ));
+
+        let cancel = |client_order_id| OrderRequest::Cancel {
+            client_order_id,
+            ts_ns: 6,
+        };
+        assert_eq!(
+            oms.cancel_by_symbol(eth, 6),
+            vec![cancel(ids[2]), cancel(ids[3])]
+        );
+        assert_eq!(oms.order_state(ids[0]), Some(OrderState::PendingNew));
+        assert_eq!(oms.order_state(ids[2]), Some(OrderState::PendingCancel));
+
+        let cancel = |client_order_id| OrderRequest::Cancel {
+            client_order_id,
+            ts_ns: 7,
+        };
+        assert_eq!(
+            oms.cancel_all(7),
+            vec![cancel(ids[0]), cancel(ids[2]), cancel(ids[3])]
+        );
+        assert_eq!(oms.order_state(ids[0]), Some(OrderState::PendingCancel));
+        assert_eq!(oms.order_state(ids[1]), Some(OrderState::Filled));
+
+        oms.on_execution_report(&build_report(
+            ids[0],
+            btc,
+            Side::Bid,
+            OrderStatus::Canceled,
+            0,
+            8,
+        ));
+        assert_eq!(oms.cancel_all(9).len(), 2);
+    }
+
+    #[test]
+    fn expire_stale_cancels_orders_past_max_age_once() {
+        let mut oms = Oms::new().with_max_age_ns(100);
+        let symbol = SymbolId::from_u32(1);
+        let place = || Intent::PlaceLimit {
+            symbol,
+            side: Side::Ask,
+            price: Price::new(101).unwrap(),
+            qty: Qty::new(1).unwrap(),
+            tif: TimeInForce::Gtc,
+            tag: None,
+        };
+        let Some(OrderRequest::Place(old)) = oms.apply_intent(place(), 10) else {
+            panic!("expected place request");
+        };
+        oms.on_execution_report(&build_report(
+            old.client_order_id,
+            symbol,
+            Side::Ask,
+            OrderStatus::Accepted,
+            0,
+            11,
+        ));
+        let Some(OrderRequest::Place(young)) = oms.apply_intent(place(), 60) else {
+            panic!("expected place request");
+        };
+
+        assert!(oms.expire_stale(110).is_empty());
+        assert_eq!(
+            oms.expire_stale(111),
+            vec![OrderRequest::Cancel {
+                client_order_id: old.client_order_id,
+                ts_ns: 111,
+            }]
+        );
+        assert_eq!(
+            oms.order_state(old.client_order_id),
+            Some(OrderState::PendingCancel)
+        );
+        assert_eq!(
+            oms.expire_stale(200),
+            vec![OrderRequest::Cancel {
+                client_order_id: young.client_order_id,
+                ts_ns: 200,
+            }]
+        );
+        assert!(Oms::new().expire_stale(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn resting_ioc_order_expires_and_is_not_open() {
+        let mut oms = Oms::new();
+        let symbol = SymbolId::from_u32(1);
+        let place = |tif| Intent::PlaceLimit {
+            symbol,
+            side: Side::Bid,
+            price: Price::new(99).unwrap(),
+            qty: Qty::new(1).unwrap(),
+            tif,
+            tag: None,
+        };
+        let Some(OrderRequest::Place(ioc)) = oms.apply_intent(place(TimeInForce::Ioc), 1) else {
+            panic!("expected place request");
+        };
+        let Some(OrderRequest::Place(gtc)) = oms.apply_intent(place(TimeInForce::Gtc), 1) else {
+            panic!("expected place request");
+        };
+        for id in [ioc.client_order_id, gtc.client_order_id] {
+            oms.on_execution_report(&build_report(
+                id,
+                symbol,
+                Side::Bid,
+                OrderStatus::Accepted,
+                0,
+                2,
+            ));
+        }
+        assert_eq!(oms.open_orders(), 2);
+
+        oms.expire_immediate_orders();
+        assert_eq!(
+            oms.order_state(ioc.client_order_id),
+            Some(OrderState::Expired)
+        );
+        assert_eq!(oms.order_state(gtc.client_order_id), Some(OrderState::Live));
+        assert_eq!(oms.open_orders(), 1);
+        assert_eq!(oms.open_orders_for(symbol), 1);
+
+        let Some(OrderRequest::Place(pending)) = oms.apply_intent(place(TimeInForce::Fok), 3)
+        else {
+            panic!("expected place request");
+        };
+        oms.expire_immediate_orders();
+        assert_eq!(
+            oms.order_state(pending.client_order_id),
+            Some(OrderState::PendingNew)
+        );
+        oms.on_execution_report(&build_report(
+            pending.client_order_id,
+            symbol,
+            Side::Bid,
+            OrderStatus::Expired,
+            0,
+            4,
+        ));
+        assert_eq!(
+            oms.order_state(pending.client_order_id),
+            Some(OrderState::Expired)
+        );
+        assert_eq!(oms.open_orders(), 1);
+    }
+
     #[test]
     fn cancel_flow() {
         let mut oms = Oms::new();
@@ -324,6 +832,43 @@ mod tests {
         ));
         assert_eq!(oms.order_state(id), Some(OrderState::Canceled));
         assert_eq!(oms.open_orders(), 0);
+        assert_eq!(oms.open_orders_for(symbol), 0);
+    }
+
+    #[test]
+    fn open_orders_are_counted_per_symbol() {
+        let mut oms = Oms::new();
+        let btc = SymbolId::from_u32(1);
+        let eth = SymbolId::from_u32(2);
+        let place = |symbol| Intent::PlaceLimit {
+            symbol,
+            side: Side::Bid,
+            price: Price::new(100).unwrap(),
+            qty: Qty::new(1).unwrap(),
+            tif: TimeInForce::Gtc,
+            tag: None,
+        };
+
+        let OrderRequest::Place(first) = oms.apply_intent(place(btc), 1).unwrap() else {
+            panic!("expected place request");
+        };
+        oms.apply_intent(place(btc), 1).unwrap();
+        oms.apply_intent(place(eth), 1).unwrap();
+        assert_eq!(oms.open_orders(), 3);
+        assert_eq!(oms.open_orders_for(btc), 2);
+        assert_eq!(oms.open_orders_for(eth), 1);
+
+        oms.on_execution_report(&build_report(
+            first.client_order_id,
+            btc,
+            Side::Bid,
+            OrderStatus::Filled,
+            1,
+            2,
+        ));
+        assert_eq!(oms.open_orders(), 2);
+        assert_eq!(oms.open_orders_for(btc), 1);
+        assert_eq!(oms.open_orders_for(SymbolId::from_u32(3)), 0);
     }
 
     #[test]
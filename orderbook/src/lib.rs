@@ -1,16 +1,47 @@
-use std::collections::BTreeMap;
-
-use lob_core::{MarketEvent, Price, Qty, Side, SymbolId};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use lob_core::{LevelUpdate, MarketEvent, Price, Qty, Side, SymbolId};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Why [`OrderBook::apply_checked`] refused an event; the book is left unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ApplyReject {
+    #[error("event symbol {actual} does not match book symbol {expected}", actual = .actual.as_u32(), expected = .expected.as_u32())]
+    SymbolMismatch {
+        expected: SymbolId,
+        actual: SymbolId,
+    },
+    #[error("event would leave the book crossed")]
+    Crossed,
+    #[error("delta carries no level updates")]
+    EmptyUpdates,
+}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBook {
     symbol: SymbolId,
     bids: BTreeMap<Price, Qty>,
     asks: BTreeMap<Price, Qty>,
     best_bid_cache: Option<(Price, Qty)>,
     best_ask_cache: Option<(Price, Qty)>,
+    last_ts_ns: u64,
+    #[serde(default)]
+    reject_crossed: bool,
+    #[serde(default)]
+    reject_empty_updates: bool,
 }
 
+/// Books compare by symbol and levels; the last event timestamp is not book state.
+impl PartialEq for OrderBook {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol && self.bids == other.bids && self.asks == other.asks
+    }
+}
+
+impl Eq for OrderBook {}
+
 impl OrderBook {
     pub fn new(symbol: SymbolId) -> Self {
         Self {
@@ -19,6 +50,40 @@ impl OrderBook {
             asks: BTreeMap::new(),
             best_bid_cache: None,
             best_ask_cache: None,
+            last_ts_ns: 0,
+            reject_crossed: false,
+            reject_empty_updates: false,
+        }
+    }
+
+    /// Rejects events that would leave the best bid at or above the best ask.
+    pub fn with_reject_crossed(mut self, reject: bool) -> Self {
+        self.reject_crossed = reject;
+        self
+    }
+
+    /// Rejects deltas without level updates instead of treating them as heartbeats.
+    pub fn with_reject_empty_updates(mut self, reject: bool) -> Self {
+        self.reject_empty_updates = reject;
+        self
+    }
+
+    /// Builds a book from an `L2Snapshot` for `symbol`; returns `None` for any other event.
+    pub fn from_snapshot(symbol: SymbolId, event: &MarketEvent) -> Option<Self> {
+        if !matches!(event, MarketEvent::L2Snapshot { .. }) {
+            return None;
+        }
+        let mut book = Self::new(symbol);
+        book.apply(event).then_some(book)
+    }
+
+    /// Materializes the current levels as an `L2Snapshot`, bids descending and asks ascending.
+    pub fn snapshot(&self, ts_ns: u64) -> MarketEvent {
+        MarketEvent::L2Snapshot {
+            ts_ns,
+            symbol: self.symbol,
+            bids: self.bid_levels().collect(),
+            asks: self.ask_levels().collect(),
         }
     }
 
@@ -66,33 +131,51 @@ impl OrderBook {
     }
 
     pub fn apply(&mut self, event: &MarketEvent) -> bool {
+        self.apply_checked(event).is_ok()
+    }
+
+    /// Applies `event`, or reports why it was rejected without touching the book.
+    pub fn apply_checked(&mut self, event: &MarketEvent) -> Result<(), ApplyReject> {
+        let symbol = event.symbol();
+        if symbol != self.symbol {
+            return Err(ApplyReject::SymbolMismatch {
+                expected: self.symbol,
+                actual: symbol,
+            });
+        }
+
         match event {
-            MarketEvent::L2Delta {
-                symbol, updates, ..
-            } => {
-                if symbol != &self.symbol {
-                    return false;
+            MarketEvent::L2Delta { updates, .. } => {
+                if self.reject_empty_updates && updates.is_empty() {
+                    return Err(ApplyReject::EmptyUpdates);
                 }
-
-                for update in updates {
-                    match update.side {
-                        Side::Bid => self.apply_bid_update(update.price, update.qty),
-                        Side::Ask => self.apply_ask_update(update.price, update.qty),
+                if self.reject_crossed {
+                    let previous: Vec<_> = updates
+                        .iter()
+                        .map(|update| {
+                            (
+                                update.side,
+                                update.price,
+                                self.level_qty(update.side, update.price),
+                            )
+                        })
+                        .collect();
+                    self.apply_updates(updates);
+                    if self.is_crossed() {
+                        self.restore_levels(&previous);
+                        return Err(ApplyReject::Crossed);
                     }
+                } else {
+                    self.apply_updates(updates);
                 }
-                true
             }
-            MarketEvent::L2Snapshot {
-                symbol, bids, asks, ..
-            } => {
-                if symbol != &self.symbol {
-                    return false;
+            MarketEvent::L2Snapshot { bids, asks, .. } => {
+                if self.reject_crossed && snapshot_crosses(bids, asks) {
+                    return Err(ApplyReject::Crossed);
                 }
 
                 self.bids.clear();
                 self.asks.clear();
-                self.best_bid_cache = None;
-                self.best_ask_cache = None;
 
                 for (price, qty) in bids.iter().copied() {
                     if !qty.is_zero() {
@@ -107,12 +190,43 @@ impl OrderBook {
                 }
 
                 self.refresh_best_levels();
+            }
+            MarketEvent::Trade { .. } => {}
+        }
+
+        self.last_ts_ns = event.ts_ns();
+        Ok(())
+    }
 
-                true
+    fn apply_updates(&mut self, updates: &[LevelUpdate]) {
+        for update in updates {
+            match update.side {
+                Side::Bid => self.apply_bid_update(update.price, update.qty),
+                Side::Ask => self.apply_ask_update(update.price, update.qty),
             }
         }
     }
 
+    /// Puts levels back in reverse order so repeated prices end at their oldest value.
+    fn restore_levels(&mut self, previous: &[(Side, Price, Option<Qty>)]) {
+        for (side, price, qty) in previous.iter().rev() {
+            let levels = match side {
+                Side::Bid => &mut self.bids,
+                Side::Ask => &mut self.asks,
+            };
+            match qty {
+                Some(qty) => levels.insert(*price, *qty),
+                None => levels.remove(price),
+            };
+        }
+        self.refresh_best_levels();
+    }
+
+    /// Timestamp of the last event applied to this book, or 0 before any event.
+    pub fn last_ts_ns(&self) -> u64 {
+        self.last_ts_ns
+    }
+
     pub fn best_bid(&self) -> Option<(Price, Qty)> {
         self.best_bid_cache
     }
@@ -121,6 +235,64 @@ impl OrderBook {
         self.best_ask_cache
     }
 
+    pub fn level_qty(&self, side: Side, price: Price) -> Option<Qty> {
+        match side {
+            Side::Bid => self.bids.get(&price).copied(),
+            Side::Ask => self.asks.get(&price).copied(),
+        }
+    }
+
+    /// Bid levels from best (highest) to worst.
+    pub fn bid_levels(&self) -> impl Iterator<Item = (Price, Qty)> + '_ {
+        self.bids.iter().rev().map(|(p, q)| (*p, *q))
+    }
+
+    /// Ask levels from best (lowest) to worst.
+    pub fn ask_levels(&self) -> impl Iterator<Item = (Price, Qty)> + '_ {
+        self.asks.iter().map(|(p, q)| (*p, *q))
+    }
+
+    /// Up to `levels` levels of `side`, best first.
+    pub fn depth(&self, side: Side, levels: usize) -> Vec<(Price, Qty)> {
+        match side {
+            Side::Bid => self.bid_levels().take(levels).collect(),
+            Side::Ask => self.ask_levels().take(levels).collect(),
+        }
+    }
+
+    /// True when the best bid is at or above the best ask.
+    pub fn is_crossed(&self) -> bool {
+        match (self.best_bid(), self.best_ask()) {
+            (Some((bid, _)), Some((ask, _))) => bid.ticks() >= ask.ticks(),
+            _ => false,
+        }
+    }
+
+    /// CRC32 over the top `depth` levels, in the OKX/Kraken style used to detect desync.
+    ///
+    /// Levels are interleaved best-first as `bid_price:bid_qty:ask_price:ask_qty:...`
+    /// using integer ticks and lots, joined by `:` with no trailing separator. When one
+    /// side runs out of levels the other side's remaining levels follow on their own.
+    pub fn crc_checksum(&self, depth: usize) -> u32 {
+        let mut canonical = String::new();
+        let mut bids = self.bid_levels().take(depth);
+        let mut asks = self.ask_levels().take(depth);
+        loop {
+            let bid = bids.next();
+            let ask = asks.next();
+            if bid.is_none() && ask.is_none() {
+                break;
+            }
+            for (price, qty) in bid.into_iter().chain(ask) {
+                if !canonical.is_empty() {
+                    canonical.push(':');
+                }
+                let _ = write!(canonical, "{}:{}", price.ticks(), qty.lots());
+            }
+        }
+        crc32fast::hash(canonical.as_bytes())
+    }
+
     pub fn spread(&self) -> Option<Price> {
         let (ask, _) = self.best_ask()?;
         let (bid, _) = self.best_bid()?;
@@ -134,6 +306,50 @@ impl OrderBook {
     }
 }
 
+fn snapshot_crosses(bids: &[(Price, Qty)], asks: &[(Price, Qty)]) -> bool {
+    let best_bid = bids
+        .iter()
+        .filter(|(_, qty)| !qty.is_zero())
+        .map(|(price, _)| *price)
+        .max();
+    let best_ask = asks
+        .iter()
+        .filter(|(_, qty)| !qty.is_zero())
+        .map(|(price, _)| *price)
+        .min();
+    matches!((best_bid, best_ask), (Some(bid), Some(ask)) if bid >= ask)
+}
+
+/// Routes events to per-symbol books keyed by the event's own symbol.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MultiBook {
+    books: HashMap<SymbolId, OrderBook>,
+}
+
+impl MultiBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies `event` to the book for its symbol, creating the book on first sight.
+    pub fn apply(&mut self, event: &MarketEvent) -> bool {
+        let symbol = event.symbol();
+        self.books
+            .entry(symbol)
+            .or_insert_with(|| OrderBook::new(symbol))
+            .apply(event)
+    }
+
+    pub fn book(&self, symbol: SymbolId) -> Option<&OrderBook> {
+        self.books.get(&symbol)
+    }
+
+    /// Symbols with a book, in unspecified order.
+    pub fn symbols(&self) -> impl Iterator<Item = SymbolId> + '_ {
+        self.books.keys().copied()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
@@ -288,6 +504,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn snapshot_round_trips_into_fresh_book() {
+        let symbol = SymbolId::from_u32(6);
+        let mut book = OrderBook::new(symbol);
+
+        assert!(book.apply(&delta(
+            symbol,
+            vec![
+                LevelUpdate {
+                    side: Side::Bid,
+                    price: Price::new(99).unwrap(),
+                    qty: Qty::new(2).unwrap(),
+                },
+                LevelUpdate {
+                    side: Side::Bid,
+                    price: Price::new(100).unwrap(),
+                    qty: Qty::new(1).unwrap(),
+                },
+                LevelUpdate {
+                    side: Side::Ask,
+                    price: Price::new(102).unwrap(),
+                    qty: Qty::new(4).unwrap(),
+                },
+                LevelUpdate {
+                    side: Side::Ask,
+                    price: Price::new(101).unwrap(),
+                    qty: Qty::new(3).unwrap(),
+                },
+            ],
+        )));
+
+        let snapshot = book.snapshot(42);
+        assert_eq!(
+            snapshot,
+            MarketEvent::L2Snapshot {
+                ts_ns: 42,
+                symbol,
+                bids: vec![
+                    (Price::new(100).unwrap(), Qty::new(1).unwrap()),
+                    (Price::new(99).unwrap(), Qty::new(2).unwrap()),
+                ],
+                asks: vec![
+                    (Price::new(101).unwrap(), Qty::new(3).unwrap()),
+                    (Price::new(102).unwrap(), Qty::new(4).unwrap()),
+                ],
+            }
+        );
+
+        let restored = OrderBook::from_snapshot(symbol, &snapshot).unwrap();
+        assert_eq!(restored, book);
+        assert!(OrderBook::from_snapshot(SymbolId::from_u32(7), &snapshot).is_none());
+        assert!(OrderBook::from_snapshot(symbol, &delta(symbol, vec![])).is_none());
+    }
+
+    #[test]
+    fn trade_events_leave_levels_unchanged() {
+        let symbol = SymbolId::from_u32(8);
+        let mut book = OrderBook::new(symbol);
+        assert!(book.apply(&delta(
+            symbol,
+            vec![LevelUpdate {
+                side: Side::Ask,
+                price: Price::new(101).unwrap(),
+                qty: Qty::new(3).unwrap(),
+            }],
+        )));
+        let before = book.clone();
+
+        let trade = |symbol| MarketEvent::Trade {
+            ts_ns: 2,
+            symbol,
+            price: Price::new(101).unwrap(),
+            qty: Qty::new(2).unwrap(),
+            aggressor_side: Side::Bid,
+        };
+        assert!(book.apply(&trade(symbol)));
+        assert!(!book.apply(&trade(SymbolId::from_u32(9))));
+        assert_eq!(book, before);
+    }
+
     #[test]
     fn best_bid_less_than_best_ask_invariant() {
         let symbol = SymbolId::from_u32(4);
@@ -312,6 +608,184 @@ mod tests {
         let (bid, _) = book.best_bid().unwrap();
         let (ask, _) = book.best_ask().unwrap();
         assert!(bid.ticks() < ask.ticks());
+        assert!(!book.is_crossed());
+
+        assert!(book.apply(&delta(
+            symbol,
+            vec![LevelUpdate {
+                side: Side::Bid,
+                price: Price::new(101).unwrap(),
+                qty: Qty::new(1).unwrap(),
+            }],
+        )));
+        assert!(book.is_crossed());
+    }
+
+    fn level(side: Side, price: i64, qty: i64) -> LevelUpdate {
+        LevelUpdate {
+            side,
+            price: Price::new(price).unwrap(),
+            qty: Qty::new(qty).unwrap(),
+        }
+    }
+
+    #[test]
+    fn apply_checked_rejects_symbol_mismatch() {
+        let symbol = SymbolId::from_u32(1);
+        let other = SymbolId::from_u32(2);
+        let mut book = OrderBook::new(symbol);
+
+        assert_eq!(
+            book.apply_checked(&delta(other, vec![level(Side::Bid, 100, 1)])),
+            Err(ApplyReject::SymbolMismatch {
+                expected: symbol,
+                actual: other,
+            })
+        );
+        assert!(!book.apply(&delta(other, vec![level(Side::Bid, 100, 1)])));
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.last_ts_ns(), 0);
+    }
+
+    #[test]
+    fn apply_checked_rejects_empty_updates_when_enabled() {
+        let symbol = SymbolId::from_u32(1);
+        let mut lenient = OrderBook::new(symbol);
+        assert_eq!(lenient.apply_checked(&delta(symbol, vec![])), Ok(()));
+
+        let mut strict = OrderBook::new(symbol).with_reject_empty_updates(true);
+        assert_eq!(
+            strict.apply_checked(&delta(symbol, vec![])),
+            Err(ApplyReject::EmptyUpdates)
+        );
+        assert_eq!(strict.last_ts_ns(), 0);
+    }
+
+    #[test]
+    fn apply_checked_rolls_back_crossing_delta() {
+        let symbol = SymbolId::from_u32(1);
+        let mut book = OrderBook::new(symbol).with_reject_crossed(true);
+        book.apply_checked(&delta(
+            symbol,
+            vec![level(Side::Bid, 99, 1), level(Side::Ask, 101, 2)],
+        ))
+        .unwrap();
+        let before = book.clone();
+
+        assert_eq!(
+            book.apply_checked(&delta(
+                symbol,
+                vec![
+                    level(Side::Bid, 99, 0),
+                    level(Side::Bid, 100, 3),
+                    level(Side::Bid, 100, 4),
+                    level(Side::Bid, 101, 1),
+                ],
+            )),
+            Err(ApplyReject::Crossed)
+        );
+        assert_eq!(book, before);
+        assert_eq!(
+            book.best_bid(),
+            Some((Price::new(99).unwrap(), Qty::new(1).unwrap()))
+        );
+        assert_eq!(
+            book.best_ask(),
+            Some((Price::new(101).unwrap(), Qty::new(2).unwrap()))
+        );
+    }
+
+    #[test]
+    fn apply_checked_rejects_crossed_snapshot() {
+        let symbol = SymbolId::from_u32(1);
+        let mut book = OrderBook::new(symbol).with_reject_crossed(true);
+        book.apply_checked(&delta(symbol, vec![level(Side::Bid, 99, 1)]))
+            .unwrap();
+
+        let crossed = MarketEvent::L2Snapshot {
+            ts_ns: 2,
+            symbol,
+            bids: vec![(Price::new(102).unwrap(), Qty::new(1).unwrap())],
+            asks: vec![
+                (Price::new(100).unwrap(), Qty::new(0).unwrap()),
+                (Price::new(101).unwrap(), Qty::new(1).unwrap()),
+            ],
+        };
+        assert_eq!(book.apply_checked(&crossed), Err(ApplyReject::Crossed));
+        assert_eq!(
+            book.best_bid(),
+            Some((Price::new(99).unwrap(), Qty::new(1).unwrap()))
+        );
+        assert_eq!(book.best_ask(), None);
+    }
+
+    #[test]
+    fn crc_checksum_interleaves_top_levels() {
+        let symbol = SymbolId::from_u32(1);
+        let mut book = OrderBook::new(symbol);
+        book.apply(&delta(
+            symbol,
+            vec![
+                level(Side::Bid, 100, 3),
+                level(Side::Bid, 99, 1),
+                level(Side::Ask, 101, 2),
+                level(Side::Ask, 103, 5),
+            ],
+        ));
+
+        // crc32("100:3:101:2:99:1:103:5")
+        assert_eq!(book.crc_checksum(2), 1_480_526_026);
+        assert_eq!(book.crc_checksum(25), 1_480_526_026);
+        // crc32("100:3:101:2")
+        assert_eq!(book.crc_checksum(1), 1_216_076_450);
+        assert_eq!(book.crc_checksum(0), 0);
+
+        book.apply(&delta(symbol, vec![level(Side::Ask, 103, 0)]));
+        // crc32("100:3:101:2:99:1")
+        assert_eq!(book.crc_checksum(2), 3_696_703_482);
+    }
+
+    #[test]
+    fn multibook_routes_by_event_symbol() {
+        let btc = SymbolId::from_u32(0);
+        let eth = SymbolId::from_u32(1);
+        let mut books = MultiBook::new();
+
+        assert!(books.apply(&delta(
+            btc,
+            vec![LevelUpdate {
+                side: Side::Bid,
+                price: Price::new(100).unwrap(),
+                qty: Qty::new(1).unwrap(),
+            }],
+        )));
+        assert!(books.apply(&delta(
+            eth,
+            vec![LevelUpdate {
+                side: Side::Ask,
+                price: Price::new(200).unwrap(),
+                qty: Qty::new(2).unwrap(),
+            }],
+        )));
+
+        let btc_book = books.book(btc).unwrap();
+        assert_eq!(
+            btc_book.best_bid(),
+            Some((Price::new(100).unwrap(), Qty::new(1).unwrap()))
+        );
+        assert_eq!(btc_book.best_ask(), None);
+
+        let eth_book = books.book(eth).unwrap();
+        assert_eq!(eth_book.best_bid(), None);
+        assert_eq!(
+            eth_book.best_ask(),
+            Some((Price::new(200).unwrap(), Qty::new(2).unwrap()))
+        );
+
+        let mut symbols: Vec<SymbolId> = books.symbols().collect();
+        symbols.sort();
+        assert_eq!(symbols, vec![btc, eth]);
+        assert!(books.book(SymbolId::from_u32(2)).is_none());
     }
 
     fn update_strategy() -> impl Strategy<Value = (bool, i64, i64)> {
@@ -377,5 +851,71 @@ mod tests {
                 prop_assert!(bid < ask);
             }
         }
+
+        #[test]
+        fn prop_snapshot_replaces_all_levels(
+            deltas in proptest::collection::vec(update_strategy(), 0..=64),
+            levels in proptest::collection::vec(update_strategy(), 0..=32),
+        ) {
+            let symbol = SymbolId::from_u32(5);
+            let mut book = OrderBook::new(symbol);
+            for (is_bid, price_ticks, qty_lots) in deltas {
+                let side = if is_bid { Side::Bid } else { Side::Ask };
+                book.apply(&delta(symbol, vec![LevelUpdate {
+                    side,
+                    price: Price::new(price_ticks).unwrap(),
+                    qty: Qty::new(qty_lots).unwrap(),
+                }]));
+            }
+
+            let mut bids: BTreeMap<i64, i64> = BTreeMap::new();
+            let mut asks: BTreeMap<i64, i64> = BTreeMap::new();
+            for (is_bid, price_ticks, qty_lots) in levels {
+                let book_side = if is_bid { &mut bids } else { &mut asks };
+                book_side.insert(price_ticks, qty_lots);
+            }
+            let to_levels = |side: &BTreeMap<i64, i64>| -> Vec<(Price, Qty)> {
+                side.iter()
+                    .map(|(p, q)| (Price::new(*p).unwrap(), Qty::new(*q).unwrap()))
+                    .collect()
+            };
+            let snapshot = MarketEvent::L2Snapshot {
+                ts_ns: 2,
+                symbol,
+                bids: to_levels(&bids),
+                asks: to_levels(&asks),
+            };
+
+            let mut other = book.clone();
+            let mismatched = MarketEvent::L2Snapshot {
+                ts_ns: 2,
+                symbol: SymbolId::from_u32(6),
+                bids: to_levels(&bids),
+                asks: to_levels(&asks),
+            };
+            prop_assert!(!other.apply(&mismatched));
+            prop_assert_eq!(&other, &book);
+
+            prop_assert!(book.apply(&snapshot));
+            // Zero-qty snapshot levels are dropped rather than installed.
+            let expected_bids: Vec<(i64, i64)> =
+                bids.iter().rev().filter(|(_, q)| **q > 0).map(|(p, q)| (*p, *q)).collect();
+            let expected_asks: Vec<(i64, i64)> =
+                asks.iter().filter(|(_, q)| **q > 0).map(|(p, q)| (*p, *q)).collect();
+            let bid_levels: Vec<(i64, i64)> =
+                book.bid_levels().map(|(p, q)| (p.ticks(), q.lots())).collect();
+            let ask_levels: Vec<(i64, i64)> =
+                book.ask_levels().map(|(p, q)| (p.ticks(), q.lots())).collect();
+            prop_assert_eq!(&bid_levels, &expected_bids);
+            prop_assert_eq!(&ask_levels, &expected_asks);
+            prop_assert_eq!(
+                book.best_bid().map(|(p, q)| (p.ticks(), q.lots())),
+                expected_bids.first().copied()
+            );
+            prop_assert_eq!(
+                book.best_ask().map(|(p, q)| (p.ticks(), q.lots())),
+                expected_asks.first().copied()
+            );
+        }
     }
 }
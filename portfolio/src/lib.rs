@@ -1,20 +1,46 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use lob_core::{Price, Qty, Side, SymbolId};
-use trading_types::{ClientOrderId, ExecutionReport, OrderStatus};
+use orderbook::MultiBook;
+use serde::{Deserialize, Serialize};
+use trading_types::{ClientOrderId, ExecutionReport, OrderStatus, OrderTag};
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 struct Position {
     position_lots: i64,
     realized_pnl_ticks: i128,
+    /// Positive fees only; negative fees (maker rebates) go to `rebates_received_ticks`.
     fees_paid_ticks: i128,
-    avg_entry_price_ticks: Option<i64>,
+    #[serde(default)]
+    rebates_received_ticks: i128,
+    cash_ticks: i128,
+    /// Signed `sum(price * lots)` of the open position; avg entry is derived from it so
+    /// no rounding accumulates per fill.
+    cost_basis_ticks: i128,
+    /// Open lots as `(price_ticks, signed_lots)`, oldest first; only kept under FIFO.
+    open_lots: VecDeque<(i64, i64)>,
 }
 
-#[derive(Debug, Default)]
+/// How closing fills are matched against the open position when realizing PnL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LotMethod {
+    #[default]
+    AverageCost,
+    Fifo,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Portfolio {
     positions: HashMap<SymbolId, Position>,
     filled_by_order: HashMap<ClientOrderId, i64>,
+    lot_method: LotMethod,
+    peak_equity_ticks: Option<i128>,
+    max_drawdown_ticks: i128,
+    /// Realized PnL per order; `None` unless enabled, and kept after the order completes.
+    order_pnl: Option<HashMap<ClientOrderId, i128>>,
+    /// Realized PnL per report tag; `None` unless attribution is enabled.
+    #[serde(default)]
+    tag_pnl: Option<HashMap<OrderTag, i128>>,
 }
 
 impl Portfolio {
@@ -22,9 +48,25 @@ impl Portfolio {
         Self {
             positions: HashMap::new(),
             filled_by_order: HashMap::new(),
+            lot_method: LotMethod::AverageCost,
+            peak_equity_ticks: None,
+            max_drawdown_ticks: 0,
+            order_pnl: None,
+            tag_pnl: None,
         }
     }
 
+    pub fn with_attribution(mut self, enabled: bool) -> Self {
+        self.order_pnl = enabled.then(HashMap::new);
+        self.tag_pnl = enabled.then(HashMap::new);
+        self
+    }
+
+    pub fn with_lot_accounting(mut self, lot_method: LotMethod) -> Self {
+        self.lot_method = lot_method;
+        self
+    }
+
     pub fn on_execution_report(&mut self, report: &ExecutionReport) {
         if matches!(
             report.status,
@@ -63,37 +105,29 @@ impl Portfolio {
             -delta_qty
         };
 
-        // Update realized pnl if reducing or flipping position.
-        if pos.position_lots != 0 && (pos.position_lots.signum() != signed_qty.signum()) {
-            if let Some(avg_entry) = pos.avg_entry_price_ticks {
-                let close_qty = signed_qty.abs().min(pos.position_lots.abs());
-                let pnl_per_lot = if pos.position_lots > 0 {
-                    fill_price - avg_entry
-                } else {
-                    avg_entry - fill_price
-                };
-                pos.realized_pnl_ticks += pnl_per_lot as i128 * close_qty as i128;
-            }
-        }
-
+        let realized_before = pos.realized_pnl_ticks;
         let new_position = pos.position_lots + signed_qty;
-
-        // Update avg entry price for remaining/open position.
-        if new_position == 0 {
-            pos.avg_entry_price_ticks = None;
-        } else if pos.position_lots == 0 || pos.position_lots.signum() == signed_qty.signum() {
-            let old_qty = pos.position_lots.abs() as i128;
-            let add_qty = signed_qty.abs() as i128;
-            let total_qty = old_qty + add_qty;
-            let old_avg = pos.avg_entry_price_ticks.unwrap_or(fill_price) as i128;
-            let new_avg = (old_avg * old_qty + fill_price as i128 * add_qty) / total_qty;
-            pos.avg_entry_price_ticks = Some(new_avg as i64);
-        } else if new_position != 0 {
-            pos.avg_entry_price_ticks = Some(fill_price);
+        match self.lot_method {
+            LotMethod::AverageCost => pos.apply_average_cost(signed_qty, fill_price),
+            LotMethod::Fifo => pos.apply_fifo(signed_qty, fill_price),
         }
 
         pos.position_lots = new_position;
-        pos.fees_paid_ticks += report.fee_ticks as i128;
+        if report.fee_ticks >= 0 {
+            pos.fees_paid_ticks += report.fee_ticks as i128;
+        } else {
+            pos.rebates_received_ticks -= report.fee_ticks as i128;
+        }
+        // Buys spend cash, sells receive it; fees reduce it and rebates add to it.
+        pos.cash_ticks -= signed_qty as i128 * fill_price as i128 + report.fee_ticks as i128;
+
+        let realized_delta = pos.realized_pnl_ticks - realized_before;
+        if let Some(order_pnl) = &mut self.order_pnl {
+            *order_pnl.entry(report.client_order_id).or_insert(0) += realized_delta;
+        }
+        if let (Some(tag_pnl), Some(tag)) = (&mut self.tag_pnl, &report.tag) {
+            *tag_pnl.entry(tag.clone()).or_insert(0) += realized_delta;
+        }
 
         if report.status == OrderStatus::Filled {
             self.filled_by_order.remove(&report.client_order_id);
@@ -112,9 +146,10 @@ impl Portfolio {
             _ => return None,
         };
         let mid = (bid + ask) / 2;
-        let avg_entry = pos.avg_entry_price_ticks?;
-        let unrealized = (mid - avg_entry) as i128 * pos.position_lots as i128;
-        Some(unrealized)
+        if pos.position_lots == 0 {
+            return None;
+        }
+        Some(mid as i128 * pos.position_lots as i128 - pos.cost_basis_ticks)
     }
 
     pub fn position_lots(&self, symbol: SymbolId) -> i64 {
@@ -137,6 +172,145 @@ impl Portfolio {
             .map(|pos| pos.fees_paid_ticks)
             .unwrap_or(0)
     }
+
+    /// Rebates from negative fees, as a non-negative amount.
+    pub fn rebates_received_ticks(&self, symbol: SymbolId) -> i128 {
+        self.positions
+            .get(&symbol)
+            .map(|pos| pos.rebates_received_ticks)
+            .unwrap_or(0)
+    }
+
+    /// Realized PnL after fees paid and rebates received.
+    pub fn net_pnl_ticks(&self, symbol: SymbolId) -> i128 {
+        self.positions
+            .get(&symbol)
+            .map(|pos| pos.realized_pnl_ticks - pos.fees_paid_ticks + pos.rebates_received_ticks)
+            .unwrap_or(0)
+    }
+
+    /// Realized PnL from fills of `id` that closed position; `None` when attribution is
+    /// disabled or the order never filled.
+    pub fn order_pnl(&self, id: ClientOrderId) -> Option<i128> {
+        self.order_pnl.as_ref()?.get(&id).copied()
+    }
+
+    /// Realized PnL credited to the tag of each closing fill; untagged fills are left out.
+    /// Empty when attribution is disabled.
+    pub fn realized_pnl_by_tag(&self) -> HashMap<OrderTag, i128> {
+        self.tag_pnl.clone().unwrap_or_default()
+    }
+
+    pub fn cash_ticks(&self, symbol: SymbolId) -> i128 {
+        self.positions
+            .get(&symbol)
+            .map(|pos| pos.cash_ticks)
+            .unwrap_or(0)
+    }
+
+    /// Signed position value at `mark`; equity is `cash_ticks + net_notional_ticks`.
+    pub fn net_notional_ticks(&self, symbol: SymbolId, mark: Price) -> i128 {
+        self.position_lots(symbol) as i128 * mark.ticks() as i128
+    }
+
+    pub fn total_realized_pnl_ticks(&self) -> i128 {
+        self.positions
+            .values()
+            .map(|pos| pos.realized_pnl_ticks)
+            .sum()
+    }
+
+    pub fn total_fees_paid_ticks(&self) -> i128 {
+        self.positions.values().map(|pos| pos.fees_paid_ticks).sum()
+    }
+
+    pub fn total_rebates_received_ticks(&self) -> i128 {
+        self.positions
+            .values()
+            .map(|pos| pos.rebates_received_ticks)
+            .sum()
+    }
+
+    /// Records one equity observation (realized + unrealized) for drawdown tracking.
+    pub fn mark(&mut self, equity_ticks: i128) {
+        let peak = self
+            .peak_equity_ticks
+            .map_or(equity_ticks, |peak| peak.max(equity_ticks));
+        self.peak_equity_ticks = Some(peak);
+        self.max_drawdown_ticks = self.max_drawdown_ticks.max(peak - equity_ticks);
+    }
+
+    /// Largest peak-to-trough equity decline seen by `mark`, as a non-negative value.
+    pub fn max_drawdown_ticks(&self) -> i128 {
+        self.max_drawdown_ticks
+    }
+
+    /// Sums `mark_to_mid` over tracked symbols, skipping any without a two-sided quote.
+    pub fn total_unrealized_ticks(&self, books: &MultiBook) -> i128 {
+        self.positions
+            .keys()
+            .filter_map(|&symbol| {
+                let book = books.book(symbol)?;
+                self.mark_to_mid(symbol, book.best_bid(), book.best_ask())
+            })
+            .sum()
+    }
+}
+
+impl Position {
+    fn apply_average_cost(&mut self, signed_qty: i64, fill_price: i64) {
+        let fill_price = fill_price as i128;
+        if self.position_lots == 0 || self.position_lots.signum() == signed_qty.signum() {
+            self.cost_basis_ticks += fill_price * signed_qty as i128;
+            return;
+        }
+
+        // Reducing or flipping: close out the matching share of cost basis.
+        let open_qty = self.position_lots.unsigned_abs() as i128;
+        let close_qty = (signed_qty.unsigned_abs() as i128).min(open_qty);
+        let closed_cost = self.cost_basis_ticks * close_qty / open_qty;
+        let proceeds = fill_price * close_qty * self.position_lots.signum() as i128;
+        self.realized_pnl_ticks += proceeds - closed_cost;
+        self.cost_basis_ticks -= closed_cost;
+
+        let new_position = self.position_lots + signed_qty;
+        if new_position.signum() != self.position_lots.signum() {
+            self.cost_basis_ticks = fill_price * new_position as i128;
+        }
+    }
+
+    fn apply_fifo(&mut self, signed_qty: i64, fill_price: i64) {
+        let mut remaining = signed_qty;
+        while remaining != 0 {
+            let Some(front) = self.open_lots.front_mut() else {
+                break;
+            };
+            if front.1.signum() == remaining.signum() {
+                break;
+            }
+            let matched = remaining.abs().min(front.1.abs());
+            let pnl_per_lot = if front.1 > 0 {
+                fill_price - front.0
+            } else {
+                front.0 - fill_price
+            };
+            self.realized_pnl_ticks += pnl_per_lot as i128 * matched as i128;
+            front.1 -= front.1.signum() * matched;
+            remaining -= remaining.signum() * matched;
+            if front.1 == 0 {
+                self.open_lots.pop_front();
+            }
+        }
+        if remaining != 0 {
+            self.open_lots.push_back((fill_price, remaining));
+        }
+
+        self.cost_basis_ticks = self
+            .open_lots
+            .iter()
+            .map(|&(price, lots)| price as i128 * lots as i128)
+            .sum();
+    }
 }
 
 #[cfg(test)]
@@ -162,6 +336,8 @@ mod tests {
             ts_ns: 1,
             symbol,
             side,
+            reason: None,
+            tag: None,
         }
     }
 
@@ -219,6 +395,44 @@ mod tests {
 
         assert_eq!(portfolio.realized_pnl_ticks(symbol), 5);
         assert_eq!(portfolio.fees_paid_ticks(symbol), 3);
+        assert_eq!(portfolio.net_pnl_ticks(symbol), 2);
+    }
+
+    #[test]
+    fn maker_rebate_increases_net_pnl() {
+        let symbol = SymbolId::from_u32(2);
+        let round_trip = |maker_fee_ticks| {
+            let mut portfolio = Portfolio::new();
+            portfolio.on_execution_report(&report(
+                ClientOrderId(1),
+                symbol,
+                1,
+                100,
+                maker_fee_ticks,
+                OrderStatus::Filled,
+                lob_core::Side::Bid,
+            ));
+            portfolio.on_execution_report(&report(
+                ClientOrderId(2),
+                symbol,
+                1,
+                105,
+                1,
+                OrderStatus::Filled,
+                lob_core::Side::Ask,
+            ));
+            portfolio
+        };
+
+        let zero_fee = round_trip(0);
+        let rebate = round_trip(-2);
+        assert_eq!(rebate.realized_pnl_ticks(symbol), 5);
+        assert_eq!(rebate.fees_paid_ticks(symbol), 1);
+        assert_eq!(rebate.rebates_received_ticks(symbol), 2);
+        assert_eq!(rebate.total_rebates_received_ticks(), 2);
+        assert_eq!(zero_fee.net_pnl_ticks(symbol), 4);
+        assert_eq!(rebate.net_pnl_ticks(symbol), 6);
+        assert_eq!(rebate.cash_ticks(symbol) - zero_fee.cash_ticks(symbol), 2);
     }
 
     #[test]
@@ -321,4 +535,309 @@ mod tests {
         assert_eq!(portfolio.position_lots(symbol), 3);
         assert_eq!(portfolio.fees_paid_ticks(symbol), 6);
     }
+
+    #[test]
+    fn fifo_realizes_against_oldest_lot() {
+        let symbol = SymbolId::from_u32(6);
+        let fills = [
+            (1, 100, lob_core::Side::Bid),
+            (2, 110, lob_core::Side::Bid),
+            (3, 120, lob_core::Side::Ask),
+        ];
+        let mut fifo = Portfolio::new().with_lot_accounting(LotMethod::Fifo);
+        let mut average = Portfolio::new();
+        for (id, price, side) in fills {
+            let fill = report(
+                ClientOrderId(id),
+                symbol,
+                1,
+                price,
+                0,
+                OrderStatus::Filled,
+                side,
+            );
+            fifo.on_execution_report(&fill);
+            average.on_execution_report(&fill);
+        }
+
+        assert_eq!(fifo.position_lots(symbol), 1);
+        assert_eq!(fifo.realized_pnl_ticks(symbol), 20);
+        assert_eq!(average.realized_pnl_ticks(symbol), 15);
+
+        let bid = Some((Price::new(114).unwrap(), Qty::new(1).unwrap()));
+        let ask = Some((Price::new(116).unwrap(), Qty::new(1).unwrap()));
+        assert_eq!(fifo.mark_to_mid(symbol, bid, ask), Some(5));
+    }
+
+    #[test]
+    fn fifo_flips_through_flat_into_new_lot() {
+        let symbol = SymbolId::from_u32(7);
+        let mut portfolio = Portfolio::new().with_lot_accounting(LotMethod::Fifo);
+        portfolio.on_execution_report(&report(
+            ClientOrderId(1),
+            symbol,
+            2,
+            100,
+            0,
+            OrderStatus::Filled,
+            lob_core::Side::Bid,
+        ));
+        portfolio.on_execution_report(&report(
+            ClientOrderId(2),
+            symbol,
+            3,
+            105,
+            0,
+            OrderStatus::Filled,
+            lob_core::Side::Ask,
+        ));
+
+        assert_eq!(portfolio.position_lots(symbol), -1);
+        assert_eq!(portfolio.realized_pnl_ticks(symbol), 10);
+        let bid = Some((Price::new(102).unwrap(), Qty::new(1).unwrap()));
+        let ask = Some((Price::new(104).unwrap(), Qty::new(1).unwrap()));
+        assert_eq!(portfolio.mark_to_mid(symbol, bid, ask), Some(2));
+    }
+
+    #[test]
+    fn totals_sum_across_symbols() {
+        let btc = SymbolId::from_u32(8);
+        let eth = SymbolId::from_u32(9);
+        let sol = SymbolId::from_u32(10);
+        let mut portfolio = Portfolio::new();
+        let fills = [
+            (1, btc, 100, 1, lob_core::Side::Bid),
+            (2, btc, 110, 1, lob_core::Side::Ask),
+            (3, eth, 50, 2, lob_core::Side::Ask),
+            (4, eth, 54, 0, lob_core::Side::Bid),
+            (5, eth, 60, 1, lob_core::Side::Ask),
+            (6, sol, 10, 0, lob_core::Side::Bid),
+        ];
+        for (id, symbol, price, fee, side) in fills {
+            portfolio.on_execution_report(&report(
+                ClientOrderId(id),
+                symbol,
+                1,
+                price,
+                fee,
+                OrderStatus::Filled,
+                side,
+            ));
+        }
+
+        assert_eq!(portfolio.total_realized_pnl_ticks(), 10 - 4);
+        assert_eq!(portfolio.total_fees_paid_ticks(), 5);
+
+        let level = |price| (Price::new(price).unwrap(), Qty::new(1).unwrap());
+        let mut books = MultiBook::new();
+        assert!(books.apply(&lob_core::MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol: eth,
+            bids: vec![level(55)],
+            asks: vec![level(57)],
+        }));
+        assert!(books.apply(&lob_core::MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol: sol,
+            bids: vec![level(9)],
+            asks: vec![],
+        }));
+        // Short one eth at 60 marked at 56; sol is one-sided and skipped.
+        assert_eq!(portfolio.total_unrealized_ticks(&books), 4);
+    }
+
+    #[test]
+    fn max_drawdown_tracks_worst_peak_to_trough() {
+        let mut portfolio = Portfolio::new();
+        assert_eq!(portfolio.max_drawdown_ticks(), 0);
+        for equity in [0, 50, 100, 80, 60, 90, 120, 95] {
+            portfolio.mark(equity);
+        }
+        assert_eq!(portfolio.max_drawdown_ticks(), 40);
+    }
+
+    #[test]
+    fn cash_moves_opposite_to_position() {
+        let symbol = SymbolId::from_u32(11);
+        let mut portfolio = Portfolio::new();
+        portfolio.on_execution_report(&report(
+            ClientOrderId(1),
+            symbol,
+            2,
+            100,
+            1,
+            OrderStatus::Filled,
+            lob_core::Side::Bid,
+        ));
+        assert_eq!(portfolio.cash_ticks(symbol), -201);
+        assert_eq!(
+            portfolio.net_notional_ticks(symbol, Price::new(105).unwrap()),
+            210
+        );
+
+        portfolio.on_execution_report(&report(
+            ClientOrderId(2),
+            symbol,
+            2,
+            110,
+            1,
+            OrderStatus::Filled,
+            lob_core::Side::Ask,
+        ));
+        assert_eq!(portfolio.cash_ticks(symbol), 20 - 2);
+        assert_eq!(
+            portfolio.net_notional_ticks(symbol, Price::new(105).unwrap()),
+            0
+        );
+    }
+
+    #[test]
+    fn attribution_credits_closing_orders() {
+        let symbol = SymbolId::from_u32(12);
+        let mut portfolio = Portfolio::new().with_attribution(true);
+        let fills = [
+            (1, 2, 100, lob_core::Side::Bid),
+            (2, 2, 106, lob_core::Side::Ask),
+            (3, 1, 100, lob_core::Side::Bid),
+            (4, 1, 97, lob_core::Side::Ask),
+        ];
+        for (id, qty, price, side) in fills {
+            portfolio.on_execution_report(&report(
+                ClientOrderId(id),
+                symbol,
+                qty,
+                price,
+                0,
+                OrderStatus::Filled,
+                side,
+            ));
+        }
+
+        assert_eq!(portfolio.order_pnl(ClientOrderId(1)), Some(0));
+        assert_eq!(portfolio.order_pnl(ClientOrderId(2)), Some(12));
+        assert_eq!(portfolio.order_pnl(ClientOrderId(3)), Some(0));
+        assert_eq!(portfolio.order_pnl(ClientOrderId(4)), Some(-3));
+        assert_eq!(portfolio.order_pnl(ClientOrderId(5)), None);
+
+        let mut disabled = Portfolio::new();
+        disabled.on_execution_report(&report(
+            ClientOrderId(1),
+            symbol,
+            1,
+            100,
+            0,
+            OrderStatus::Filled,
+            lob_core::Side::Bid,
+        ));
+        assert_eq!(disabled.order_pnl(ClientOrderId(1)), None);
+    }
+
+    #[test]
+    fn attribution_splits_realized_pnl_by_tag() {
+        let symbol = SymbolId::from_u32(12);
+        let mut portfolio = Portfolio::new().with_attribution(true);
+        let alpha = OrderTag("alpha".to_string());
+        let beta = OrderTag("beta".to_string());
+        let fills = [
+            (1, 2, 100, lob_core::Side::Bid, &alpha),
+            (2, 1, 105, lob_core::Side::Ask, &alpha),
+            (3, 1, 96, lob_core::Side::Ask, &beta),
+            (4, 3, 99, lob_core::Side::Ask, &beta),
+            (5, 3, 101, lob_core::Side::Bid, &alpha),
+        ];
+        for (id, qty, price, side, tag) in fills {
+            let mut fill = report(
+                ClientOrderId(id),
+                symbol,
+                qty,
+                price,
+                0,
+                OrderStatus::Filled,
+                side,
+            );
+            fill.tag = Some(tag.clone());
+            portfolio.on_execution_report(&fill);
+        }
+
+        // alpha: +5 closing at 105, then buys back beta's 3 short at 101 (99 avg) for -6.
+        // beta: -4 closing at 96 against the 100 entry.
+        let by_tag = portfolio.realized_pnl_by_tag();
+        assert_eq!(by_tag.get(&alpha), Some(&-1));
+        assert_eq!(by_tag.get(&beta), Some(&-4));
+        assert_eq!(
+            by_tag.values().sum::<i128>(),
+            portfolio.realized_pnl_ticks(symbol)
+        );
+        assert!(Portfolio::new().realized_pnl_by_tag().is_empty());
+    }
+
+    #[test]
+    fn partial_reduce_keeps_average_entry() {
+        let symbol = SymbolId::from_u32(13);
+        let mut portfolio = Portfolio::new();
+        let fills = [
+            (1, 2, 100, lob_core::Side::Bid),
+            (2, 1, 106, lob_core::Side::Ask),
+            (3, 1, 97, lob_core::Side::Ask),
+        ];
+        for (id, qty, price, side) in fills {
+            portfolio.on_execution_report(&report(
+                ClientOrderId(id),
+                symbol,
+                qty,
+                price,
+                0,
+                OrderStatus::Filled,
+                side,
+            ));
+        }
+        assert_eq!(portfolio.realized_pnl_ticks(symbol), 6 - 3);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn many_adds_then_full_close_realize_exact_pnl(
+            adds in proptest::collection::vec((1i64..5, 90i64..110), 1..64),
+            close_price in 90i64..110,
+            short in proptest::bool::ANY,
+        ) {
+            let symbol = SymbolId::from_u32(14);
+            let mut portfolio = Portfolio::new();
+            let (open_side, close_side) = if short {
+                (lob_core::Side::Ask, lob_core::Side::Bid)
+            } else {
+                (lob_core::Side::Bid, lob_core::Side::Ask)
+            };
+
+            let mut total_qty = 0i64;
+            let mut total_cost = 0i128;
+            for (id, &(qty, price)) in adds.iter().enumerate() {
+                portfolio.on_execution_report(&report(
+                    ClientOrderId(id as u64),
+                    symbol,
+                    qty,
+                    price,
+                    0,
+                    OrderStatus::Filled,
+                    open_side,
+                ));
+                total_qty += qty;
+                total_cost += qty as i128 * price as i128;
+            }
+            portfolio.on_execution_report(&report(
+                ClientOrderId(u64::MAX),
+                symbol,
+                total_qty,
+                close_price,
+                0,
+                OrderStatus::Filled,
+                close_side,
+            ));
+
+            let long_pnl = total_qty as i128 * close_price as i128 - total_cost;
+            let expected = if short { -long_pnl } else { long_pnl };
+            proptest::prop_assert_eq!(portfolio.position_lots(symbol), 0);
+            proptest::prop_assert_eq!(portfolio.realized_pnl_ticks(symbol), expected);
+        }
+    }
 }
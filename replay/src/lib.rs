@@ -1,10 +1,15 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
+use std::ops::RangeInclusive;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
+use rand::Rng;
 use thiserror::Error;
 
-use lob_core::{CoreError, MarketEvent, SymbolTable};
+use lob_core::{CoreError, LevelUpdate, MarketEvent, Price, Qty, Side, SymbolId, SymbolTable};
 
 #[derive(Debug, Error)]
 pub enum ReplayError {
@@ -14,19 +19,291 @@ pub enum ReplayError {
     Core(#[from] CoreError),
     #[error("decode error: {0}")]
     Decode(#[from] codec::CodecError),
+    #[error("zstd compressed input requires the replay/compression feature")]
+    CompressionUnsupported,
+    #[error("timestamp went backwards: {got} after {prev}")]
+    NonMonotonicTimestamp { prev: u64, got: u64 },
 }
 
+/// First bytes of every zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReplayFormat {
     Jsonl,
     Bin,
+    /// Rows as written by `codec::encode_event_csv_line`, optionally led by `codec::CSV_HEADER`.
+    Csv,
+    /// `codec::encode_event_msgpack` records; requires the `msgpack` feature.
+    MsgPack,
+}
+
+/// What `ReplayReader::next_event` does with a record that fails to decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// Return the error; the reader is not usable afterwards.
+    #[default]
+    Fail,
+    /// Count the record in `malformed_count` and move on to the next one. Binary input
+    /// resyncs on `BIN_RECORD_MAGIC`, so legacy length-prefixed records are not supported.
+    SkipMalformed,
+}
+
+/// Checks `ReplayReader` applies to each decoded event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Validation {
+    #[default]
+    Permissive,
+    /// Rejects any event whose `ts_ns` is below the previous event's.
+    StrictMonotonicTs,
+}
+
+/// Flags feed sequence gaps: any `seq` other than the previous one plus one.
+#[derive(Debug, Clone, Default)]
+pub struct SequenceTracker {
+    last_seq: Option<u64>,
+    gaps: Vec<RangeInclusive<u64>>,
+}
+
+impl SequenceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `seq` and returns the skipped range if it jumps ahead. Duplicates and
+    /// stale numbers are ignored and never move the expected sequence backwards.
+    pub fn observe(&mut self, seq: u64) -> Option<RangeInclusive<u64>> {
+        let gap = match self.last_seq {
+            Some(last) if seq <= last => return None,
+            Some(last) if seq > last + 1 => Some(last + 1..=seq - 1),
+            _ => None,
+        };
+        self.last_seq = Some(seq);
+        if let Some(gap) = &gap {
+            self.gaps.push(gap.clone());
+        }
+        gap
+    }
+
+    pub fn last_seq(&self) -> Option<u64> {
+        self.last_seq
+    }
+
+    /// Number of gaps seen, not the number of missing messages.
+    pub fn gap_count(&self) -> usize {
+        self.gaps.len()
+    }
+
+    /// Missing sequence numbers, one inclusive range per gap, in feed order.
+    pub fn gaps(&self) -> &[RangeInclusive<u64>] {
+        &self.gaps
+    }
 }
 
 pub struct ReplayReader {
-    reader: BufReader<File>,
+    reader: Box<dyn BufRead>,
     format: ReplayFormat,
     buffer: String,
     bin_buf: Vec<u8>,
+    csv: codec::CsvEventDecoder,
+    /// Previous binary record timestamp, the base for delta-encoded records.
+    bin_last_ts_ns: Option<u64>,
+    validation: Validation,
+    error_policy: ErrorPolicy,
+    malformed: u64,
+    /// Record magic already consumed by a resync scan, read back as the next prefix.
+    bin_prefix: Option<[u8; 4]>,
+    last_ts_ns: Option<u64>,
+    symbol_filter: Option<SymbolFilter>,
+    /// Exclusive upper bound set by `with_ts_bound`.
+    end_ts_ns: Option<u64>,
+    end_reached: bool,
+    /// First event at or after the `seek_to_ts` target, held for the next call.
+    pending: Option<MarketEvent>,
+    events_read: u64,
+    progress: Option<Progress>,
+    /// Bytes of binary input consumed, and where the latest record started.
+    bin_pos: u64,
+    record_offset: u64,
+    /// Fed from the `seq` key of JSON lines when enabled by `with_sequence_tracking`.
+    sequence: Option<SequenceTracker>,
+    symbols: SymbolTable,
+}
+
+struct Progress {
+    every_n: u64,
+    callback: Box<dyn FnMut(u64)>,
+}
+
+/// Allow-list matched by text once per `SymbolId`, then by id. Names are not interned up
+/// front so they cannot shift ids a binary file header expects.
+struct SymbolFilter {
+    names: Vec<String>,
+    by_id: Vec<Option<bool>>,
+}
+
+impl SymbolFilter {
+    fn allows(&mut self, id: SymbolId, symbols: &SymbolTable) -> bool {
+        let index = id.as_u32() as usize;
+        if index >= self.by_id.len() {
+            self.by_id.resize(index + 1, None);
+        }
+        let names = &self.names;
+        *self.by_id[index].get_or_insert_with(|| {
+            symbols
+                .try_resolve(id)
+                .is_some_and(|text| names.iter().any(|name| name == text))
+        })
+    }
+}
+
+/// Emits events at their recorded inter-arrival timing for demos. It uses wall-clock
+/// sleeps, so it is not meant for backtesting.
+pub struct PacedReplay {
+    reader: ReplayReader,
+    /// First event's `ts_ns`, the wall-clock instant it was emitted, and the speed used.
+    anchor: Option<(u64, Instant, f64)>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum FeedConfigError {
+    #[error("min qty must be at least 1, got {0}")]
+    MinQty(i64),
+    #[error("min qty {min} exceeds max qty {max}")]
+    QtyRange { min: i64, max: i64 },
+    #[error("remove probability must be within [0, 1], got {0}")]
+    RemoveProbability(f64),
+    #[error("trade probability must be within [0, 1], got {0}")]
+    TradeProbability(f64),
+    #[error("levels must be at least 1")]
+    Levels,
+    #[error("levels must be at most {max}, got {levels}")]
+    TooManyLevels { levels: u32, max: u32 },
+}
+
+/// Upper bound on [`SyntheticFeedConfig::levels`]; every snapshot allocates this many
+/// levels per side.
+pub const MAX_SYNTHETIC_LEVELS: u32 = 10_000;
+
+/// Distribution knobs for [`SyntheticFeed`]; the defaults reproduce the original `gen` feed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyntheticFeedConfig {
+    /// The mid moves by a uniform step in `-volatility_ticks..=volatility_ticks` per event.
+    pub volatility_ticks: u32,
+    pub min_qty_lots: i64,
+    pub max_qty_lots: i64,
+    /// Chance that a delta removes its level instead of setting a quantity.
+    pub remove_prob: f64,
+    /// Snapshot depth per side, and the furthest tick offset from mid a delta touches.
+    pub levels: u32,
+    /// Chance that an event is a trade print at the touch instead of a delta.
+    pub trade_prob: f64,
+}
+
+impl Default for SyntheticFeedConfig {
+    fn default() -> Self {
+        Self {
+            volatility_ticks: 1,
+            min_qty_lots: 1,
+            max_qty_lots: 10,
+            remove_prob: 0.1,
+            levels: 5,
+            trade_prob: 0.0,
+        }
+    }
+}
+
+impl SyntheticFeedConfig {
+    pub fn validate(&self) -> Result<(), FeedConfigError> {
+        if self.min_qty_lots < 1 {
+            return Err(FeedConfigError::MinQty(self.min_qty_lots));
+        }
+        if self.min_qty_lots > self.max_qty_lots {
+            return Err(FeedConfigError::QtyRange {
+                min: self.min_qty_lots,
+                max: self.max_qty_lots,
+            });
+        }
+        if !(0.0..=1.0).contains(&self.remove_prob) {
+            return Err(FeedConfigError::RemoveProbability(self.remove_prob));
+        }
+        if !(0.0..=1.0).contains(&self.trade_prob) {
+            return Err(FeedConfigError::TradeProbability(self.trade_prob));
+        }
+        if self.levels == 0 {
+            return Err(FeedConfigError::Levels);
+        }
+        if self.levels > MAX_SYNTHETIC_LEVELS {
+            return Err(FeedConfigError::TooManyLevels {
+                levels: self.levels,
+                max: MAX_SYNTHETIC_LEVELS,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Random-walk L2 feed, as written by the CLI `gen` command. Events rotate round-robin
+/// across the feed's symbols. All randomness comes from the injected `rng`, so a seeded
+/// rng always yields the same feed.
+pub struct SyntheticFeed<R> {
+    rng: R,
+    config: SyntheticFeedConfig,
+    books: Vec<FeedBook>,
+    next_book: usize,
+    ts_ns: u64,
+    snapshot_first: bool,
+}
+
+/// Per-symbol random-walk state; the levels mirror what a replayed book would hold, so
+/// trade prints can be placed at the touch.
+struct FeedBook {
+    symbol: SymbolId,
+    mid_ticks: i64,
+    started: bool,
+    bids: BTreeMap<i64, i64>,
+    asks: BTreeMap<i64, i64>,
+}
+
+impl FeedBook {
+    fn new(symbol: SymbolId, mid_ticks: i64) -> Self {
+        Self {
+            symbol,
+            mid_ticks,
+            started: false,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        }
+    }
+
+    /// Best bid and ask, if both sides are present and the book is not crossed.
+    fn touch(&self) -> Option<(i64, i64)> {
+        let bid = *self.bids.keys().next_back()?;
+        let ask = *self.asks.keys().next()?;
+        (bid < ask).then_some((bid, ask))
+    }
+
+    fn set_level(&mut self, side: Side, price_ticks: i64, qty_lots: i64) {
+        let levels = match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        };
+        if qty_lots == 0 {
+            levels.remove(&price_ticks);
+        } else {
+            levels.insert(price_ticks, qty_lots);
+        }
+    }
+}
+
+/// k-way merge of several replay files into one stream ordered by `ts_ns`; ties go to
+/// the earlier file. Symbols are re-interned into one shared table.
+pub struct MergingReplayReader {
+    readers: Vec<ReplayReader>,
+    /// Per reader, its local `SymbolId` mapped into `symbols`.
+    symbol_maps: Vec<Vec<Option<SymbolId>>>,
+    heads: Vec<Option<MarketEvent>>,
+    heap: BinaryHeap<Reverse<(u64, usize)>>,
     symbols: SymbolTable,
 }
 
@@ -34,6 +311,7 @@ pub struct ReplayReader {
 pub struct MmapReplayReader {
     mmap: memmap2::Mmap,
     pos: usize,
+    last_ts_ns: Option<u64>,
     symbols: SymbolTable,
 }
 
@@ -65,51 +343,342 @@ impl ReplayReader {
         symbols: SymbolTable,
     ) -> Result<Self, ReplayError> {
         let file = File::open(path)?;
+        let is_zst = path.extension().is_some_and(|ext| ext == "zst");
+        Self::from_buffered(
+            BufReader::with_capacity(64 * 1024, file),
+            is_zst,
+            format,
+            symbols,
+        )
+    }
+
+    /// Replays from any byte source (socket, stdin, in-memory buffer). zstd input is
+    /// detected by its frame magic.
+    pub fn from_reader<R: Read + 'static>(
+        reader: R,
+        format: ReplayFormat,
+    ) -> Result<Self, ReplayError> {
+        Self::from_reader_with_symbols(reader, format, SymbolTable::new())
+    }
+
+    pub fn from_reader_with_symbols<R: Read + 'static>(
+        reader: R,
+        format: ReplayFormat,
+        symbols: SymbolTable,
+    ) -> Result<Self, ReplayError> {
+        Self::from_buffered(
+            BufReader::with_capacity(64 * 1024, reader),
+            false,
+            format,
+            symbols,
+        )
+    }
+
+    fn from_buffered<R: Read + 'static>(
+        mut reader: BufReader<R>,
+        is_zst: bool,
+        format: ReplayFormat,
+        symbols: SymbolTable,
+    ) -> Result<Self, ReplayError> {
+        let is_zstd = is_zst || reader.fill_buf()?.starts_with(&ZSTD_MAGIC);
+        let reader: Box<dyn BufRead> = if is_zstd {
+            open_zstd(reader)?
+        } else {
+            Box::new(reader)
+        };
         Ok(Self {
-            reader: BufReader::with_capacity(64 * 1024, file),
+            reader,
             format,
             buffer: String::with_capacity(4096),
             bin_buf: Vec::with_capacity(4096),
+            csv: codec::CsvEventDecoder::new(),
+            bin_last_ts_ns: None,
+            validation: Validation::default(),
+            error_policy: ErrorPolicy::default(),
+            malformed: 0,
+            bin_prefix: None,
+            last_ts_ns: None,
+            symbol_filter: None,
+            end_ts_ns: None,
+            end_reached: false,
+            pending: None,
+            events_read: 0,
+            progress: None,
+            bin_pos: 0,
+            record_offset: 0,
+            sequence: None,
             symbols,
         })
     }
 
-    pub fn next_event(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
-        match self.format {
-            ReplayFormat::Jsonl => self.next_event_json(),
-            ReplayFormat::Bin => self.next_event_bin(),
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    /// Byte offset of the binary record read most recently, in the decompressed stream.
+    /// After a decode error it points at the offending record.
+    pub fn record_offset(&self) -> u64 {
+        self.record_offset
+    }
+
+    /// Events returned by `next_event` so far.
+    pub fn events_read(&self) -> u64 {
+        self.events_read
+    }
+
+    /// Calls `f` with the cumulative event count after every `every_n` events returned.
+    pub fn set_progress(&mut self, every_n: u64, f: Box<dyn FnMut(u64)>) {
+        self.progress = Some(Progress {
+            every_n: every_n.max(1),
+            callback: f,
+        });
+    }
+
+    pub fn with_validation(mut self, validation: Validation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    /// Records skipped under `ErrorPolicy::SkipMalformed`.
+    pub fn malformed_count(&self) -> u64 {
+        self.malformed
+    }
+
+    /// Skips binary input up to the next record magic, e.g. after a decode error left
+    /// the stream mid-record, and returns the number of bytes skipped. Decoding resumes
+    /// at that record. Text formats are line-oriented and are left untouched.
+    pub fn resync(&mut self) -> Result<u64, ReplayError> {
+        if self.format != ReplayFormat::Bin || self.bin_prefix.is_some() {
+            return Ok(0);
+        }
+        let start = self.bin_pos;
+        let mut window = [0u8; 4];
+        let mut read = 0usize;
+        while read < window.len() {
+            let n = self.reader.read(&mut window[read..])?;
+            if n == 0 {
+                self.bin_pos += read as u64;
+                return Ok(self.bin_pos - start);
+            }
+            read += n;
         }
+        self.bin_prefix = self.scan_to_record_magic(window)?;
+        self.record_offset = self.bin_pos;
+        Ok(self.bin_pos - start)
     }
 
-    fn next_event_json(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
-        self.buffer.clear();
-        let bytes = self.reader.read_line(&mut self.buffer)?;
-        if bytes == 0 {
+    /// Only events for these symbols are returned; the rest are decoded and dropped.
+    pub fn with_symbol_filter(mut self, symbols: &[&str]) -> Self {
+        self.symbol_filter = Some(SymbolFilter {
+            names: symbols.iter().map(|name| name.trim().to_string()).collect(),
+            by_id: Vec::new(),
+        });
+        self
+    }
+
+    /// Tracks the `seq` key of JSON lines, before any symbol filter, to detect dropped
+    /// messages. Other formats carry no sequence numbers.
+    pub fn with_sequence_tracking(mut self) -> Self {
+        self.sequence = Some(SequenceTracker::new());
+        self
+    }
+
+    pub fn sequence_tracker(&self) -> Option<&SequenceTracker> {
+        self.sequence.as_ref()
+    }
+
+    /// `next_event` returns `None` from the first event at or after `to_ts_ns` onwards.
+    pub fn with_ts_bound(mut self, to_ts_ns: u64) -> Self {
+        self.end_ts_ns = Some(to_ts_ns);
+        self
+    }
+
+    /// Skips events earlier than `from_ts_ns` with a linear scan; the next call to
+    /// `next_event` returns the first event at or after it. Skipped events are not
+    /// counted in `events_read` and do not fire the progress callback.
+    pub fn seek_to_ts(&mut self, from_ts_ns: u64) -> Result<(), ReplayError> {
+        while let Some(event) = self.next_unread()? {
+            if event.ts_ns() >= from_ts_ns {
+                self.pending = Some(event);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn next_event(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
+        let Some(event) = self.next_unread()? else {
             return Ok(None);
+        };
+        self.events_read += 1;
+        if let Some(progress) = self.progress.as_mut() {
+            if self.events_read.is_multiple_of(progress.every_n) {
+                (progress.callback)(self.events_read);
+            }
         }
-        let event = codec::decode_event_json_line(&self.buffer, &mut self.symbols)?;
         Ok(Some(event))
     }
 
-    fn next_event_bin(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
-        let mut prefix_buf = [0u8; 4];
+    /// The next event that passes the filters and bounds, without counting it as read.
+    fn next_unread(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
+        if let Some(event) = self.pending.take() {
+            return Ok(Some(event));
+        }
+        if self.end_reached {
+            return Ok(None);
+        }
+        loop {
+            let decoded = match self.next_decoded() {
+                Err(err)
+                    if self.error_policy == ErrorPolicy::SkipMalformed
+                        && is_malformed_record(&err) =>
+                {
+                    self.malformed += 1;
+                    // A partially assembled CSV event cannot be trusted either.
+                    self.csv = codec::CsvEventDecoder::new();
+                    continue;
+                }
+                decoded => decoded?,
+            };
+            let Some(event) = decoded else {
+                return Ok(None);
+            };
+            let ts_ns = event.ts_ns();
+            if self.validation == Validation::StrictMonotonicTs {
+                if let Some(prev) = self.last_ts_ns.filter(|prev| ts_ns < *prev) {
+                    return Err(ReplayError::NonMonotonicTimestamp { prev, got: ts_ns });
+                }
+            }
+            self.last_ts_ns = Some(ts_ns);
+
+            let allowed = match self.symbol_filter.as_mut() {
+                Some(filter) => filter.allows(event.symbol(), &self.symbols),
+                None => true,
+            };
+            if !allowed {
+                continue;
+            }
+            if self.end_ts_ns.is_some_and(|end| ts_ns >= end) {
+                self.end_reached = true;
+                return Ok(None);
+            }
+            return Ok(Some(event));
+        }
+    }
+
+    fn next_decoded(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
+        match self.format {
+            ReplayFormat::Jsonl => self.next_event_json(),
+            ReplayFormat::Bin => self.next_event_bin(),
+            ReplayFormat::Csv => self.next_event_csv(),
+            ReplayFormat::MsgPack => self.next_event_msgpack(),
+        }
+    }
+
+    fn next_event_msgpack(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
+        let mut header_buf = [0u8; codec::BIN_RECORD_HEADER_LEN];
         let mut read = 0usize;
-        while read < prefix_buf.len() {
-            let n = self.reader.read(&mut prefix_buf[read..])?;
+        while read < header_buf.len() {
+            let n = self.reader.read(&mut header_buf[read..])?;
             if n == 0 {
                 if read == 0 {
                     return Ok(None);
                 }
                 return Err(std::io::Error::new(
                     std::io::ErrorKind::UnexpectedEof,
-                    "truncated binary record prefix",
+                    "truncated msgpack record header",
                 )
                 .into());
             }
             read += n;
         }
 
-        if prefix_buf == codec::BIN_RECORD_MAGIC {
+        let header = codec::decode_event_msgpack_header(&header_buf)?;
+        let record_len = codec::BIN_RECORD_HEADER_LEN + header.payload_len;
+        self.bin_buf.resize(record_len, 0);
+        self.bin_buf[..codec::BIN_RECORD_HEADER_LEN].copy_from_slice(&header_buf);
+        self.reader
+            .read_exact(&mut self.bin_buf[codec::BIN_RECORD_HEADER_LEN..])?;
+
+        let event = codec::decode_event_msgpack(&self.bin_buf, &mut self.symbols)?;
+        Ok(Some(event))
+    }
+
+    fn next_event_csv(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
+        loop {
+            self.buffer.clear();
+            let bytes = self.reader.read_line(&mut self.buffer)?;
+            if bytes == 0 {
+                if self.csv.is_idle() {
+                    return Ok(None);
+                }
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "truncated csv event",
+                )
+                .into());
+            }
+            if let Some(event) = self.csv.push_line(&self.buffer, &mut self.symbols)? {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    fn next_event_json(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
+        self.buffer.clear();
+        let bytes = self.reader.read_line(&mut self.buffer)?;
+        if bytes == 0 {
+            return Ok(None);
+        }
+        let Some(tracker) = self.sequence.as_mut() else {
+            let event = codec::decode_event_json_line(&self.buffer, &mut self.symbols)?;
+            return Ok(Some(event));
+        };
+        let sequenced = codec::decode_sequenced_event_json_line(&self.buffer, &mut self.symbols)?;
+        if let Some(seq) = sequenced.seq {
+            tracker.observe(seq);
+        }
+        Ok(Some(sequenced.event))
+    }
+
+    fn next_event_bin(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
+        self.record_offset = self.bin_pos;
+        let mut prefix_buf = [0u8; 4];
+        if let Some(prefix) = self.bin_prefix.take() {
+            prefix_buf = prefix;
+        } else {
+            let mut read = 0usize;
+            while read < prefix_buf.len() {
+                let n = self.reader.read(&mut prefix_buf[read..])?;
+                if n == 0 {
+                    if read == 0 {
+                        return Ok(None);
+                    }
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "truncated binary record prefix",
+                    )
+                    .into());
+                }
+                read += n;
+            }
+        }
+        if self.error_policy == ErrorPolicy::SkipMalformed && !is_bin_magic(&prefix_buf) {
+            self.malformed += 1;
+            match self.scan_to_record_magic(prefix_buf)? {
+                Some(magic) => prefix_buf = magic,
+                None => return Ok(None),
+            }
+            self.record_offset = self.bin_pos;
+        }
+
+        let is_file_header = prefix_buf == codec::BIN_FILE_MAGIC;
+        if prefix_buf == codec::BIN_RECORD_MAGIC || is_file_header {
             let mut header_buf = [0u8; codec::BIN_RECORD_HEADER_LEN];
             header_buf[..4].copy_from_slice(&prefix_buf);
 
@@ -126,7 +695,11 @@ impl ReplayReader {
                 read += n;
             }
 
-            let header = codec::decode_event_bin_header(&header_buf)?;
+            let header = if is_file_header {
+                codec::decode_bin_file_frame(&header_buf)?
+            } else {
+                codec::decode_event_bin_header(&header_buf)?
+            };
             let record_len = codec::BIN_RECORD_HEADER_LEN + header.payload_len;
             self.bin_buf.resize(record_len, 0);
             self.bin_buf[..codec::BIN_RECORD_HEADER_LEN].copy_from_slice(&header_buf);
@@ -144,173 +717,1343 @@ impl ReplayReader {
                 }
                 read += n;
             }
+            self.bin_pos += record_len as u64;
+
+            if is_file_header {
+                self.bin_last_ts_ns =
+                    codec::decode_bin_file_header(&self.bin_buf, &mut self.symbols)?;
+                return self.next_event_bin();
+            }
+            let event = codec::decode_event_bin_record_in_stream(
+                &self.bin_buf,
+                &mut self.symbols,
+                &mut self.bin_last_ts_ns,
+            )?;
+            Ok(Some(event))
+        } else {
+            let payload_len = u32::from_le_bytes(prefix_buf) as usize;
+            self.bin_buf.resize(payload_len, 0);
+            let mut read = 0usize;
+            while read < payload_len {
+                let n = self.reader.read(&mut self.bin_buf[read..])?;
+                if n == 0 {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "truncated legacy binary payload",
+                    )
+                    .into());
+                }
+                read += n;
+            }
+            self.bin_pos += (4 + payload_len) as u64;
+
+            let event = codec::decode_event_bin_payload(&self.bin_buf, &mut self.symbols)?;
+            Ok(Some(event))
+        }
+    }
+
+    /// Slides a 4-byte window, seeded with bytes already read, forward one byte at a
+    /// time until it holds a record or file magic. Skipped bytes are added to the stream
+    /// position; the magic itself is not, as it is read back as the next prefix.
+    fn scan_to_record_magic(
+        &mut self,
+        mut window: [u8; 4],
+    ) -> Result<Option<[u8; 4]>, ReplayError> {
+        let mut byte = [0u8; 1];
+        loop {
+            if is_bin_magic(&window) {
+                return Ok(Some(window));
+            }
+            if self.reader.read(&mut byte)? == 0 {
+                self.bin_pos += window.len() as u64;
+                return Ok(None);
+            }
+            window.rotate_left(1);
+            window[3] = byte[0];
+            self.bin_pos += 1;
+        }
+    }
+}
+
+fn is_bin_magic(prefix: &[u8; 4]) -> bool {
+    *prefix == codec::BIN_RECORD_MAGIC || *prefix == codec::BIN_FILE_MAGIC
+}
+
+/// Errors that condemn a single record rather than the input as a whole.
+fn is_malformed_record(err: &ReplayError) -> bool {
+    match err {
+        ReplayError::Decode(_) | ReplayError::Core(_) => true,
+        ReplayError::Io(err) => matches!(
+            err.kind(),
+            std::io::ErrorKind::UnexpectedEof | std::io::ErrorKind::InvalidData
+        ),
+        _ => false,
+    }
+}
+
+impl MergingReplayReader {
+    pub fn open(paths: &[&Path], format: ReplayFormat) -> Result<Self, ReplayError> {
+        let readers = paths
+            .iter()
+            .map(|path| ReplayReader::open_with_format(path, format))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut merged = Self {
+            symbol_maps: vec![Vec::new(); readers.len()],
+            heads: vec![None; readers.len()],
+            heap: BinaryHeap::with_capacity(readers.len()),
+            readers,
+            symbols: SymbolTable::new(),
+        };
+        for index in 0..merged.readers.len() {
+            merged.advance(index)?;
+        }
+        Ok(merged)
+    }
+
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    pub fn next_event(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
+        let Some(Reverse((_, index))) = self.heap.pop() else {
+            return Ok(None);
+        };
+        let event = self.heads[index].take();
+        self.advance(index)?;
+        Ok(event)
+    }
+
+    fn advance(&mut self, index: usize) -> Result<(), ReplayError> {
+        let Some(mut event) = self.readers[index].next_event()? else {
+            return Ok(());
+        };
+        let local = event.symbol();
+        let map = &mut self.symbol_maps[index];
+        let slot = local.as_u32() as usize;
+        if slot >= map.len() {
+            map.resize(slot + 1, None);
+        }
+        let shared = match map[slot] {
+            Some(shared) => shared,
+            None => {
+                let text = self.readers[index].symbols().resolve(local);
+                let shared = self.symbols.try_intern(text)?;
+                map[slot] = Some(shared);
+                shared
+            }
+        };
+        set_symbol(&mut event, shared);
+        self.heap.push(Reverse((event.ts_ns(), index)));
+        self.heads[index] = Some(event);
+        Ok(())
+    }
+}
+
+fn set_symbol(event: &mut MarketEvent, id: SymbolId) {
+    match event {
+        MarketEvent::L2Delta { symbol, .. }
+        | MarketEvent::L2Snapshot { symbol, .. }
+        | MarketEvent::Trade { symbol, .. } => *symbol = id,
+    }
+}
+
+impl PacedReplay {
+    pub fn new(reader: ReplayReader) -> Self {
+        Self {
+            reader,
+            anchor: None,
+        }
+    }
+
+    pub fn into_inner(self) -> ReplayReader {
+        self.reader
+    }
+
+    /// Sleeps until the event is due: `speed` 2.0 plays twice as fast, 0.0 never sleeps.
+    /// Deadlines are measured from the anchor, so decode time does not accumulate as drift;
+    /// changing `speed` re-anchors on the current event.
+    pub fn next_event_paced(&mut self, speed: f64) -> Result<Option<MarketEvent>, ReplayError> {
+        let Some(event) = self.reader.next_event()? else {
+            return Ok(None);
+        };
+        if speed <= 0.0 || !speed.is_finite() {
+            return Ok(Some(event));
+        }
+
+        let ts_ns = event.ts_ns();
+        match self.anchor {
+            Some((anchor_ts_ns, anchor_at, anchor_speed)) if anchor_speed == speed => {
+                let offset_ns = ts_ns.saturating_sub(anchor_ts_ns) as f64 / speed;
+                let due = anchor_at + Duration::from_nanos(offset_ns as u64);
+                let now = Instant::now();
+                if due > now {
+                    std::thread::sleep(due - now);
+                }
+            }
+            _ => self.anchor = Some((ts_ns, Instant::now(), speed)),
+        }
+        Ok(Some(event))
+    }
+}
+
+impl<R: Rng> SyntheticFeed<R> {
+    const START_MID_TICKS: i64 = 100_000;
+
+    pub fn new(symbol: SymbolId, rng: R) -> Self {
+        Self {
+            rng,
+            config: SyntheticFeedConfig::default(),
+            books: vec![FeedBook::new(symbol, Self::START_MID_TICKS)],
+            next_book: 0,
+            ts_ns: 0,
+            snapshot_first: false,
+        }
+    }
+
+    pub fn with_config(
+        symbol: SymbolId,
+        rng: R,
+        config: SyntheticFeedConfig,
+    ) -> Result<Self, FeedConfigError> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            ..Self::new(symbol, rng)
+        })
+    }
+
+    /// Replaces the feed's symbols; events then rotate through them in order. An empty
+    /// list keeps the current symbols.
+    pub fn with_symbols(mut self, symbols: &[SymbolId]) -> Self {
+        if !symbols.is_empty() {
+            self.books = symbols
+                .iter()
+                .map(|&symbol| FeedBook::new(symbol, Self::START_MID_TICKS))
+                .collect();
+            self.next_book = 0;
+        }
+        self
+    }
+
+    /// Starts each symbol with a two-sided snapshot, `levels` deep, around the initial mid.
+    pub fn with_snapshot_first(mut self, enabled: bool) -> Self {
+        self.snapshot_first = enabled;
+        self
+    }
+
+    pub fn symbol_count(&self) -> usize {
+        self.books.len()
+    }
+
+    /// Events are one nanosecond apart; after any snapshot, each is either a trade print
+    /// at the touch (with probability `trade_prob`, when the book is two-sided) or a
+    /// one-level delta placed 1..=`levels` ticks from a mid that drifts by up to
+    /// `volatility_ticks`.
+    pub fn next_event(&mut self) -> MarketEvent {
+        let ts_ns = self.ts_ns;
+        self.ts_ns += 1;
+        let index = self.next_book;
+        self.next_book = (self.next_book + 1) % self.books.len();
+        if !std::mem::replace(&mut self.books[index].started, true) && self.snapshot_first {
+            return self.snapshot(index, ts_ns);
+        }
+        if self.config.trade_prob > 0.0 && self.rng.gen_bool(self.config.trade_prob) {
+            if let Some(trade) = self.trade(index, ts_ns) {
+                return trade;
+            }
+        }
+
+        let volatility = i64::from(self.config.volatility_ticks);
+        let drift: i64 = self.rng.gen_range(-volatility..=volatility);
+        let mid_ticks = (self.books[index].mid_ticks + drift).max(1);
+        self.books[index].mid_ticks = mid_ticks;
+        let side = if self.rng.gen_bool(0.5) {
+            Side::Bid
+        } else {
+            Side::Ask
+        };
+        let offset: i64 = self.rng.gen_range(1..=i64::from(self.config.levels));
+        let price_ticks = match side {
+            Side::Bid => (mid_ticks - offset).max(1),
+            Side::Ask => mid_ticks + offset,
+        };
+        let remove = self.rng.gen_bool(self.config.remove_prob);
+        let qty_lots: i64 = if remove { 0 } else { self.random_qty() };
+        let book = &mut self.books[index];
+        book.set_level(side, price_ticks, qty_lots);
+
+        MarketEvent::L2Delta {
+            ts_ns,
+            symbol: book.symbol,
+            updates: vec![LevelUpdate {
+                side,
+                price: feed_price(price_ticks),
+                qty: feed_qty(qty_lots),
+            }],
+        }
+    }
+
+    /// A buy-aggressor print at the best ask or a sell-aggressor print at the best bid;
+    /// `None` while the book is one-sided or crossed.
+    fn trade(&mut self, index: usize, ts_ns: u64) -> Option<MarketEvent> {
+        let (bid, ask) = self.books[index].touch()?;
+        let (aggressor_side, price_ticks) = if self.rng.gen_bool(0.5) {
+            (Side::Bid, ask)
+        } else {
+            (Side::Ask, bid)
+        };
+        let qty_lots = self.random_qty();
+        Some(MarketEvent::Trade {
+            ts_ns,
+            symbol: self.books[index].symbol,
+            price: feed_price(price_ticks),
+            qty: feed_qty(qty_lots),
+            aggressor_side,
+        })
+    }
+
+    fn random_qty(&mut self) -> i64 {
+        self.rng
+            .gen_range(self.config.min_qty_lots..=self.config.max_qty_lots)
+    }
+
+    fn snapshot(&mut self, index: usize, ts_ns: u64) -> MarketEvent {
+        let levels = i64::from(self.config.levels);
+        let mid_ticks = self.books[index].mid_ticks;
+        let mut bids = Vec::with_capacity(levels as usize);
+        let mut asks = Vec::with_capacity(levels as usize);
+        let mut book = FeedBook::new(self.books[index].symbol, mid_ticks);
+        book.started = true;
+        for level in 1..=levels {
+            let bid_qty = self.random_qty();
+            let ask_qty = self.random_qty();
+            let bid_ticks = (mid_ticks - level).max(1);
+            book.set_level(Side::Bid, bid_ticks, bid_qty);
+            book.set_level(Side::Ask, mid_ticks + level, ask_qty);
+            bids.push((feed_price(bid_ticks), feed_qty(bid_qty)));
+            asks.push((feed_price(mid_ticks + level), feed_qty(ask_qty)));
+        }
+        let symbol = book.symbol;
+        self.books[index] = book;
+        MarketEvent::L2Snapshot {
+            ts_ns,
+            symbol,
+            bids,
+            asks,
+        }
+    }
+}
+
+impl<R: Rng> Iterator for SyntheticFeed<R> {
+    type Item = MarketEvent;
+
+    fn next(&mut self) -> Option<MarketEvent> {
+        Some(self.next_event())
+    }
+}
+
+fn feed_price(ticks: i64) -> Price {
+    match Price::new(ticks) {
+        Ok(price) => price,
+        Err(_) => unreachable!("synthetic prices are clamped to at least one tick"),
+    }
+}
+
+fn feed_qty(lots: i64) -> Qty {
+    match Qty::new(lots) {
+        Ok(qty) => qty,
+        Err(_) => unreachable!("synthetic quantities are non-negative"),
+    }
+}
+
+/// Record framing is unchanged under compression; only the byte stream is wrapped.
+#[cfg(feature = "compression")]
+fn open_zstd<R: Read + 'static>(reader: BufReader<R>) -> Result<Box<dyn BufRead>, ReplayError> {
+    let decoder = zstd::Decoder::with_buffer(reader)?;
+    Ok(Box::new(BufReader::with_capacity(64 * 1024, decoder)))
+}
+
+#[cfg(not(feature = "compression"))]
+fn open_zstd<R: Read + 'static>(_reader: BufReader<R>) -> Result<Box<dyn BufRead>, ReplayError> {
+    Err(ReplayError::CompressionUnsupported)
+}
+
+#[cfg(feature = "mmap")]
+impl MmapReplayReader {
+    pub fn open(path: &Path) -> Result<Self, ReplayError> {
+        Self::open_with_symbols(path, SymbolTable::new())
+    }
+
+    pub fn open_with_predeclared_symbols<I, S>(path: &Path, symbols: I) -> Result<Self, ReplayError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let symbols = SymbolTable::try_from_symbols(symbols)?;
+        Self::open_with_symbols(path, symbols)
+    }
+
+    /// A leading symbol-table header is loaded here, so offsets from `build_index` can be
+    /// used before any event has been read.
+    pub fn open_with_symbols(path: &Path, symbols: SymbolTable) -> Result<Self, ReplayError> {
+        let file = File::open(path)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let mut reader = Self {
+            mmap,
+            pos: 0,
+            last_ts_ns: None,
+            symbols,
+        };
+        if reader.mmap.starts_with(&codec::BIN_FILE_MAGIC) {
+            let (_, len) =
+                decode_mmap_record(&reader.mmap, 0, &mut reader.symbols, &mut reader.last_ts_ns)?;
+            reader.pos = len;
+        }
+        Ok(reader)
+    }
+
+    /// Skips records earlier than `from_ts_ns`, leaving the reader positioned on the first
+    /// record at or after it. This is a linear scan; see `build_index` for repeated seeks.
+    pub fn seek_to_ts(&mut self, from_ts_ns: u64) -> Result<(), ReplayError> {
+        loop {
+            let (pos, last_ts_ns) = (self.pos, self.last_ts_ns);
+            match self.next_event()? {
+                Some(event) if event.ts_ns() < from_ts_ns => {}
+                Some(_) => {
+                    self.pos = pos;
+                    self.last_ts_ns = last_ts_ns;
+                    return Ok(());
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Walks every record once and returns `(ts_ns, byte_offset)` pairs in file order,
+    /// suitable for a binary search followed by `seek_to_offset`.
+    pub fn build_index(&self) -> Result<Vec<(u64, usize)>, ReplayError> {
+        let mut symbols = self.symbols.clone();
+        let mut last_ts_ns = None;
+        let mut index = Vec::new();
+        let mut pos = 0usize;
+        while pos < self.mmap.len() {
+            let (event, len) = decode_mmap_record(&self.mmap, pos, &mut symbols, &mut last_ts_ns)?;
+            if let Some(event) = event {
+                index.push((event.ts_ns(), pos));
+            }
+            pos += len;
+        }
+        Ok(index)
+    }
+
+    /// Positions the reader at a record offset from `build_index`. Delta-timestamp
+    /// records need their predecessor, so they cannot be resumed this way.
+    pub fn seek_to_offset(&mut self, offset: usize) -> Result<(), ReplayError> {
+        if offset > self.mmap.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "seek offset past end of file",
+            )
+            .into());
+        }
+        self.pos = offset;
+        self.last_ts_ns = None;
+        Ok(())
+    }
+
+    pub fn next_event(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
+        while self.pos < self.mmap.len() {
+            let (event, len) = decode_mmap_record(
+                &self.mmap,
+                self.pos,
+                &mut self.symbols,
+                &mut self.last_ts_ns,
+            )?;
+            self.pos += len;
+            if event.is_some() {
+                return Ok(event);
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Decodes the record at `pos`, returning its event (`None` for a file header) and length.
+#[cfg(feature = "mmap")]
+fn decode_mmap_record(
+    bytes: &[u8],
+    pos: usize,
+    symbols: &mut SymbolTable,
+    last_ts_ns: &mut Option<u64>,
+) -> Result<(Option<MarketEvent>, usize), ReplayError> {
+    let remaining = &bytes[pos..];
+    if remaining.len() < 4 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "truncated binary record prefix",
+        )
+        .into());
+    }
+
+    let prefix = [remaining[0], remaining[1], remaining[2], remaining[3]];
+    let is_file_header = prefix == codec::BIN_FILE_MAGIC;
+    if prefix == codec::BIN_RECORD_MAGIC || is_file_header {
+        if remaining.len() < codec::BIN_RECORD_HEADER_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "truncated binary record header",
+            )
+            .into());
+        }
+
+        let header_slice = &remaining[..codec::BIN_RECORD_HEADER_LEN];
+        let header = if is_file_header {
+            codec::decode_bin_file_frame(header_slice)?
+        } else {
+            codec::decode_event_bin_header(header_slice)?
+        };
+        let record_len = codec::BIN_RECORD_HEADER_LEN + header.payload_len;
+        if remaining.len() < record_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "truncated binary payload",
+            )
+            .into());
+        }
+
+        let record = &remaining[..record_len];
+        if is_file_header {
+            *last_ts_ns = codec::decode_bin_file_header(record, symbols)?;
+            return Ok((None, record_len));
+        }
+        let event = codec::decode_event_bin_record_in_stream(record, symbols, last_ts_ns)?;
+        Ok((Some(event), record_len))
+    } else {
+        let payload_len = u32::from_le_bytes(prefix) as usize;
+        let record_len = 4 + payload_len;
+        if remaining.len() < record_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "truncated legacy binary payload",
+            )
+            .into());
+        }
+        let event = codec::decode_event_bin_payload(&remaining[4..record_len], symbols)?;
+        Ok((Some(event), record_len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use lob_core::{LevelUpdate, Price, Qty, Side, SymbolId, SymbolTable};
+    #[cfg(feature = "bin")]
+    use orderbook::OrderBook;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reads_in_order_and_handles_eof() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let path = dir.path().join("events.log");
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let eth = symbols.try_intern("ETH-USD")?;
+
+        let event_one = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol: btc,
+            updates: vec![LevelUpdate {
+                side: Side::Bid,
+                price: Price::new(100)?,
+                qty: Qty::new(5)?,
+            }],
+        };
+
+        let event_two = MarketEvent::L2Delta {
+            ts_ns: 2,
+            symbol: eth,
+            updates: vec![LevelUpdate {
+                side: Side::Ask,
+                price: Price::new(200)?,
+                qty: Qty::new(1)?,
+            }],
+        };
+
+        let mut file = File::create(&path)?;
+        writeln!(
+            file,
+            "{}",
+            codec::encode_event_json_line(&event_one, &symbols)?
+        )?;
+        writeln!(
+            file,
+            "{}",
+            codec::encode_event_json_line(&event_two, &symbols)?
+        )?;
+
+        let mut reader = ReplayReader::open(&path)?;
+        assert_eq!(reader.next_event()?.unwrap(), event_one);
+        assert_eq!(reader.next_event()?.unwrap(), event_two);
+        assert_eq!(reader.next_event()?, None);
+        assert_eq!(reader.next_event()?, None);
+
+        Ok(())
+    }
+
+    fn out_of_order_trades(symbol: SymbolId) -> Result<Vec<MarketEvent>, CoreError> {
+        [5u64, 3, 7]
+            .into_iter()
+            .map(|ts_ns| {
+                Ok(MarketEvent::Trade {
+                    ts_ns,
+                    symbol,
+                    price: Price::new(100)?,
+                    qty: Qty::new(1)?,
+                    aggressor_side: Side::Bid,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strict_validation_rejects_backwards_timestamps() -> Result<(), Box<dyn std::error::Error>> {
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let events = out_of_order_trades(btc)?;
+        let mut jsonl = String::new();
+        for event in &events {
+            jsonl.push_str(&codec::encode_event_json_line(event, &symbols)?);
+            jsonl.push('\n');
+        }
+
+        let mut permissive =
+            ReplayReader::from_reader(std::io::Cursor::new(jsonl.clone()), ReplayFormat::Jsonl)?;
+        let mut seen = Vec::new();
+        while let Some(event) = permissive.next_event()? {
+            seen.push(event);
+        }
+        assert_eq!(seen, events);
+
+        let mut strict =
+            ReplayReader::from_reader(std::io::Cursor::new(jsonl), ReplayFormat::Jsonl)?
+                .with_validation(Validation::StrictMonotonicTs);
+        assert_eq!(strict.next_event()?.as_ref(), Some(&events[0]));
+        assert!(matches!(
+            strict.next_event(),
+            Err(ReplayError::NonMonotonicTimestamp { prev: 5, got: 3 })
+        ));
+
+        #[cfg(feature = "bin")]
+        {
+            let mut bytes = Vec::new();
+            codec::encode_events_bin(&events, &symbols, &mut bytes)?;
+            let mut strict =
+                ReplayReader::from_reader(std::io::Cursor::new(bytes), ReplayFormat::Bin)?
+                    .with_validation(Validation::StrictMonotonicTs);
+            assert!(strict.next_event()?.is_some());
+            assert!(matches!(
+                strict.next_event(),
+                Err(ReplayError::NonMonotonicTimestamp { prev: 5, got: 3 })
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn synthetic_feed_is_reproducible_for_a_seed() {
+        use rand::SeedableRng;
+
+        let symbol = SymbolId::from_u32(0);
+        let feed = |seed| {
+            SyntheticFeed::new(symbol, rand::rngs::StdRng::seed_from_u64(seed))
+                .take(64)
+                .collect::<Vec<_>>()
+        };
+        let events = feed(7);
+        assert_eq!(events, feed(7));
+        assert_ne!(events, feed(8));
+        assert!(events
+            .iter()
+            .enumerate()
+            .all(|(index, event)| event.ts_ns() == index as u64
+                && matches!(event, MarketEvent::L2Delta { updates, .. } if updates.len() == 1)));
+    }
+
+    #[test]
+    fn synthetic_feed_config_validation() {
+        let valid = SyntheticFeedConfig::default();
+        assert_eq!(valid.validate(), Ok(()));
+        let config = SyntheticFeedConfig {
+            min_qty_lots: 5,
+            max_qty_lots: 2,
+            ..valid
+        };
+        assert_eq!(
+            config.validate(),
+            Err(FeedConfigError::QtyRange { min: 5, max: 2 })
+        );
+        let config = SyntheticFeedConfig {
+            remove_prob: 1.5,
+            ..valid
+        };
+        assert_eq!(
+            config.validate(),
+            Err(FeedConfigError::RemoveProbability(1.5))
+        );
+        let config = SyntheticFeedConfig {
+            trade_prob: -0.1,
+            ..valid
+        };
+        assert_eq!(
+            config.validate(),
+            Err(FeedConfigError::TradeProbability(-0.1))
+        );
+        let config = SyntheticFeedConfig {
+            levels: MAX_SYNTHETIC_LEVELS,
+            ..valid
+        };
+        assert_eq!(config.validate(), Ok(()));
+        let config = SyntheticFeedConfig {
+            levels: u32::MAX,
+            ..valid
+        };
+        assert_eq!(
+            config.validate(),
+            Err(FeedConfigError::TooManyLevels {
+                levels: u32::MAX,
+                max: MAX_SYNTHETIC_LEVELS,
+            })
+        );
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_snapshot_first_yields_two_sided_book(seed in proptest::prelude::any::<u64>()) {
+            use rand::SeedableRng;
+
+            let symbol = SymbolId::from_u32(0);
+            let mut feed = SyntheticFeed::new(symbol, rand::rngs::StdRng::seed_from_u64(seed))
+                .with_snapshot_first(true);
+            let snapshot = feed.next_event();
+            let is_snapshot = matches!(snapshot, MarketEvent::L2Snapshot { ts_ns: 0, .. });
+            proptest::prop_assert!(is_snapshot);
+
+            let mut book = orderbook::OrderBook::new(symbol);
+            proptest::prop_assert!(book.apply(&snapshot));
+            proptest::prop_assert_eq!(book.bid_levels().count(), 5);
+            proptest::prop_assert_eq!(book.ask_levels().count(), 5);
+            proptest::prop_assert!(!book.is_crossed());
+            proptest::prop_assert_eq!(feed.next_event().ts_ns(), 1);
+        }
+    }
+
+    #[test]
+    fn sequence_tracker_reports_missing_ranges() {
+        let mut tracker = SequenceTracker::new();
+        assert_eq!(tracker.observe(10), None);
+        assert_eq!(tracker.observe(11), None);
+        assert_eq!(tracker.observe(14), Some(12..=13));
+        assert_eq!(tracker.observe(14), None);
+        assert_eq!(tracker.observe(9), None);
+        assert_eq!(tracker.observe(16), Some(15..=15));
+
+        assert_eq!(tracker.last_seq(), Some(16));
+        assert_eq!(tracker.gap_count(), 2);
+        assert_eq!(tracker.gaps(), &[12..=13, 15..=15]);
+    }
+
+    #[test]
+    fn reader_tracks_json_sequence_gaps() -> Result<(), Box<dyn std::error::Error>> {
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let eth = symbols.try_intern("ETH-USD")?;
+        let mut jsonl = String::new();
+        for (seq, symbol) in [(1, btc), (2, eth), (4, btc), (5, btc)] {
+            let event = lob_core::SequencedEvent {
+                seq: Some(seq),
+                event: MarketEvent::Trade {
+                    ts_ns: seq,
+                    symbol,
+                    price: Price::new(100)?,
+                    qty: Qty::new(1)?,
+                    aggressor_side: Side::Bid,
+                },
+            };
+            jsonl.push_str(&codec::encode_sequenced_event_json_line(&event, &symbols)?);
+            jsonl.push('\n');
+        }
+
+        // Filtered-out symbols still advance the feed sequence.
+        let mut reader =
+            ReplayReader::from_reader(std::io::Cursor::new(jsonl), ReplayFormat::Jsonl)?
+                .with_symbol_filter(&["BTC-USD"])
+                .with_sequence_tracking();
+        let mut read = 0;
+        while reader.next_event()?.is_some() {
+            read += 1;
+        }
+        assert_eq!(read, 3);
+
+        let tracker = reader.sequence_tracker().expect("tracking enabled");
+        assert_eq!(tracker.gap_count(), 1);
+        assert_eq!(tracker.gaps(), &[3..=3]);
+        assert_eq!(tracker.last_seq(), Some(5));
+        Ok(())
+    }
+
+    #[test]
+    fn symbol_filter_yields_only_allowed_symbols() -> Result<(), Box<dyn std::error::Error>> {
+        let mut symbols = SymbolTable::new();
+        let eth = symbols.try_intern("ETH-USD")?;
+        let btc = symbols.try_intern("BTC-USD")?;
+        let sol = symbols.try_intern("SOL-USD")?;
+        let mut jsonl = String::new();
+        for (ts_ns, symbol) in [(1, eth), (2, btc), (3, sol), (4, btc), (5, eth)] {
+            let event = MarketEvent::Trade {
+                ts_ns,
+                symbol,
+                price: Price::new(100)?,
+                qty: Qty::new(1)?,
+                aggressor_side: Side::Ask,
+            };
+            jsonl.push_str(&codec::encode_event_json_line(&event, &symbols)?);
+            jsonl.push('\n');
+        }
+
+        let mut reader =
+            ReplayReader::from_reader(std::io::Cursor::new(jsonl), ReplayFormat::Jsonl)?
+                .with_symbol_filter(&["BTC-USD"]);
+        let mut seen = Vec::new();
+        while let Some(event) = reader.next_event()? {
+            assert_eq!(event.symbol(), btc);
+            seen.push(event.ts_ns());
+        }
+        assert_eq!(seen, vec![2, 4]);
+        assert_eq!(reader.next_event()?, None);
+        Ok(())
+    }
+
+    fn generated_trades(symbol: SymbolId, count: u64) -> Result<Vec<MarketEvent>, CoreError> {
+        (0..count)
+            .map(|i| {
+                Ok(MarketEvent::Trade {
+                    ts_ns: 1_000 + i * 10,
+                    symbol,
+                    price: Price::new(100 + (i % 4) as i64)?,
+                    qty: Qty::new(1 + (i % 3) as i64)?,
+                    aggressor_side: Side::Bid,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn progress_callback_fires_every_n_events() -> Result<(), Box<dyn std::error::Error>> {
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let mut jsonl = String::new();
+        for event in generated_trades(btc, 25)? {
+            jsonl.push_str(&codec::encode_event_json_line(&event, &symbols)?);
+            jsonl.push('\n');
+        }
+
+        let calls = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let seen = std::rc::Rc::clone(&calls);
+        let mut reader =
+            ReplayReader::from_reader(std::io::Cursor::new(jsonl), ReplayFormat::Jsonl)?;
+        reader.set_progress(10, Box::new(move |count| seen.borrow_mut().push(count)));
+        while reader.next_event()?.is_some() {}
+
+        assert_eq!(*calls.borrow(), vec![10, 20]);
+        assert_eq!(reader.events_read(), 25);
+        Ok(())
+    }
+
+    #[test]
+    fn seek_and_bound_replay_a_time_window() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let path = dir.path().join("window.jsonl");
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let events = generated_trades(btc, 100)?;
+        let mut file = File::create(&path)?;
+        for event in &events {
+            writeln!(file, "{}", codec::encode_event_json_line(event, &symbols)?)?;
+        }
+
+        let mut reader = ReplayReader::open(&path)?.with_ts_bound(1_505);
+        reader.seek_to_ts(1_495)?;
+        let mut window = Vec::new();
+        while let Some(event) = reader.next_event()? {
+            window.push(event);
+        }
+        assert_eq!(window, events[50..51]);
+        assert_eq!(reader.next_event()?, None);
+
+        let mut reader = ReplayReader::open(&path)?;
+        let progress = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let seen = std::rc::Rc::clone(&progress);
+        reader.set_progress(10, Box::new(move |count| seen.borrow_mut().push(count)));
+        reader.seek_to_ts(1_500)?;
+        assert_eq!(reader.events_read(), 0);
+        assert!(progress.borrow().is_empty());
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[50]));
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[51]));
+        assert_eq!(reader.events_read(), 2);
+
+        #[cfg(feature = "mmap")]
+        {
+            let bin_path = dir.path().join("window.bin");
+            let mut bytes = Vec::new();
+            codec::encode_events_bin(&events, &symbols, &mut bytes)?;
+            std::fs::write(&bin_path, bytes)?;
+            let mut mmap_reader = MmapReplayReader::open(&bin_path)?;
+            mmap_reader.seek_to_ts(1_500)?;
+            assert_eq!(mmap_reader.next_event()?.as_ref(), Some(&events[50]));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn paced_replay_honours_recorded_gaps() -> Result<(), Box<dyn std::error::Error>> {
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let mut jsonl = String::new();
+        for ts_ns in [0u64, 4_000_000, 8_000_000] {
+            let event = MarketEvent::Trade {
+                ts_ns,
+                symbol: btc,
+                price: Price::new(100)?,
+                qty: Qty::new(1)?,
+                aggressor_side: Side::Bid,
+            };
+            jsonl.push_str(&codec::encode_event_json_line(&event, &symbols)?);
+            jsonl.push('\n');
+        }
+
+        let reader =
+            ReplayReader::from_reader(std::io::Cursor::new(jsonl.clone()), ReplayFormat::Jsonl)?;
+        let mut paced = PacedReplay::new(reader);
+        let started = Instant::now();
+        let mut count = 0;
+        while paced.next_event_paced(2.0)?.is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        assert!(started.elapsed() >= Duration::from_millis(4));
+
+        let reader = ReplayReader::from_reader(std::io::Cursor::new(jsonl), ReplayFormat::Jsonl)?;
+        let mut unpaced = PacedReplay::new(reader);
+        let mut count = 0;
+        while unpaced.next_event_paced(0.0)?.is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn merging_reader_interleaves_files_by_timestamp() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let first = dir.path().join("hour-00.jsonl");
+        let second = dir.path().join("hour-01.jsonl");
+        let shards: [(&Path, &str, &[u64]); 2] = [
+            (&first, "BTC-USD", &[1, 4, 6, 9]),
+            (&second, "ETH-USD", &[2, 4, 5, 10, 11]),
+        ];
+        for (path, name, stamps) in shards {
+            let mut symbols = SymbolTable::new();
+            let symbol = symbols.try_intern(name)?;
+            let mut file = File::create(path)?;
+            for &ts_ns in stamps {
+                let event = MarketEvent::Trade {
+                    ts_ns,
+                    symbol,
+                    price: Price::new(100)?,
+                    qty: Qty::new(1)?,
+                    aggressor_side: Side::Bid,
+                };
+                writeln!(file, "{}", codec::encode_event_json_line(&event, &symbols)?)?;
+            }
+        }
+
+        let mut merged = MergingReplayReader::open(&[&first, &second], ReplayFormat::Jsonl)?;
+        let mut seen = Vec::new();
+        while let Some(event) = merged.next_event()? {
+            seen.push((
+                event.ts_ns(),
+                merged.symbols().resolve(event.symbol()).to_string(),
+            ));
+        }
+        assert_eq!(seen.len(), 9);
+        assert!(seen.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+        assert_eq!(seen[2], (4, "BTC-USD".to_string()));
+        assert_eq!(seen[3], (4, "ETH-USD".to_string()));
+        assert_eq!(merged.next_event()?, None);
+        Ok(())
+    }
+
+    #[cfg(feature = "bin")]
+    #[test]
+    fn decodes_bin_records_from_in_memory_reader() -> Result<(), Box<dyn std::error::Error>> {
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let events = vec![
+            MarketEvent::L2Delta {
+                ts_ns: 1,
+                symbol: btc,
+                updates: vec![LevelUpdate {
+                    side: Side::Bid,
+                    price: Price::new(100)?,
+                    qty: Qty::new(2)?,
+                }],
+            },
+            MarketEvent::Trade {
+                ts_ns: 2,
+                symbol: btc,
+                price: Price::new(100)?,
+                qty: Qty::new(1)?,
+                aggressor_side: Side::Ask,
+            },
+        ];
+        let mut bytes = Vec::new();
+        codec::encode_events_bin(&events, &symbols, &mut bytes)?;
+
+        let mut reader = ReplayReader::from_reader(std::io::Cursor::new(bytes), ReplayFormat::Bin)?;
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[0]));
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[1]));
+        assert_eq!(reader.next_event()?, None);
+        Ok(())
+    }
 
-            let event = codec::decode_event_bin_record(&self.bin_buf, &mut self.symbols)?;
-            Ok(Some(event))
-        } else {
-            let payload_len = u32::from_le_bytes(prefix_buf) as usize;
-            self.bin_buf.resize(payload_len, 0);
-            let mut read = 0usize;
-            while read < payload_len {
-                let n = self.reader.read(&mut self.bin_buf[read..])?;
-                if n == 0 {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::UnexpectedEof,
-                        "truncated legacy binary payload",
-                    )
-                    .into());
-                }
-                read += n;
+    #[test]
+    fn skip_malformed_policy_steps_over_a_bad_json_line() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let trades = out_of_order_trades(btc)?;
+        let mut text = String::new();
+        for (index, event) in trades.iter().enumerate() {
+            if index == 1 {
+                text.push_str("{\"type\":\"trade\",\"ts_ns\":\n");
             }
+            text.push_str(&codec::encode_event_json_line(event, &symbols)?);
+            text.push('\n');
+        }
 
-            let event = codec::decode_event_bin_payload(&self.bin_buf, &mut self.symbols)?;
-            Ok(Some(event))
+        let mut strict = ReplayReader::from_reader(
+            std::io::Cursor::new(text.clone().into_bytes()),
+            ReplayFormat::Jsonl,
+        )?;
+        assert_eq!(strict.next_event()?.as_ref(), Some(&trades[0]));
+        assert!(matches!(strict.next_event(), Err(ReplayError::Decode(_))));
+
+        let mut reader = ReplayReader::from_reader(
+            std::io::Cursor::new(text.into_bytes()),
+            ReplayFormat::Jsonl,
+        )?
+        .with_error_policy(ErrorPolicy::SkipMalformed);
+        let mut replayed = Vec::new();
+        while let Some(event) = reader.next_event()? {
+            replayed.push(event);
         }
+        assert_eq!(replayed, trades);
+        assert_eq!(reader.malformed_count(), 1);
+        Ok(())
     }
-}
 
-#[cfg(feature = "mmap")]
-impl MmapReplayReader {
-    pub fn open(path: &Path) -> Result<Self, ReplayError> {
-        Self::open_with_symbols(path, SymbolTable::new())
-    }
+    #[cfg(feature = "bin")]
+    #[test]
+    fn skip_malformed_policy_resyncs_binary_records_on_magic(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let trades = out_of_order_trades(btc)?;
+        let mut bytes = Vec::new();
+        for (index, event) in trades.iter().enumerate() {
+            let mut record = codec::encode_event_bin_record(event, &symbols)?;
+            if index == 1 {
+                // Flip a payload byte so the CRC check rejects the record.
+                let last = record.len() - 1;
+                record[last] ^= 0xFF;
+                bytes.extend_from_slice(b"junk");
+            }
+            bytes.extend_from_slice(&record);
+        }
 
-    pub fn open_with_predeclared_symbols<I, S>(path: &Path, symbols: I) -> Result<Self, ReplayError>
-    where
-        I: IntoIterator<Item = S>,
-        S: AsRef<str>,
-    {
-        let symbols = SymbolTable::try_from_symbols(symbols)?;
-        Self::open_with_symbols(path, symbols)
+        let mut reader = ReplayReader::from_reader(std::io::Cursor::new(bytes), ReplayFormat::Bin)?
+            .with_error_policy(ErrorPolicy::SkipMalformed);
+        assert_eq!(reader.next_event()?.as_ref(), Some(&trades[0]));
+        assert_eq!(reader.next_event()?.as_ref(), Some(&trades[2]));
+        assert_eq!(reader.next_event()?, None);
+        assert_eq!(reader.malformed_count(), 2);
+        Ok(())
     }
 
-    pub fn open_with_symbols(path: &Path, symbols: SymbolTable) -> Result<Self, ReplayError> {
-        let file = File::open(path)?;
-        let mmap = unsafe { memmap2::Mmap::map(&file)? };
-        Ok(Self {
-            mmap,
-            pos: 0,
-            symbols,
-        })
+    #[cfg(feature = "bin")]
+    #[test]
+    fn resync_recovers_the_record_after_injected_garbage() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let trades = out_of_order_trades(btc)?;
+        let mut bytes = codec::encode_event_bin_record(&trades[0], &symbols)?;
+        // Read as a 3-byte legacy record that fails to decode, then two stray bytes.
+        bytes.extend_from_slice(&[3, 0, 0, 0, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE]);
+        let second_offset = bytes.len() as u64;
+        bytes.extend_from_slice(&codec::encode_event_bin_record(&trades[1], &symbols)?);
+
+        let mut reader = ReplayReader::from_reader(std::io::Cursor::new(bytes), ReplayFormat::Bin)?;
+        assert_eq!(reader.next_event()?.as_ref(), Some(&trades[0]));
+        assert!(reader.next_event().is_err());
+        assert_eq!(reader.resync()?, 2);
+        assert_eq!(reader.resync()?, 0);
+        assert_eq!(reader.next_event()?.as_ref(), Some(&trades[1]));
+        assert_eq!(reader.record_offset(), second_offset);
+        assert_eq!(reader.next_event()?, None);
+        assert_eq!(reader.resync()?, 0);
+        Ok(())
     }
 
-    pub fn next_event(&mut self) -> Result<Option<MarketEvent>, ReplayError> {
-        if self.pos == self.mmap.len() {
-            return Ok(None);
-        }
-        if self.mmap.len().saturating_sub(self.pos) < 4 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::UnexpectedEof,
-                "truncated binary record prefix",
-            )
-            .into());
-        }
-
-        let prefix = [
-            self.mmap[self.pos],
-            self.mmap[self.pos + 1],
-            self.mmap[self.pos + 2],
-            self.mmap[self.pos + 3],
+    #[cfg(feature = "bin")]
+    #[test]
+    fn symbol_table_header_resolves_id_records() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let path = dir.path().join("events-with-header.bin");
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let eth = symbols.try_intern("ETH-USD")?;
+        let events = vec![
+            MarketEvent::Trade {
+                ts_ns: 1,
+                symbol: eth,
+                price: Price::new(200)?,
+                qty: Qty::new(1)?,
+                aggressor_side: Side::Bid,
+            },
+            MarketEvent::L2Delta {
+                ts_ns: 2,
+                symbol: btc,
+                updates: vec![LevelUpdate {
+                    side: Side::Ask,
+                    price: Price::new(101)?,
+                    qty: Qty::new(3)?,
+                }],
+            },
         ];
 
-        if prefix == codec::BIN_RECORD_MAGIC {
-            if self.mmap.len().saturating_sub(self.pos) < codec::BIN_RECORD_HEADER_LEN {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::UnexpectedEof,
-                    "truncated binary record header",
-                )
-                .into());
-            }
+        let mut bytes = codec::encode_bin_file_header(&symbols)?;
+        for event in &events {
+            bytes.extend_from_slice(&codec::encode_event_bin_id_record(event)?);
+        }
+        std::fs::write(&path, &bytes)?;
 
-            let header_slice = &self.mmap[self.pos..self.pos + codec::BIN_RECORD_HEADER_LEN];
-            let header = codec::decode_event_bin_header(header_slice)?;
-            let record_len = codec::BIN_RECORD_HEADER_LEN + header.payload_len;
-            if self.mmap.len().saturating_sub(self.pos) < record_len {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::UnexpectedEof,
-                    "truncated binary payload",
-                )
-                .into());
-            }
+        let mut reader = ReplayReader::open_with_format_and_predeclared_symbols(
+            &path,
+            ReplayFormat::Bin,
+            ["BTC-USD"],
+        )?;
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[0]));
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[1]));
+        assert_eq!(reader.next_event()?, None);
 
-            let record = &self.mmap[self.pos..self.pos + record_len];
-            self.pos += record_len;
-            let event = codec::decode_event_bin_record(record, &mut self.symbols)?;
-            Ok(Some(event))
-        } else {
-            let payload_len = u32::from_le_bytes(prefix) as usize;
-            let record_len = 4 + payload_len;
-            if self.mmap.len().saturating_sub(self.pos) < record_len {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::UnexpectedEof,
-                    "truncated legacy binary payload",
-                )
-                .into());
-            }
-            let payload = &self.mmap[self.pos + 4..self.pos + record_len];
-            self.pos += record_len;
-            let event = codec::decode_event_bin_payload(payload, &mut self.symbols)?;
-            Ok(Some(event))
+        #[cfg(feature = "mmap")]
+        {
+            let mut mmap_reader = MmapReplayReader::open(&path)?;
+            assert_eq!(mmap_reader.next_event()?.as_ref(), Some(&events[0]));
+            assert_eq!(mmap_reader.next_event()?.as_ref(), Some(&events[1]));
+            assert_eq!(mmap_reader.next_event()?, None);
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::io::Write;
+        let mut conflicting = ReplayReader::open_with_format_and_predeclared_symbols(
+            &path,
+            ReplayFormat::Bin,
+            ["ETH-USD"],
+        )?;
+        assert!(matches!(
+            conflicting.next_event(),
+            Err(ReplayError::Decode(
+                codec::CodecError::SymbolIdConflict { .. }
+            ))
+        ));
+        Ok(())
+    }
 
-    use super::*;
-    use lob_core::{LevelUpdate, Price, Qty, Side, SymbolId, SymbolTable};
     #[cfg(feature = "bin")]
-    use orderbook::OrderBook;
-    use tempfile::tempdir;
+    #[test]
+    fn delta_timestamp_encoding_reconstructs_ts_and_shrinks_output(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let events = (0..1000u64)
+            .map(|i| {
+                Ok(MarketEvent::Trade {
+                    ts_ns: 1_700_000_000_000_000_000 + i * 250 + i % 7,
+                    symbol: btc,
+                    price: Price::new(100 + (i % 5) as i64)?,
+                    qty: Qty::new(1 + (i % 3) as i64)?,
+                    aggressor_side: if i % 2 == 0 { Side::Bid } else { Side::Ask },
+                })
+            })
+            .collect::<Result<Vec<_>, CoreError>>()?;
+
+        let mut standard = Vec::new();
+        codec::encode_events_bin_with(
+            &events,
+            &symbols,
+            codec::BinEncoding::Standard,
+            &mut standard,
+        )?;
+        let mut delta = Vec::new();
+        codec::encode_events_bin_with(
+            &events,
+            &symbols,
+            codec::BinEncoding::DeltaTimestamps,
+            &mut delta,
+        )?;
+        assert!(delta.len() < standard.len());
+
+        let mut reader = ReplayReader::from_reader(std::io::Cursor::new(delta), ReplayFormat::Bin)?;
+        let mut decoded = Vec::new();
+        while let Some(event) = reader.next_event()? {
+            decoded.push(event);
+        }
+        assert_eq!(decoded, events);
+        Ok(())
+    }
 
+    #[cfg(all(feature = "bin", feature = "compression"))]
     #[test]
-    fn reads_in_order_and_handles_eof() -> Result<(), Box<dyn std::error::Error>> {
+    fn zstd_streams_match_uncompressed_events() -> Result<(), Box<dyn std::error::Error>> {
         let dir = tempdir()?;
-        let path = dir.path().join("events.log");
         let mut symbols = SymbolTable::new();
         let btc = symbols.try_intern("BTC-USD")?;
-        let eth = symbols.try_intern("ETH-USD")?;
+        let events: Vec<MarketEvent> = (0..100)
+            .map(|ts_ns| MarketEvent::L2Delta {
+                ts_ns,
+                symbol: btc,
+                updates: vec![LevelUpdate {
+                    side: if ts_ns % 2 == 0 { Side::Bid } else { Side::Ask },
+                    price: Price::new(100 + ts_ns as i64 % 7).unwrap(),
+                    qty: Qty::new(ts_ns as i64 % 3).unwrap(),
+                }],
+            })
+            .collect();
 
-        let event_one = MarketEvent::L2Delta {
-            ts_ns: 1,
-            symbol: btc,
-            updates: vec![LevelUpdate {
-                side: Side::Bid,
-                price: Price::new(100)?,
-                qty: Qty::new(5)?,
-            }],
+        let mut raw = Vec::new();
+        for event in &events {
+            raw.extend_from_slice(&codec::encode_event_bin_record(event, &symbols)?);
+        }
+        let compressed = zstd::encode_all(raw.as_slice(), 0)?;
+        let plain_path = dir.path().join("events.bin");
+        // Detection works by extension and, without one, by frame magic.
+        let zst_path = dir.path().join("events.bin.zst");
+        let sniffed_path = dir.path().join("events.compressed");
+        std::fs::write(&plain_path, &raw)?;
+        std::fs::write(&zst_path, &compressed)?;
+        std::fs::write(&sniffed_path, &compressed)?;
+
+        let read_all = |path: &Path| -> Result<Vec<MarketEvent>, ReplayError> {
+            let mut reader = ReplayReader::open_with_format(path, ReplayFormat::Bin)?;
+            let mut out = Vec::new();
+            while let Some(event) = reader.next_event()? {
+                out.push(event);
+            }
+            Ok(out)
         };
+        assert_eq!(read_all(&plain_path)?, events);
+        assert_eq!(read_all(&zst_path)?, events);
+        assert_eq!(read_all(&sniffed_path)?, events);
+        Ok(())
+    }
 
-        let event_two = MarketEvent::L2Delta {
-            ts_ns: 2,
-            symbol: eth,
-            updates: vec![LevelUpdate {
-                side: Side::Ask,
-                price: Price::new(200)?,
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn reads_msgpack_records() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let path = dir.path().join("events.msgpack");
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let events = vec![
+            MarketEvent::L2Snapshot {
+                ts_ns: 1,
+                symbol: btc,
+                bids: vec![(Price::new(100)?, Qty::new(1)?)],
+                asks: vec![(Price::new(101)?, Qty::new(1)?)],
+            },
+            MarketEvent::Trade {
+                ts_ns: 2,
+                symbol: btc,
+                price: Price::new(101)?,
                 qty: Qty::new(1)?,
-            }],
-        };
+                aggressor_side: Side::Bid,
+            },
+        ];
 
         let mut file = File::create(&path)?;
-        writeln!(
-            file,
-            "{}",
-            codec::encode_event_json_line(&event_one, &symbols)?
-        )?;
-        writeln!(
-            file,
-            "{}",
-            codec::encode_event_json_line(&event_two, &symbols)?
-        )?;
+        for event in &events {
+            file.write_all(&codec::encode_event_msgpack(event, &symbols)?)?;
+        }
 
-        let mut reader = ReplayReader::open(&path)?;
-        assert_eq!(reader.next_event()?.unwrap(), event_one);
-        assert_eq!(reader.next_event()?.unwrap(), event_two);
+        let mut reader = ReplayReader::open_with_format(&path, ReplayFormat::MsgPack)?;
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[0]));
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[1]));
         assert_eq!(reader.next_event()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn reads_multi_row_csv_events() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let path = dir.path().join("events.csv");
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let events = vec![
+            MarketEvent::L2Snapshot {
+                ts_ns: 1,
+                symbol: btc,
+                bids: vec![
+                    (Price::new(100)?, Qty::new(1)?),
+                    (Price::new(99)?, Qty::new(2)?),
+                ],
+                asks: vec![(Price::new(101)?, Qty::new(1)?)],
+            },
+            MarketEvent::L2Delta {
+                ts_ns: 2,
+                symbol: btc,
+                updates: vec![
+                    LevelUpdate {
+                        side: Side::Bid,
+                        price: Price::new(100)?,
+                        qty: Qty::new(0)?,
+                    },
+                    LevelUpdate {
+                        side: Side::Ask,
+                        price: Price::new(102)?,
+                        qty: Qty::new(4)?,
+                    },
+                ],
+            },
+        ];
+
+        let mut file = File::create(&path)?;
+        writeln!(file, "{}", codec::CSV_HEADER)?;
+        for event in &events {
+            writeln!(file, "{}", codec::encode_event_csv_line(event, &symbols)?)?;
+        }
+
+        let mut reader = ReplayReader::open_with_format(&path, ReplayFormat::Csv)?;
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[0]));
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[1]));
         assert_eq!(reader.next_event()?, None);
 
+        let truncated = dir.path().join("truncated.csv");
+        std::fs::write(&truncated, "snapshot,1,BTC-USD,bid,100,1,1\n")?;
+        let mut reader = ReplayReader::open_with_format(&truncated, ReplayFormat::Csv)?;
+        assert!(reader.next_event().is_err());
+
         Ok(())
     }
 
@@ -503,6 +2246,37 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn offset_index_supports_binary_search_seeks() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let path = dir.path().join("indexed.bin");
+        let mut symbols = SymbolTable::new();
+        let btc = symbols.try_intern("BTC-USD")?;
+        let events = generated_trades(btc, 64)?;
+        let mut bytes = codec::encode_bin_file_header(&symbols)?;
+        for event in &events {
+            bytes.extend_from_slice(&codec::encode_event_bin_id_record(event)?);
+        }
+        std::fs::write(&path, bytes)?;
+
+        let mut reader = MmapReplayReader::open(&path)?;
+        let index = reader.build_index()?;
+        assert_eq!(index.len(), events.len());
+        assert!(index.windows(2).all(|pair| pair[0].1 < pair[1].1));
+
+        let target = events[40].ts_ns() - 5;
+        let slot = index.partition_point(|(ts_ns, _)| *ts_ns < target);
+        reader.seek_to_offset(index[slot].1)?;
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[40]));
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[41]));
+
+        reader.seek_to_offset(index[0].1)?;
+        assert_eq!(reader.next_event()?.as_ref(), Some(&events[0]));
+        assert!(reader.seek_to_offset(usize::MAX).is_err());
+        Ok(())
+    }
+
     #[test]
     fn replay_symbol_mapping_is_deterministic_for_same_stream(
     ) -> Result<(), Box<dyn std::error::Error>> {
@@ -542,11 +2316,7 @@ mod tests {
             let mut reader = ReplayReader::open(path)?;
             let mut ids = Vec::new();
             while let Some(event) = reader.next_event()? {
-                let symbol = match event {
-                    MarketEvent::L2Delta { symbol, .. } => symbol,
-                    MarketEvent::L2Snapshot { symbol, .. } => symbol,
-                };
-                ids.push(symbol);
+                ids.push(event.symbol());
             }
             Ok(ids)
         }
@@ -605,15 +2375,8 @@ mod tests {
         let first = reader.next_event()?.expect("first event");
         let second = reader.next_event()?.expect("second event");
 
-        let first_symbol = match first {
-            MarketEvent::L2Delta { symbol, .. } | MarketEvent::L2Snapshot { symbol, .. } => symbol,
-        };
-        let second_symbol = match second {
-            MarketEvent::L2Delta { symbol, .. } | MarketEvent::L2Snapshot { symbol, .. } => symbol,
-        };
-
-        assert_eq!(first_symbol, SymbolId::from_u32(1));
-        assert_eq!(second_symbol, SymbolId::from_u32(0));
+        assert_eq!(first.symbol(), SymbolId::from_u32(1));
+        assert_eq!(second.symbol(), SymbolId::from_u32(0));
 
         Ok(())
     }
@@ -1,10 +1,13 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 
-use lob_core::Side;
+use lob_core::{Price, Side, SymbolId};
+use serde::Serialize;
 use strategy_api::ContextSnapshot;
 use trading_types::Intent;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum RiskAction {
     Allow(Intent),
     Reject { reason: String },
@@ -15,14 +18,22 @@ pub trait RiskPolicy {
     fn evaluate(&self, ctx: &ContextSnapshot, intent: &Intent) -> RiskAction;
 }
 
+/// Audit hook notified of every policy `Reject` and `Transform`, with the intent as that
+/// policy saw it.
+pub trait RiskObserver {
+    fn on_decision(&self, intent: &Intent, action: &RiskAction);
+}
+
 pub struct RiskEngine {
     policies: Vec<Box<dyn RiskPolicy>>,
+    observer: Option<Box<dyn RiskObserver>>,
 }
 
 impl RiskEngine {
     pub fn new() -> Self {
         Self {
             policies: Vec::new(),
+            observer: None,
         }
     }
 
@@ -31,10 +42,21 @@ impl RiskEngine {
         self
     }
 
+    pub fn with_observer(mut self, observer: Box<dyn RiskObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
     pub fn evaluate(&self, ctx: &ContextSnapshot, intent: &Intent) -> RiskAction {
         let mut current = intent.clone();
         for policy in &self.policies {
-            match policy.evaluate(ctx, &current) {
+            let action = policy.evaluate(ctx, &current);
+            if let Some(observer) = &self.observer {
+                if !matches!(action, RiskAction::Allow(_)) {
+                    observer.on_decision(&current, &action);
+                }
+            }
+            match action {
                 RiskAction::Allow(next) | RiskAction::Transform(next) => {
                     current = next;
                 }
@@ -45,6 +67,35 @@ impl RiskEngine {
     }
 }
 
+/// Tallies rejects by reason. Clones share the same counts, so keep one to query after
+/// handing another to `RiskEngine::with_observer`.
+#[derive(Debug, Clone, Default)]
+pub struct CountingObserver {
+    rejects: Rc<RefCell<HashMap<String, u64>>>,
+}
+
+impl CountingObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rejects(&self, reason: &str) -> u64 {
+        self.rejects.borrow().get(reason).copied().unwrap_or(0)
+    }
+
+    pub fn total_rejects(&self) -> u64 {
+        self.rejects.borrow().values().sum()
+    }
+}
+
+impl RiskObserver for CountingObserver {
+    fn on_decision(&self, _intent: &Intent, action: &RiskAction) {
+        if let RiskAction::Reject { reason } = action {
+            *self.rejects.borrow_mut().entry(reason.clone()).or_insert(0) += 1;
+        }
+    }
+}
+
 impl Default for RiskEngine {
     fn default() -> Self {
         Self::new()
@@ -53,28 +104,48 @@ impl Default for RiskEngine {
 
 pub struct MaxPositionPolicy {
     limit_lots: i64,
+    symbol_limits: HashMap<SymbolId, i64>,
 }
 
 impl MaxPositionPolicy {
     pub fn new(limit_lots: i64) -> Self {
-        Self { limit_lots }
+        Self {
+            limit_lots,
+            symbol_limits: HashMap::new(),
+        }
+    }
+
+    /// Symbols missing from `limits` use `default_lots`.
+    pub fn per_symbol(limits: HashMap<SymbolId, i64>, default_lots: i64) -> Self {
+        Self {
+            limit_lots: default_lots,
+            symbol_limits: limits,
+        }
     }
 }
 
 impl RiskPolicy for MaxPositionPolicy {
     fn evaluate(&self, ctx: &ContextSnapshot, intent: &Intent) -> RiskAction {
-        let limit = self.limit_lots.abs();
+        // Cancels and replaces never grow the position, so they always pass.
+        let (side, qty) = match intent {
+            Intent::PlaceLimit { side, qty, .. }
+            | Intent::PlaceMarket { side, qty, .. }
+            | Intent::PlacePegged { side, qty, .. } => (*side, qty.lots()),
+            _ => return RiskAction::Allow(intent.clone()),
+        };
+
+        let limit = self
+            .symbol_limits
+            .get(&ctx.symbol)
+            .copied()
+            .unwrap_or(self.limit_lots)
+            .abs();
         if limit == 0 {
             return RiskAction::Reject {
                 reason: "position limit is zero".to_string(),
             };
         }
 
-        let (side, qty) = match intent {
-            Intent::PlaceLimit { side, qty, .. } => (*side, qty.lots()),
-            _ => return RiskAction::Allow(intent.clone()),
-        };
-
         let projected = if side == Side::Bid {
             ctx.position_lots + qty
         } else {
@@ -91,6 +162,37 @@ impl RiskPolicy for MaxPositionPolicy {
     }
 }
 
+/// Fat-finger guard on the size of a single order, independent of position.
+pub struct MaxOrderSizePolicy {
+    max_qty_lots: i64,
+}
+
+impl MaxOrderSizePolicy {
+    pub fn new(max_qty_lots: i64) -> Self {
+        Self { max_qty_lots }
+    }
+}
+
+impl RiskPolicy for MaxOrderSizePolicy {
+    fn evaluate(&self, _ctx: &ContextSnapshot, intent: &Intent) -> RiskAction {
+        let qty = match intent {
+            Intent::PlaceLimit { qty, .. }
+            | Intent::PlaceMarket { qty, .. }
+            | Intent::PlacePegged { qty, .. } => *qty,
+            Intent::Replace { new_qty, .. } => *new_qty,
+            _ => return RiskAction::Allow(intent.clone()),
+        };
+
+        if qty.lots() > self.max_qty_lots.abs() {
+            return RiskAction::Reject {
+                reason: "order size exceeds limit".to_string(),
+            };
+        }
+
+        RiskAction::Allow(intent.clone())
+    }
+}
+
 pub struct PriceBandPolicy {
     max_distance_ticks: i64,
 }
@@ -123,6 +225,48 @@ impl RiskPolicy for PriceBandPolicy {
     }
 }
 
+/// Like `PriceBandPolicy`, but moves out-of-band prices to the nearest band edge.
+pub struct PriceBandClampPolicy {
+    max_distance_ticks: i64,
+}
+
+impl PriceBandClampPolicy {
+    pub fn new(max_distance_ticks: i64) -> Self {
+        Self { max_distance_ticks }
+    }
+}
+
+impl RiskPolicy for PriceBandClampPolicy {
+    fn evaluate(&self, ctx: &ContextSnapshot, intent: &Intent) -> RiskAction {
+        let max_distance = self.max_distance_ticks.abs();
+        let Intent::PlaceLimit { price, .. } = intent else {
+            return RiskAction::Allow(intent.clone());
+        };
+        let Some(mid) = ctx.mid_price else {
+            return RiskAction::Allow(intent.clone());
+        };
+
+        let clamped = price.ticks().clamp(
+            mid.ticks().saturating_sub(max_distance),
+            mid.ticks().saturating_add(max_distance),
+        );
+        if clamped == price.ticks() {
+            return RiskAction::Allow(intent.clone());
+        }
+        let Ok(clamped) = Price::new(clamped) else {
+            return RiskAction::Reject {
+                reason: "price outside band".to_string(),
+            };
+        };
+
+        let mut transformed = intent.clone();
+        if let Intent::PlaceLimit { price, .. } = &mut transformed {
+            *price = clamped;
+        }
+        RiskAction::Transform(transformed)
+    }
+}
+
 pub struct RateLimitPolicy {
     max_per_sec: u64,
     window_bucket: RefCell<u64>,
@@ -170,18 +314,78 @@ impl RiskPolicy for RateLimitPolicy {
     }
 }
 
+/// Caps `qty * price` placed per one-second bucket. Only new placements consume budget;
+/// cancels and replaces are exempt since the policy cannot see the resting order they modify.
+/// Market orders are priced at the opposite touch, and pass unchecked without one.
+pub struct NotionalRateLimitPolicy {
+    max_notional_per_sec: i128,
+    window_bucket: RefCell<u64>,
+    notional: RefCell<i128>,
+}
+
+impl NotionalRateLimitPolicy {
+    pub fn new(max_notional_per_sec: i128) -> Self {
+        Self {
+            max_notional_per_sec,
+            window_bucket: RefCell::new(u64::MAX),
+            notional: RefCell::new(0),
+        }
+    }
+}
+
+impl RiskPolicy for NotionalRateLimitPolicy {
+    fn evaluate(&self, ctx: &ContextSnapshot, intent: &Intent) -> RiskAction {
+        let (price, qty) = match intent {
+            Intent::PlaceLimit { price, qty, .. } => (*price, *qty),
+            Intent::PlaceMarket { side, qty, .. } => {
+                let touch = match side {
+                    Side::Bid => ctx.best_ask,
+                    Side::Ask => ctx.best_bid,
+                };
+                let Some((price, _)) = touch else {
+                    return RiskAction::Allow(intent.clone());
+                };
+                (price, *qty)
+            }
+            _ => return RiskAction::Allow(intent.clone()),
+        };
+
+        let mut window_bucket = self.window_bucket.borrow_mut();
+        let mut notional = self.notional.borrow_mut();
+        let bucket = ctx.ts_ns / 1_000_000_000;
+        if *window_bucket != bucket {
+            *window_bucket = bucket;
+            *notional = 0;
+        }
+
+        let order_notional = price.ticks() as i128 * qty.lots() as i128;
+        if *notional + order_notional > self.max_notional_per_sec {
+            return RiskAction::Reject {
+                reason: "notional rate limit exceeded".to_string(),
+            };
+        }
+
+        *notional += order_notional;
+        RiskAction::Allow(intent.clone())
+    }
+}
+
 fn is_order_intent(intent: &Intent) -> bool {
     matches!(
         intent,
-        Intent::PlaceLimit { .. } | Intent::Cancel { .. } | Intent::Replace { .. }
+        Intent::PlaceLimit { .. }
+            | Intent::PlaceMarket { .. }
+            | Intent::PlacePegged { .. }
+            | Intent::Cancel { .. }
+            | Intent::Replace { .. }
     )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use lob_core::{Price, Qty, Side, SymbolId};
-    use trading_types::TimeInForce;
+    use lob_core::{Qty, Side, SymbolId};
+    use trading_types::{ClientOrderId, TimeInForce};
 
     fn ctx_with_mid(ts_ns: u64, position_lots: i64) -> ContextSnapshot {
         let symbol = SymbolId::from_u32(1);
@@ -195,6 +399,42 @@ mod tests {
         )
     }
 
+    #[test]
+    fn counting_observer_tallies_rejects_without_changing_action() {
+        let observer = CountingObserver::new();
+        let engine = RiskEngine::new()
+            .with_policy(MaxOrderSizePolicy::new(5))
+            .with_policy(MaxPositionPolicy::new(10))
+            .with_observer(Box::new(observer.clone()));
+        let place = |lots| Intent::PlaceLimit {
+            symbol: SymbolId::from_u32(1),
+            side: Side::Bid,
+            price: Price::new(101).unwrap(),
+            qty: Qty::new(lots).unwrap(),
+            tif: TimeInForce::Gtc,
+            tag: None,
+        };
+
+        assert_eq!(
+            engine.evaluate(&ctx_with_mid(1, 0), &place(6)),
+            RiskAction::Reject {
+                reason: "order size exceeds limit".to_string()
+            }
+        );
+        assert!(matches!(
+            engine.evaluate(&ctx_with_mid(1, 8), &place(3)),
+            RiskAction::Reject { .. }
+        ));
+        assert_eq!(
+            engine.evaluate(&ctx_with_mid(1, 0), &place(1)),
+            RiskAction::Allow(place(1))
+        );
+
+        assert_eq!(observer.rejects("order size exceeds limit"), 1);
+        assert_eq!(observer.rejects("max position exceeded"), 1);
+        assert_eq!(observer.total_rejects(), 2);
+    }
+
     #[test]
     fn max_position_rejects_excess() {
         let policy = MaxPositionPolicy::new(10);
@@ -231,6 +471,118 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn per_symbol_position_limits_use_context_symbol() {
+        let big = SymbolId::from_u32(1);
+        let small = SymbolId::from_u32(2);
+        let other = SymbolId::from_u32(3);
+        let policy = MaxPositionPolicy::per_symbol(HashMap::from([(big, 20), (small, 2)]), 5);
+        let buy = |symbol, lots| Intent::PlaceLimit {
+            symbol,
+            side: Side::Bid,
+            price: Price::new(101).unwrap(),
+            qty: Qty::new(lots).unwrap(),
+            tif: TimeInForce::Gtc,
+            tag: None,
+        };
+        let ctx_for = |symbol| {
+            let mut ctx = ctx_with_mid(1, 0);
+            ctx.symbol = symbol;
+            ctx
+        };
+
+        assert!(matches!(
+            policy.evaluate(&ctx_for(big), &buy(big, 15)),
+            RiskAction::Allow(_)
+        ));
+        assert!(matches!(
+            policy.evaluate(&ctx_for(small), &buy(small, 3)),
+            RiskAction::Reject { .. }
+        ));
+        assert!(matches!(
+            policy.evaluate(&ctx_for(other), &buy(other, 5)),
+            RiskAction::Allow(_)
+        ));
+        assert!(matches!(
+            policy.evaluate(&ctx_for(other), &buy(other, 6)),
+            RiskAction::Reject { .. }
+        ));
+    }
+
+    #[test]
+    fn zero_position_limit_still_lets_orders_be_pulled() {
+        let listed = SymbolId::from_u32(1);
+        let unlisted = SymbolId::from_u32(2);
+        let policy = MaxPositionPolicy::per_symbol(HashMap::from([(listed, 5)]), 0);
+        let mut ctx = ctx_with_mid(1, 0);
+        ctx.symbol = unlisted;
+
+        let cancel = Intent::Cancel {
+            client_order_id: ClientOrderId(1),
+        };
+        let replace = Intent::Replace {
+            client_order_id: ClientOrderId(1),
+            new_price: Price::new(101).unwrap(),
+            new_qty: Qty::new(1).unwrap(),
+        };
+        for intent in [cancel, replace] {
+            assert!(matches!(
+                policy.evaluate(&ctx, &intent),
+                RiskAction::Allow(_)
+            ));
+        }
+        let buy = Intent::PlaceLimit {
+            symbol: unlisted,
+            side: Side::Bid,
+            price: Price::new(101).unwrap(),
+            qty: Qty::new(1).unwrap(),
+            tif: TimeInForce::Gtc,
+            tag: None,
+        };
+        assert!(matches!(
+            policy.evaluate(&ctx, &buy),
+            RiskAction::Reject { .. }
+        ));
+    }
+
+    #[test]
+    fn max_order_size_checks_place_and_replace() {
+        let policy = MaxOrderSizePolicy::new(5);
+        let ctx = ctx_with_mid(1, 0);
+        let place = |lots| Intent::PlaceLimit {
+            symbol: SymbolId::from_u32(1),
+            side: Side::Bid,
+            price: Price::new(101).unwrap(),
+            qty: Qty::new(lots).unwrap(),
+            tif: TimeInForce::Gtc,
+            tag: None,
+        };
+        let replace = |lots| Intent::Replace {
+            client_order_id: trading_types::ClientOrderId(1),
+            new_price: Price::new(101).unwrap(),
+            new_qty: Qty::new(lots).unwrap(),
+        };
+
+        assert!(matches!(
+            policy.evaluate(&ctx, &place(5)),
+            RiskAction::Allow(_)
+        ));
+        assert_eq!(
+            policy.evaluate(&ctx, &place(6)),
+            RiskAction::Reject {
+                reason: "order size exceeds limit".to_string()
+            }
+        );
+        assert!(matches!(
+            policy.evaluate(&ctx, &replace(5)),
+            RiskAction::Allow(_)
+        ));
+        assert!(matches!(
+            policy.evaluate(&ctx, &replace(50)),
+            RiskAction::Reject { .. }
+        ));
+    }
+
     #[test]
     fn price_band_rejects_far_price() {
         let policy = PriceBandPolicy::new(3);
@@ -249,6 +601,71 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn market_orders_skip_price_bands_but_count_size_and_notional() {
+        let ctx = ctx_with_mid(1, 0);
+        let market = |lots| Intent::PlaceMarket {
+            symbol: SymbolId::from_u32(1),
+            side: Side::Bid,
+            qty: Qty::new(lots).unwrap(),
+            tif: TimeInForce::Ioc,
+            tag: None,
+        };
+
+        assert_eq!(
+            PriceBandPolicy::new(0).evaluate(&ctx, &market(1)),
+            RiskAction::Allow(market(1))
+        );
+        assert_eq!(
+            PriceBandClampPolicy::new(0).evaluate(&ctx, &market(1)),
+            RiskAction::Allow(market(1))
+        );
+        assert!(matches!(
+            MaxOrderSizePolicy::new(2).evaluate(&ctx, &market(3)),
+            RiskAction::Reject { .. }
+        ));
+        // Priced at the 102 ask: 9 lots fit a 1_000 budget, a 10th does not.
+        let notional = NotionalRateLimitPolicy::new(1_000);
+        assert!(matches!(
+            notional.evaluate(&ctx, &market(9)),
+            RiskAction::Allow(_)
+        ));
+        assert!(matches!(
+            notional.evaluate(&ctx, &market(1)),
+            RiskAction::Reject { .. }
+        ));
+    }
+
+    #[test]
+    fn price_band_clamp_pulls_far_bid_into_band() {
+        let policy = PriceBandClampPolicy::new(3);
+        let ctx = ctx_with_mid(1, 0);
+        let bid = |price| Intent::PlaceLimit {
+            symbol: SymbolId::from_u32(1),
+            side: Side::Bid,
+            price: Price::new(price).unwrap(),
+            qty: Qty::new(1).unwrap(),
+            tif: TimeInForce::Gtc,
+            tag: None,
+        };
+
+        assert_eq!(
+            policy.evaluate(&ctx, &bid(50)),
+            RiskAction::Transform(bid(98))
+        );
+        assert_eq!(
+            policy.evaluate(&ctx, &bid(200)),
+            RiskAction::Transform(bid(104))
+        );
+        assert_eq!(policy.evaluate(&ctx, &bid(99)), RiskAction::Allow(bid(99)));
+
+        // The clamped intent is what downstream policies see.
+        let engine = RiskEngine::new()
+            .with_policy(PriceBandClampPolicy::new(3))
+            .with_policy(PriceBandPolicy::new(3));
+        assert_eq!(engine.evaluate(&ctx, &bid(50)), RiskAction::Allow(bid(98)));
+    }
+
     #[test]
     fn rate_limit_enforced_per_second() {
         let policy = RateLimitPolicy::new(2);
@@ -281,4 +698,44 @@ mod tests {
             RiskAction::Allow(_)
         ));
     }
+
+    #[test]
+    fn notional_rate_limit_rejects_once_budget_is_spent() {
+        let policy = NotionalRateLimitPolicy::new(1_000);
+        let ctx = ctx_with_mid(1, 0);
+        let place = |lots| Intent::PlaceLimit {
+            symbol: SymbolId::from_u32(1),
+            side: Side::Bid,
+            price: Price::new(100).unwrap(),
+            qty: Qty::new(lots).unwrap(),
+            tif: TimeInForce::Gtc,
+            tag: None,
+        };
+        let cancel = Intent::Cancel {
+            client_order_id: trading_types::ClientOrderId(1),
+        };
+
+        assert!(matches!(
+            policy.evaluate(&ctx, &place(2)),
+            RiskAction::Allow(_)
+        ));
+        assert!(matches!(
+            policy.evaluate(&ctx, &place(3)),
+            RiskAction::Allow(_)
+        ));
+        assert!(matches!(
+            policy.evaluate(&ctx, &place(6)),
+            RiskAction::Reject { .. }
+        ));
+        assert!(matches!(
+            policy.evaluate(&ctx, &cancel),
+            RiskAction::Allow(_)
+        ));
+
+        let ctx_next = ctx_with_mid(1_000_000_000, 0);
+        assert!(matches!(
+            policy.evaluate(&ctx_next, &place(6)),
+            RiskAction::Allow(_)
+        ));
+    }
 }
@@ -1,5 +1,7 @@
-use lob_core::{MarketEvent, Price, Qty, Side};
-use strategy_api::{ContextSnapshot, Strategy};
+use std::collections::HashMap;
+
+use lob_core::{CoreError, MarketEvent, Price, Qty, Side};
+use strategy_api::{ContextSnapshot, Strategy, TimerId};
 use trading_types::{ClientOrderId, ExecutionReport, Intent, OrderStatus, TimeInForce};
 
 pub struct NoopStrategy;
@@ -10,14 +12,89 @@ pub struct TwapStrategy {
     remaining_qty_lots: i64,
     next_ts_ns: Option<u64>,
     interval_ns: u64,
+    child: ChildOrder,
+}
+
+/// Paces child orders to a fraction of observed trade volume.
+pub struct VwapStrategy {
+    target_qty_lots: i64,
+    participation_rate_bps: u32,
+    remaining_qty_lots: i64,
+    observed_volume_lots: i64,
+    child: ChildOrder,
+}
+
+/// Sends a fixed fraction of the trade volume printed since its last child order.
+pub struct PovStrategy {
+    target_qty_lots: i64,
+    pov_bps: u32,
+    remaining_qty_lots: i64,
+    unsent_volume_lots: i64,
+    child: ChildOrder,
+}
+
+/// In-flight and cumulative-fill bookkeeping for one working child order at a time.
+#[derive(Default)]
+struct ChildOrder {
     in_flight: bool,
-    last_reported_qty: i64,
+    last_reported_qty: Qty,
 }
 
 pub struct MmStrategy {
     half_spread_ticks: i64,
     quote_qty_lots: i64,
     skew_per_lot_ticks: i64,
+    quotes: TwoSidedQuotes,
+}
+
+/// Avellaneda–Stoikov market maker.
+///
+/// Units: `gamma` is risk aversion per lot-tick, `sigma` is mid volatility in ticks per
+/// sqrt(second), `k` is order-arrival intensity decay per tick, and the horizon is in seconds
+/// measured from the first quoted event.
+pub struct AsMmStrategy {
+    gamma: f64,
+    sigma: f64,
+    k: f64,
+    horizon_secs: f64,
+    quote_qty_lots: i64,
+    session_start_ns: Option<u64>,
+    quotes: TwoSidedQuotes,
+}
+
+/// Wraps a strategy and flattens the position with a marketable order once unrealized PnL
+/// per lot reaches `-stop_ticks` or `+target_ticks` against the tracked entry price.
+pub struct ProtectiveStrategy {
+    inner: Box<dyn Strategy>,
+    stop_ticks: i64,
+    target_ticks: i64,
+    position_lots: i64,
+    cost_ticks: i128,
+    reported_fills: HashMap<ClientOrderId, i64>,
+    flatten_side: Option<Side>,
+}
+
+/// Forwards every callback to each child in order and concatenates their intents.
+///
+/// Client order ids are assigned centrally by the OMS, and every child sees every execution
+/// report, so children must not assume they own the ids they are told about.
+pub struct CompositeStrategy {
+    children: Vec<Box<dyn Strategy>>,
+}
+
+/// Holds back `inner`'s market events and timers until the book has been two-sided once,
+/// or until more than `min_events` market events have been seen; after that it forwards
+/// everything. Execution reports are always forwarded.
+pub struct WarmupStrategy {
+    inner: Box<dyn Strategy>,
+    min_events: u64,
+    events: u64,
+    warmed_up: bool,
+}
+
+/// Resting bid/ask bookkeeping shared by the market makers.
+#[derive(Default)]
+struct TwoSidedQuotes {
     bid_order_id: Option<ClientOrderId>,
     ask_order_id: Option<ClientOrderId>,
     bid_price: Option<Price>,
@@ -37,11 +114,7 @@ impl TwapStrategy {
             abs_target.div_ceil(slice_u)
         };
         let horizon_ns = horizon_secs.saturating_mul(1_000_000_000);
-        let mut interval_ns = if total_slices == 0 {
-            0
-        } else {
-            horizon_ns / total_slices
-        };
+        let mut interval_ns = horizon_ns.checked_div(total_slices).unwrap_or(0);
         if total_slices > 0 && interval_ns == 0 {
             interval_ns = 1;
         }
@@ -52,13 +125,12 @@ impl TwapStrategy {
             remaining_qty_lots: target_qty_lots,
             next_ts_ns: None,
             interval_ns,
-            in_flight: false,
-            last_reported_qty: 0,
+            child: ChildOrder::default(),
         }
     }
 
     fn maybe_place(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
-        if self.remaining_qty_lots == 0 || self.in_flight {
+        if self.remaining_qty_lots == 0 || self.child.in_flight {
             return;
         }
 
@@ -72,21 +144,137 @@ impl TwapStrategy {
             return;
         }
 
-        let (side, price) = if self.remaining_qty_lots > 0 {
+        if !self
+            .child
+            .place(ctx, self.remaining_qty_lots, qty_lots, out)
+        {
+            return;
+        }
+        *next_ts = ctx.ts_ns.saturating_add(self.interval_ns.max(1));
+    }
+
+    fn on_report(&mut self, report: &ExecutionReport) {
+        self.remaining_qty_lots =
+            self.child
+                .on_report(report, self.target_qty_lots, self.remaining_qty_lots);
+    }
+}
+
+impl VwapStrategy {
+    pub fn new(target_qty_lots: i64, participation_rate_bps: u32) -> Self {
+        Self {
+            target_qty_lots,
+            participation_rate_bps: participation_rate_bps.min(10_000),
+            remaining_qty_lots: target_qty_lots,
+            observed_volume_lots: 0,
+            child: ChildOrder::default(),
+        }
+    }
+
+    fn observe(&mut self, ctx: &ContextSnapshot, event: &MarketEvent) {
+        if let MarketEvent::Trade { symbol, qty, .. } = event {
+            if *symbol == ctx.symbol {
+                self.observed_volume_lots = self.observed_volume_lots.saturating_add(qty.lots());
+            }
+        }
+    }
+
+    /// Lots allowed so far by the cumulative volume bucket, net of what already executed.
+    fn allowed_lots(&self) -> i64 {
+        let budget = (self.observed_volume_lots as i128 * self.participation_rate_bps as i128
+            / 10_000)
+            .min(self.target_qty_lots.unsigned_abs() as i128) as i64;
+        let executed = (self.target_qty_lots - self.remaining_qty_lots).abs();
+        budget.saturating_sub(executed)
+    }
+
+    fn maybe_place(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        if self.remaining_qty_lots == 0 || self.child.in_flight {
+            return;
+        }
+
+        let qty_lots = self.remaining_qty_lots.abs().min(self.allowed_lots());
+        if qty_lots <= 0 {
+            return;
+        }
+        self.child
+            .place(ctx, self.remaining_qty_lots, qty_lots, out);
+    }
+
+    fn on_report(&mut self, report: &ExecutionReport) {
+        self.remaining_qty_lots =
+            self.child
+                .on_report(report, self.target_qty_lots, self.remaining_qty_lots);
+    }
+}
+
+impl PovStrategy {
+    pub fn new(target_qty_lots: i64, pov_bps: u32) -> Self {
+        Self {
+            target_qty_lots,
+            pov_bps: pov_bps.min(10_000),
+            remaining_qty_lots: target_qty_lots,
+            unsent_volume_lots: 0,
+            child: ChildOrder::default(),
+        }
+    }
+
+    fn on_event(&mut self, ctx: &ContextSnapshot, event: &MarketEvent, out: &mut Vec<Intent>) {
+        let MarketEvent::Trade { symbol, qty, .. } = event else {
+            return;
+        };
+        if *symbol != ctx.symbol || self.remaining_qty_lots == 0 {
+            return;
+        }
+        self.unsent_volume_lots = self.unsent_volume_lots.saturating_add(qty.lots());
+        if self.child.in_flight {
+            return;
+        }
+
+        // Volume too small for a whole lot keeps accumulating instead of emitting zero.
+        let qty_lots = (self.unsent_volume_lots as i128 * self.pov_bps as i128 / 10_000)
+            .min(self.remaining_qty_lots.unsigned_abs() as i128) as i64;
+        if qty_lots <= 0 {
+            return;
+        }
+        if self
+            .child
+            .place(ctx, self.remaining_qty_lots, qty_lots, out)
+        {
+            self.unsent_volume_lots = 0;
+        }
+    }
+
+    fn on_report(&mut self, report: &ExecutionReport) {
+        self.remaining_qty_lots =
+            self.child
+                .on_report(report, self.target_qty_lots, self.remaining_qty_lots);
+    }
+}
+
+impl ChildOrder {
+    /// Crosses the spread in the direction of `remaining_qty_lots`; returns whether an intent was emitted.
+    fn place(
+        &mut self,
+        ctx: &ContextSnapshot,
+        remaining_qty_lots: i64,
+        qty_lots: i64,
+        out: &mut Vec<Intent>,
+    ) -> bool {
+        let (side, price) = if remaining_qty_lots > 0 {
             (Side::Bid, ctx.best_ask.map(|(price, _)| price))
         } else {
             (Side::Ask, ctx.best_bid.map(|(price, _)| price))
         };
         let Some(price) = price else {
-            return;
+            return false;
         };
         let Ok(qty) = Qty::new(qty_lots) else {
-            return;
+            return false;
         };
 
         self.in_flight = true;
-        self.last_reported_qty = 0;
-        *next_ts = ctx.ts_ns.saturating_add(self.interval_ns.max(1));
+        self.last_reported_qty = Qty::ZERO;
 
         out.push(Intent::PlaceLimit {
             symbol: ctx.symbol,
@@ -96,44 +284,52 @@ impl TwapStrategy {
             tif: TimeInForce::Gtc,
             tag: None,
         });
+        true
     }
 
-    fn on_report(&mut self, report: &ExecutionReport) {
+    /// Applies the report's cumulative fill delta and returns the updated remaining quantity.
+    fn on_report(
+        &mut self,
+        report: &ExecutionReport,
+        target_qty_lots: i64,
+        mut remaining_qty_lots: i64,
+    ) -> i64 {
         if !self.in_flight {
-            return;
+            return remaining_qty_lots;
         }
 
         match report.status {
             OrderStatus::Filled | OrderStatus::PartiallyFilled => {
-                let reported = report.filled_qty.lots();
-                let delta = reported.saturating_sub(self.last_reported_qty);
+                let reported = report.filled_qty;
+                let delta = reported.saturating_sub(self.last_reported_qty).lots();
                 if delta > 0 {
                     if report.side == Side::Bid {
-                        self.remaining_qty_lots -= delta;
+                        remaining_qty_lots -= delta;
                     } else {
-                        self.remaining_qty_lots += delta;
+                        remaining_qty_lots += delta;
                     }
                     self.last_reported_qty = reported;
                 }
                 if report.status == OrderStatus::Filled {
                     self.in_flight = false;
-                    self.last_reported_qty = 0;
+                    self.last_reported_qty = Qty::ZERO;
                 }
             }
             OrderStatus::Canceled | OrderStatus::Rejected | OrderStatus::Expired => {
                 self.in_flight = false;
-                self.last_reported_qty = 0;
+                self.last_reported_qty = Qty::ZERO;
             }
             _ => {}
         }
 
-        if (self.target_qty_lots >= 0 && self.remaining_qty_lots <= 0)
-            || (self.target_qty_lots <= 0 && self.remaining_qty_lots >= 0)
+        if (target_qty_lots >= 0 && remaining_qty_lots <= 0)
+            || (target_qty_lots <= 0 && remaining_qty_lots >= 0)
         {
-            self.remaining_qty_lots = 0;
+            remaining_qty_lots = 0;
             self.in_flight = false;
-            self.last_reported_qty = 0;
+            self.last_reported_qty = Qty::ZERO;
         }
+        remaining_qty_lots
     }
 }
 
@@ -143,26 +339,213 @@ impl MmStrategy {
             half_spread_ticks: half_spread_ticks.abs().max(1),
             quote_qty_lots: quote_qty_lots.abs().max(1),
             skew_per_lot_ticks,
-            bid_order_id: None,
-            ask_order_id: None,
-            bid_price: None,
-            ask_price: None,
-            pending_bid: false,
-            pending_ask: false,
+            quotes: TwoSidedQuotes::default(),
         }
     }
 
     fn quote(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
         let Some(mid) = ctx.mid_price else {
-            self.cancel_all(out);
+            self.quotes.cancel_all(out);
             return;
         };
 
         let skew = (ctx.position_lots as i128 * self.skew_per_lot_ticks as i128)
             .clamp(i64::MIN as i128, i64::MAX as i128) as i64;
-        let mid_ticks = mid.ticks();
-        let mut bid_ticks = mid_ticks - self.half_spread_ticks - skew;
-        let mut ask_ticks = mid_ticks + self.half_spread_ticks - skew;
+        // A side pushed below zero is clamped to 1 tick; only a skew that overflows i64
+        // pulls the quotes.
+        let side_ticks = |delta_ticks: Option<i64>| match mid.offset(delta_ticks?) {
+            Ok(price) => Some(price.ticks()),
+            Err(CoreError::InvalidPrice(_)) => Some(1),
+            Err(_) => None,
+        };
+        let bid_ticks = side_ticks(
+            self.half_spread_ticks
+                .checked_add(skew)
+                .and_then(i64::checked_neg),
+        );
+        let ask_ticks = side_ticks(self.half_spread_ticks.checked_sub(skew));
+        let (Some(bid_ticks), Some(ask_ticks)) = (bid_ticks, ask_ticks) else {
+            self.quotes.cancel_all(out);
+            return;
+        };
+        self.quotes
+            .quote(ctx, bid_ticks, ask_ticks, self.quote_qty_lots, out);
+    }
+}
+
+impl AsMmStrategy {
+    pub fn new(gamma: f64, sigma: f64, k: f64, horizon_secs: u64, quote_qty_lots: i64) -> Self {
+        Self {
+            gamma: gamma.abs().max(f64::EPSILON),
+            sigma: sigma.abs(),
+            k: k.abs().max(f64::EPSILON),
+            horizon_secs: horizon_secs as f64,
+            quote_qty_lots: quote_qty_lots.abs().max(1),
+            session_start_ns: None,
+            quotes: TwoSidedQuotes::default(),
+        }
+    }
+
+    /// Returns `(reservation_price, half_spread)` in ticks.
+    fn model(&self, mid_ticks: f64, position_lots: i64, elapsed_secs: f64) -> (f64, f64) {
+        let time_left = (self.horizon_secs - elapsed_secs).max(0.0);
+        let variance_term = self.gamma * self.sigma * self.sigma * time_left;
+        let reservation = mid_ticks - position_lots as f64 * variance_term;
+        let spread = variance_term + (2.0 / self.gamma) * (1.0 + self.gamma / self.k).ln();
+        (reservation, spread / 2.0)
+    }
+
+    fn quote(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        let Some(mid) = ctx.mid_price else {
+            self.quotes.cancel_all(out);
+            return;
+        };
+
+        let start_ns = *self.session_start_ns.get_or_insert(ctx.ts_ns);
+        let elapsed_secs = ctx.ts_ns.saturating_sub(start_ns) as f64 / 1_000_000_000.0;
+        let (reservation, half_spread) =
+            self.model(mid.ticks() as f64, ctx.position_lots, elapsed_secs);
+
+        // Round outward so the quoted spread never narrows below the model's.
+        let bid_ticks = (reservation - half_spread).floor() as i64;
+        let ask_ticks = (reservation + half_spread).ceil() as i64;
+        self.quotes
+            .quote(ctx, bid_ticks, ask_ticks, self.quote_qty_lots, out);
+    }
+}
+
+impl ProtectiveStrategy {
+    pub fn new(inner: Box<dyn Strategy>, stop_ticks: i64, target_ticks: i64) -> Self {
+        Self {
+            inner,
+            stop_ticks: stop_ticks.abs(),
+            target_ticks: target_ticks.abs(),
+            position_lots: 0,
+            cost_ticks: 0,
+            reported_fills: HashMap::new(),
+            flatten_side: None,
+        }
+    }
+
+    fn on_report(&mut self, report: &ExecutionReport) {
+        match report.status {
+            OrderStatus::Filled | OrderStatus::PartiallyFilled => {
+                let reported = report.filled_qty.lots();
+                let previous = self
+                    .reported_fills
+                    .insert(report.client_order_id, reported)
+                    .unwrap_or(0);
+                let delta = reported.saturating_sub(previous);
+                if delta > 0 {
+                    let signed = if report.side == Side::Bid {
+                        delta
+                    } else {
+                        -delta
+                    };
+                    self.apply_fill(signed, report.last_fill_price);
+                }
+            }
+            OrderStatus::Canceled | OrderStatus::Rejected | OrderStatus::Expired
+                if self.flatten_side == Some(report.side) =>
+            {
+                self.flatten_side = None;
+            }
+            _ => {}
+        }
+        if matches!(
+            report.status,
+            OrderStatus::Filled
+                | OrderStatus::Canceled
+                | OrderStatus::Rejected
+                | OrderStatus::Expired
+        ) {
+            self.reported_fills.remove(&report.client_order_id);
+        }
+        if self.position_lots == 0 {
+            self.flatten_side = None;
+        }
+    }
+
+    fn apply_fill(&mut self, signed_lots: i64, price: Price) {
+        let before = self.position_lots;
+        let after = before + signed_lots;
+        let price = price.ticks() as i128;
+        if before == 0 || before.signum() == signed_lots.signum() {
+            self.cost_ticks += price * signed_lots as i128;
+        } else if after == 0 || after.signum() == before.signum() {
+            self.cost_ticks = self.cost_ticks * after as i128 / before as i128;
+        } else {
+            self.cost_ticks = price * after as i128;
+        }
+        self.position_lots = after;
+    }
+
+    fn maybe_flatten(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        if self.position_lots == 0 || ctx.position_lots == 0 || self.flatten_side.is_some() {
+            return;
+        }
+        let Some(mid) = ctx.mid_price else {
+            return;
+        };
+
+        let lots = self.position_lots.unsigned_abs() as i128;
+        let pnl_ticks = mid.ticks() as i128 * self.position_lots as i128 - self.cost_ticks;
+        let stopped = pnl_ticks <= -(self.stop_ticks as i128) * lots;
+        let targeted = pnl_ticks >= self.target_ticks as i128 * lots;
+        if !stopped && !targeted {
+            return;
+        }
+
+        let (side, price) = if ctx.position_lots > 0 {
+            (Side::Ask, ctx.best_bid.map(|(price, _)| price))
+        } else {
+            (Side::Bid, ctx.best_ask.map(|(price, _)| price))
+        };
+        let Some(price) = price else {
+            return;
+        };
+        let Ok(qty) = Qty::new(ctx.position_lots.abs()) else {
+            return;
+        };
+
+        self.flatten_side = Some(side);
+        out.push(Intent::PlaceLimit {
+            symbol: ctx.symbol,
+            side,
+            price,
+            qty,
+            tif: TimeInForce::Ioc,
+            tag: None,
+        });
+    }
+}
+
+impl CompositeStrategy {
+    pub fn new(children: Vec<Box<dyn Strategy>>) -> Self {
+        Self { children }
+    }
+}
+
+impl WarmupStrategy {
+    pub fn new(inner: Box<dyn Strategy>, min_events: u64) -> Self {
+        Self {
+            inner,
+            min_events,
+            events: 0,
+            warmed_up: false,
+        }
+    }
+}
+
+impl TwoSidedQuotes {
+    fn quote(
+        &mut self,
+        ctx: &ContextSnapshot,
+        mut bid_ticks: i64,
+        mut ask_ticks: i64,
+        qty_lots: i64,
+        out: &mut Vec<Intent>,
+    ) {
         if bid_ticks < 1 {
             bid_ticks = 1;
         }
@@ -170,7 +553,12 @@ impl MmStrategy {
             ask_ticks = 1;
         }
         if ask_ticks <= bid_ticks {
-            ask_ticks = bid_ticks + 1;
+            // A bid at `i64::MAX` leaves no room for an ask above it.
+            let Some(above_bid) = bid_ticks.checked_add(1) else {
+                self.cancel_all(out);
+                return;
+            };
+            ask_ticks = above_bid;
         }
 
         let Ok(bid_price) = Price::new(bid_ticks) else {
@@ -179,7 +567,7 @@ impl MmStrategy {
         let Ok(ask_price) = Price::new(ask_ticks) else {
             return;
         };
-        let Ok(qty) = Qty::new(self.quote_qty_lots) else {
+        let Ok(qty) = Qty::new(qty_lots) else {
             return;
         };
 
@@ -324,18 +712,19 @@ impl Strategy for TwapStrategy {
     }
 }
 
-impl Strategy for MmStrategy {
+impl Strategy for VwapStrategy {
     fn on_market_event(
         &mut self,
         ctx: &ContextSnapshot,
-        _event: &MarketEvent,
+        event: &MarketEvent,
         out: &mut Vec<Intent>,
     ) {
-        self.quote(ctx, out);
+        self.observe(ctx, event);
+        self.maybe_place(ctx, out);
     }
 
     fn on_timer(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
-        self.quote(ctx, out);
+        self.maybe_place(ctx, out);
     }
 
     fn on_execution_report(
@@ -348,55 +737,405 @@ impl Strategy for MmStrategy {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use lob_core::SymbolId;
+impl Strategy for PovStrategy {
+    fn on_market_event(
+        &mut self,
+        ctx: &ContextSnapshot,
+        event: &MarketEvent,
+        out: &mut Vec<Intent>,
+    ) {
+        self.on_event(ctx, event, out);
+    }
 
-    fn ctx_with_book(
-        ts_ns: u64,
-        symbol: SymbolId,
-        best_bid: i64,
-        best_ask: i64,
-        position_lots: i64,
-    ) -> ContextSnapshot {
-        ContextSnapshot::new(
-            ts_ns,
-            symbol,
-            Some((Price::new(best_bid).unwrap(), Qty::new(1).unwrap())),
-            Some((Price::new(best_ask).unwrap(), Qty::new(1).unwrap())),
-            position_lots,
-            0,
-        )
+    fn on_execution_report(
+        &mut self,
+        _ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        _out: &mut Vec<Intent>,
+    ) {
+        self.on_report(report);
     }
+}
 
-    #[test]
-    fn noop_strategy_returns_empty_intents() {
-        let mut strategy = NoopStrategy;
-        let symbol = SymbolId::from_u32(1);
-        let ctx = ContextSnapshot::new(
-            1,
-            symbol,
-            Some((Price::new(100).unwrap(), Qty::new(1).unwrap())),
-            Some((Price::new(101).unwrap(), Qty::new(1).unwrap())),
-            0,
-            0,
-        );
-        let event = MarketEvent::L2Delta {
-            ts_ns: 1,
-            symbol,
-            updates: vec![],
-        };
+impl Strategy for MmStrategy {
+    fn on_market_event(
+        &mut self,
+        ctx: &ContextSnapshot,
+        _event: &MarketEvent,
+        out: &mut Vec<Intent>,
+    ) {
+        self.quote(ctx, out);
+    }
 
-        let mut intents = Vec::new();
-        strategy.on_market_event(&ctx, &event, &mut intents);
-        assert!(intents.is_empty());
-        strategy.on_timer(&ctx, &mut intents);
-        assert!(intents.is_empty());
+    fn on_timer(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        self.quote(ctx, out);
     }
 
-    #[test]
-    fn twap_emits_until_target_reached() {
+    fn on_execution_report(
+        &mut self,
+        _ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        _out: &mut Vec<Intent>,
+    ) {
+        self.quotes.on_report(report);
+    }
+}
+
+impl Strategy for AsMmStrategy {
+    fn on_market_event(
+        &mut self,
+        ctx: &ContextSnapshot,
+        _event: &MarketEvent,
+        out: &mut Vec<Intent>,
+    ) {
+        self.quote(ctx, out);
+    }
+
+    fn on_timer(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        self.quote(ctx, out);
+    }
+
+    fn on_execution_report(
+        &mut self,
+        _ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        _out: &mut Vec<Intent>,
+    ) {
+        self.quotes.on_report(report);
+    }
+}
+
+impl Strategy for ProtectiveStrategy {
+    fn on_market_event(
+        &mut self,
+        ctx: &ContextSnapshot,
+        event: &MarketEvent,
+        out: &mut Vec<Intent>,
+    ) {
+        if self.inner.is_ready(ctx) {
+            self.inner.on_market_event(ctx, event, out);
+        }
+        self.maybe_flatten(ctx, out);
+    }
+
+    fn on_timer(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        self.on_timer_id(TimerId::DEFAULT, ctx, out);
+    }
+
+    fn on_timer_id(&mut self, timer: TimerId, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        if self.inner.is_ready(ctx) {
+            self.inner.on_timer_id(timer, ctx, out);
+        }
+        self.maybe_flatten(ctx, out);
+    }
+
+    fn on_execution_report(
+        &mut self,
+        ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        out: &mut Vec<Intent>,
+    ) {
+        self.on_report(report);
+        self.inner.on_execution_report(ctx, report, out);
+    }
+
+    fn on_order_accepted(
+        &mut self,
+        ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        out: &mut Vec<Intent>,
+    ) {
+        self.inner.on_order_accepted(ctx, report, out);
+    }
+
+    fn on_order_rejected(
+        &mut self,
+        ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        out: &mut Vec<Intent>,
+    ) {
+        self.inner.on_order_rejected(ctx, report, out);
+    }
+}
+
+impl Strategy for CompositeStrategy {
+    fn on_market_event(
+        &mut self,
+        ctx: &ContextSnapshot,
+        event: &MarketEvent,
+        out: &mut Vec<Intent>,
+    ) {
+        for child in &mut self.children {
+            if child.is_ready(ctx) {
+                child.on_market_event(ctx, event, out);
+            }
+        }
+    }
+
+    fn on_timer(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        self.on_timer_id(TimerId::DEFAULT, ctx, out);
+    }
+
+    fn on_timer_id(&mut self, timer: TimerId, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        for child in &mut self.children {
+            if child.is_ready(ctx) {
+                child.on_timer_id(timer, ctx, out);
+            }
+        }
+    }
+
+    fn on_execution_report(
+        &mut self,
+        ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        out: &mut Vec<Intent>,
+    ) {
+        for child in &mut self.children {
+            child.on_execution_report(ctx, report, out);
+        }
+    }
+
+    fn on_order_accepted(
+        &mut self,
+        ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        out: &mut Vec<Intent>,
+    ) {
+        for child in &mut self.children {
+            child.on_order_accepted(ctx, report, out);
+        }
+    }
+
+    fn on_order_rejected(
+        &mut self,
+        ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        out: &mut Vec<Intent>,
+    ) {
+        for child in &mut self.children {
+            child.on_order_rejected(ctx, report, out);
+        }
+    }
+}
+
+impl Strategy for WarmupStrategy {
+    fn is_ready(&self, ctx: &ContextSnapshot) -> bool {
+        self.inner.is_ready(ctx)
+    }
+
+    fn on_market_event(
+        &mut self,
+        ctx: &ContextSnapshot,
+        event: &MarketEvent,
+        out: &mut Vec<Intent>,
+    ) {
+        if !self.warmed_up {
+            self.events += 1;
+            let two_sided = ctx.best_bid.is_some() && ctx.best_ask.is_some();
+            self.warmed_up = two_sided || self.events > self.min_events;
+        }
+        if self.warmed_up {
+            self.inner.on_market_event(ctx, event, out);
+        }
+    }
+
+    fn on_timer(&mut self, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        if self.warmed_up {
+            self.inner.on_timer(ctx, out);
+        }
+    }
+
+    fn on_timer_id(&mut self, timer: TimerId, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        if self.warmed_up {
+            self.inner.on_timer_id(timer, ctx, out);
+        }
+    }
+
+    fn on_execution_report(
+        &mut self,
+        ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        out: &mut Vec<Intent>,
+    ) {
+        self.inner.on_execution_report(ctx, report, out);
+    }
+
+    fn on_order_accepted(
+        &mut self,
+        ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        out: &mut Vec<Intent>,
+    ) {
+        self.inner.on_order_accepted(ctx, report, out);
+    }
+
+    fn on_order_rejected(
+        &mut self,
+        ctx: &ContextSnapshot,
+        report: &ExecutionReport,
+        out: &mut Vec<Intent>,
+    ) {
+        self.inner.on_order_rejected(ctx, report, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lob_core::SymbolId;
+
+    fn ctx_with_book(
+        ts_ns: u64,
+        symbol: SymbolId,
+        best_bid: i64,
+        best_ask: i64,
+        position_lots: i64,
+    ) -> ContextSnapshot {
+        ContextSnapshot::new(
+            ts_ns,
+            symbol,
+            Some((Price::new(best_bid).unwrap(), Qty::new(1).unwrap())),
+            Some((Price::new(best_ask).unwrap(), Qty::new(1).unwrap())),
+            position_lots,
+            0,
+        )
+    }
+
+    /// Bids one lot at the best bid on every market event.
+    struct JoinBidStrategy;
+
+    impl Strategy for JoinBidStrategy {
+        fn on_market_event(
+            &mut self,
+            ctx: &ContextSnapshot,
+            _event: &MarketEvent,
+            out: &mut Vec<Intent>,
+        ) {
+            let Some((price, _)) = ctx.best_bid else {
+                return;
+            };
+            out.push(Intent::PlaceLimit {
+                symbol: ctx.symbol,
+                side: Side::Bid,
+                price,
+                qty: Qty::new(1).unwrap(),
+                tif: TimeInForce::Gtc,
+                tag: None,
+            });
+        }
+    }
+
+    #[test]
+    fn warmup_holds_intents_until_book_is_two_sided() {
+        let symbol = SymbolId::from_u32(1);
+        let mut strategy = WarmupStrategy::new(Box::new(JoinBidStrategy), 100);
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol,
+            updates: vec![],
+        };
+        let mut drive = |ctx: &ContextSnapshot| {
+            let mut out = Vec::new();
+            if strategy.is_ready(ctx) {
+                strategy.on_market_event(ctx, &event, &mut out);
+            }
+            out.len()
+        };
+
+        let bid_only = ContextSnapshot::new(
+            1,
+            symbol,
+            Some((Price::new(100).unwrap(), Qty::new(1).unwrap())),
+            None,
+            0,
+            0,
+        );
+        assert_eq!(drive(&bid_only), 0);
+        assert_eq!(drive(&bid_only), 0);
+        assert_eq!(drive(&ctx_with_book(2, symbol, 100, 101, 0)), 1);
+        assert_eq!(drive(&bid_only), 1);
+    }
+
+    #[test]
+    fn warmup_releases_after_min_events() {
+        let symbol = SymbolId::from_u32(1);
+        let mut strategy = WarmupStrategy::new(Box::new(JoinBidStrategy), 2);
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol,
+            updates: vec![],
+        };
+        let bid_only = ContextSnapshot::new(
+            1,
+            symbol,
+            Some((Price::new(100).unwrap(), Qty::new(1).unwrap())),
+            None,
+            0,
+            0,
+        );
+        let mut drive = || {
+            let mut out = Vec::new();
+            strategy.on_market_event(&bid_only, &event, &mut out);
+            out.len()
+        };
+        assert_eq!(drive(), 0);
+        assert_eq!(drive(), 0);
+        assert_eq!(drive(), 1);
+    }
+
+    #[test]
+    fn warmup_is_ready_does_not_advance_warmup() {
+        let symbol = SymbolId::from_u32(1);
+        let mut strategy = WarmupStrategy::new(Box::new(JoinBidStrategy), 1);
+        let bid_only = ContextSnapshot::new(
+            1,
+            symbol,
+            Some((Price::new(100).unwrap(), Qty::new(1).unwrap())),
+            None,
+            0,
+            0,
+        );
+        for _ in 0..5 {
+            assert!(strategy.is_ready(&bid_only));
+        }
+        let mut out = Vec::new();
+        strategy.on_timer(&bid_only, &mut out);
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol,
+            updates: vec![],
+        };
+        strategy.on_market_event(&bid_only, &event, &mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn noop_strategy_returns_empty_intents() {
+        let mut strategy = NoopStrategy;
+        let symbol = SymbolId::from_u32(1);
+        let ctx = ContextSnapshot::new(
+            1,
+            symbol,
+            Some((Price::new(100).unwrap(), Qty::new(1).unwrap())),
+            Some((Price::new(101).unwrap(), Qty::new(1).unwrap())),
+            0,
+            0,
+        );
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol,
+            updates: vec![],
+        };
+
+        let mut intents = Vec::new();
+        strategy.on_market_event(&ctx, &event, &mut intents);
+        assert!(intents.is_empty());
+        strategy.on_timer(&ctx, &mut intents);
+        assert!(intents.is_empty());
+    }
+
+    #[test]
+    fn twap_emits_until_target_reached() {
         let symbol = SymbolId::from_u32(2);
         let mut strategy = TwapStrategy::new(3, 0, 1);
 
@@ -429,6 +1168,8 @@ mod tests {
             ts_ns: 2,
             symbol,
             side: Side::Bid,
+            reason: None,
+            tag: None,
         };
         strategy.on_execution_report(&ctx, &report, &mut intents);
 
@@ -516,4 +1257,337 @@ mod tests {
         assert_eq!(bid_price, Some(94));
         assert_eq!(ask_price, Some(98));
     }
+
+    #[test]
+    fn mm_pulls_quotes_when_skew_leaves_price_range() {
+        let symbol = SymbolId::from_u32(3);
+        let mut mm = MmStrategy::new(2, 1, i64::MAX);
+        let ctx = ctx_with_book(1, symbol, 100, 102, 1_000);
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol,
+            updates: vec![],
+        };
+
+        let mut intents = Vec::new();
+        mm.on_market_event(&ctx, &event, &mut intents);
+        assert!(intents.is_empty());
+    }
+
+    #[test]
+    fn mm_keeps_quoting_the_ask_when_a_long_skew_pushes_the_bid_below_one_tick() {
+        let symbol = SymbolId::from_u32(3);
+        let mut mm = MmStrategy::new(1, 1, 1);
+        let ctx = ctx_with_book(1, symbol, 2, 4, 5);
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol,
+            updates: vec![],
+        };
+
+        let mut intents = Vec::new();
+        mm.on_market_event(&ctx, &event, &mut intents);
+        let quoted: Vec<(Side, i64)> = intents
+            .iter()
+            .filter_map(|intent| match intent {
+                Intent::PlaceLimit { side, price, .. } => Some((*side, price.ticks())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(quoted, vec![(Side::Bid, 1), (Side::Ask, 2)]);
+    }
+
+    #[test]
+    fn two_sided_quotes_pull_when_the_bid_saturates_at_i64_max() {
+        let symbol = SymbolId::from_u32(3);
+        let ctx = ctx_with_book(1, symbol, 100, 102, 0);
+        let mut quotes = TwoSidedQuotes {
+            bid_order_id: Some(ClientOrderId(7)),
+            ..TwoSidedQuotes::default()
+        };
+
+        let mut intents = Vec::new();
+        quotes.quote(&ctx, i64::MAX, i64::MAX, 1, &mut intents);
+        assert_eq!(
+            intents,
+            vec![Intent::Cancel {
+                client_order_id: ClientOrderId(7)
+            }]
+        );
+    }
+
+    fn trade(ts_ns: u64, symbol: SymbolId, qty_lots: i64) -> MarketEvent {
+        MarketEvent::Trade {
+            ts_ns,
+            symbol,
+            price: Price::new(101).unwrap(),
+            qty: Qty::new(qty_lots).unwrap(),
+            aggressor_side: Side::Bid,
+        }
+    }
+
+    fn placed_qty(intents: &[Intent]) -> Option<i64> {
+        intents.iter().find_map(|intent| match intent {
+            Intent::PlaceLimit { qty, .. } => Some(qty.lots()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn vwap_slices_scale_with_trade_volume() {
+        let symbol = SymbolId::from_u32(4);
+        let ctx = ctx_with_book(1, symbol, 100, 102, 0);
+        let mut intents = Vec::new();
+
+        let mut quiet = VwapStrategy::new(10, 2_000);
+        quiet.on_market_event(&ctx, &trade(1, symbol, 4), &mut intents);
+        assert!(intents.is_empty());
+
+        let mut busy = VwapStrategy::new(10, 2_000);
+        busy.on_market_event(&ctx, &trade(1, symbol, 20), &mut intents);
+        assert_eq!(placed_qty(&intents), Some(4));
+
+        intents.clear();
+        quiet.on_market_event(&ctx, &trade(2, symbol, 6), &mut intents);
+        assert_eq!(placed_qty(&intents), Some(2));
+
+        // Trades on other symbols do not count toward participation.
+        let mut other = VwapStrategy::new(10, 2_000);
+        intents.clear();
+        other.on_market_event(&ctx, &trade(1, SymbolId::from_u32(5), 50), &mut intents);
+        assert!(intents.is_empty());
+    }
+
+    #[test]
+    fn vwap_nets_executed_lots_against_volume_budget() {
+        let symbol = SymbolId::from_u32(4);
+        let ctx = ctx_with_book(1, symbol, 100, 102, 0);
+        let mut strategy = VwapStrategy::new(-10, 5_000);
+        let mut intents = Vec::new();
+
+        strategy.on_market_event(&ctx, &trade(1, symbol, 4), &mut intents);
+        assert!(matches!(
+            intents[0],
+            Intent::PlaceLimit { side: Side::Ask, qty, .. } if qty == Qty::new(2).unwrap()
+        ));
+
+        let report = ExecutionReport {
+            client_order_id: ClientOrderId(1),
+            status: OrderStatus::Filled,
+            filled_qty: Qty::new(2).unwrap(),
+            last_fill_price: Price::new(100).unwrap(),
+            fee_ticks: 0,
+            ts_ns: 2,
+            symbol,
+            side: Side::Ask,
+            reason: None,
+            tag: None,
+        };
+        intents.clear();
+        strategy.on_execution_report(&ctx, &report, &mut intents);
+        strategy.on_timer(&ctx, &mut intents);
+        assert!(intents.is_empty());
+
+        strategy.on_market_event(&ctx, &trade(3, symbol, 2), &mut intents);
+        assert_eq!(placed_qty(&intents), Some(1));
+    }
+
+    #[test]
+    fn pov_skips_tiny_volume_and_stops_at_target() {
+        let symbol = SymbolId::from_u32(6);
+        let ctx = ctx_with_book(1, symbol, 100, 102, 0);
+        let mut strategy = PovStrategy::new(5, 2_500);
+        let mut intents = Vec::new();
+
+        strategy.on_market_event(&ctx, &trade(1, symbol, 3), &mut intents);
+        assert!(intents.is_empty());
+
+        strategy.on_market_event(&ctx, &trade(2, symbol, 1), &mut intents);
+        assert_eq!(placed_qty(&intents), Some(1));
+
+        let fill = |qty_lots: i64| ExecutionReport {
+            client_order_id: ClientOrderId(1),
+            status: OrderStatus::Filled,
+            filled_qty: Qty::new(qty_lots).unwrap(),
+            last_fill_price: Price::new(102).unwrap(),
+            fee_ticks: 0,
+            ts_ns: 3,
+            symbol,
+            side: Side::Bid,
+            reason: None,
+            tag: None,
+        };
+        strategy.on_execution_report(&ctx, &fill(1), &mut intents);
+
+        // A large print is capped by the remaining four lots.
+        intents.clear();
+        strategy.on_market_event(&ctx, &trade(4, symbol, 100), &mut intents);
+        assert_eq!(placed_qty(&intents), Some(4));
+
+        // Nothing more is sent while the child is working.
+        intents.clear();
+        strategy.on_market_event(&ctx, &trade(5, symbol, 100), &mut intents);
+        assert!(intents.is_empty());
+
+        strategy.on_execution_report(&ctx, &fill(4), &mut intents);
+        strategy.on_market_event(&ctx, &trade(6, symbol, 100), &mut intents);
+        assert!(intents.is_empty());
+    }
+
+    fn quoted_prices(intents: &[Intent]) -> (Option<i64>, Option<i64>) {
+        let mut bid_price = None;
+        let mut ask_price = None;
+        for intent in intents {
+            if let Intent::PlaceLimit { side, price, .. } = intent {
+                match side {
+                    Side::Bid => bid_price = Some(price.ticks()),
+                    Side::Ask => ask_price = Some(price.ticks()),
+                }
+            }
+        }
+        (bid_price, ask_price)
+    }
+
+    #[test]
+    fn as_mm_shifts_reservation_price_against_inventory() {
+        let symbol = SymbolId::from_u32(7);
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol,
+            updates: vec![],
+        };
+        let mut intents = Vec::new();
+
+        // gamma=0.1, sigma=2, T=10s: variance term is 4 ticks per lot at t=0.
+        let mut flat = AsMmStrategy::new(0.1, 2.0, 1.5, 10, 1);
+        flat.on_market_event(&ctx_with_book(1, symbol, 100, 102, 0), &event, &mut intents);
+        let (flat_bid, flat_ask) = quoted_prices(&intents);
+        let (flat_bid, flat_ask) = (flat_bid.unwrap(), flat_ask.unwrap());
+        assert_eq!(flat_bid + flat_ask, 202);
+        assert!(flat_bid < 101 && flat_ask > 101);
+
+        let mut long = AsMmStrategy::new(0.1, 2.0, 1.5, 10, 1);
+        intents.clear();
+        long.on_market_event(&ctx_with_book(1, symbol, 100, 102, 2), &event, &mut intents);
+        let (long_bid, long_ask) = quoted_prices(&intents);
+        assert_eq!(long_bid, Some(flat_bid - 8));
+        assert_eq!(long_ask, Some(flat_ask - 8));
+    }
+
+    #[test]
+    fn as_mm_spread_narrows_toward_session_end() {
+        let strategy = AsMmStrategy::new(0.1, 2.0, 1.5, 10, 1);
+        let (_, early) = strategy.model(101.0, 0, 0.0);
+        let (_, late) = strategy.model(101.0, 0, 10.0);
+        let (reservation, _) = strategy.model(101.0, 3, 20.0);
+        assert!(late < early);
+        assert_eq!(reservation, 101.0);
+    }
+
+    fn fill_report(
+        id: u64,
+        symbol: SymbolId,
+        side: Side,
+        qty_lots: i64,
+        price: i64,
+    ) -> ExecutionReport {
+        ExecutionReport {
+            client_order_id: ClientOrderId(id),
+            status: OrderStatus::Filled,
+            filled_qty: Qty::new(qty_lots).unwrap(),
+            last_fill_price: Price::new(price).unwrap(),
+            fee_ticks: 0,
+            ts_ns: 1,
+            symbol,
+            side,
+            reason: None,
+            tag: None,
+        }
+    }
+
+    #[test]
+    fn protective_flattens_on_stop_and_target() {
+        let symbol = SymbolId::from_u32(8);
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol,
+            updates: vec![],
+        };
+        let mut intents = Vec::new();
+
+        let mut strategy = ProtectiveStrategy::new(Box::new(NoopStrategy), 3, 5);
+        let ctx = ctx_with_book(1, symbol, 100, 102, 2);
+        strategy.on_execution_report(
+            &ctx,
+            &fill_report(1, symbol, Side::Bid, 2, 101),
+            &mut intents,
+        );
+
+        strategy.on_market_event(&ctx_with_book(2, symbol, 98, 100, 2), &event, &mut intents);
+        assert!(intents.is_empty());
+
+        strategy.on_market_event(&ctx_with_book(3, symbol, 97, 99, 2), &event, &mut intents);
+        assert!(matches!(
+            intents[..],
+            [Intent::PlaceLimit { side: Side::Ask, price, qty, tif: TimeInForce::Ioc, .. }]
+                if price == Price::new(97).unwrap() && qty == Qty::new(2).unwrap()
+        ));
+
+        // No duplicate flatten while the first one is working.
+        intents.clear();
+        strategy.on_market_event(&ctx_with_book(4, symbol, 96, 98, 2), &event, &mut intents);
+        assert!(intents.is_empty());
+
+        let mut strategy = ProtectiveStrategy::new(Box::new(NoopStrategy), 3, 5);
+        let ctx = ctx_with_book(1, symbol, 100, 102, -1);
+        strategy.on_execution_report(
+            &ctx,
+            &fill_report(2, symbol, Side::Ask, 1, 100),
+            &mut intents,
+        );
+        strategy.on_market_event(&ctx_with_book(2, symbol, 94, 96, -1), &event, &mut intents);
+        assert!(matches!(
+            intents[..],
+            [Intent::PlaceLimit { side: Side::Bid, price, .. }] if price == Price::new(96).unwrap()
+        ));
+    }
+
+    #[test]
+    fn protective_delegates_to_inner_strategy() {
+        let symbol = SymbolId::from_u32(8);
+        let mut strategy = ProtectiveStrategy::new(Box::new(TwapStrategy::new(1, 0, 1)), 3, 5);
+        let mut intents = Vec::new();
+        strategy.on_timer(&ctx_with_book(1, symbol, 100, 102, 0), &mut intents);
+        assert_eq!(placed_qty(&intents), Some(1));
+    }
+
+    #[test]
+    fn composite_of_noop_and_twap_matches_standalone_twap() {
+        let symbol = SymbolId::from_u32(9);
+        let event = MarketEvent::L2Delta {
+            ts_ns: 1,
+            symbol,
+            updates: vec![],
+        };
+        let mut standalone = TwapStrategy::new(2, 0, 1);
+        let mut composite = CompositeStrategy::new(vec![
+            Box::new(NoopStrategy),
+            Box::new(TwapStrategy::new(2, 0, 1)),
+        ]);
+
+        let mut expected = Vec::new();
+        let mut actual = Vec::new();
+        for ts_ns in 1..=4 {
+            let ctx = ctx_with_book(ts_ns, symbol, 100, 102, 0);
+            standalone.on_market_event(&ctx, &event, &mut expected);
+            composite.on_market_event(&ctx, &event, &mut actual);
+
+            let report = fill_report(ts_ns, symbol, Side::Bid, 1, 102);
+            standalone.on_execution_report(&ctx, &report, &mut expected);
+            composite.on_execution_report(&ctx, &report, &mut actual);
+        }
+
+        assert_eq!(expected.len(), 2);
+        assert_eq!(actual, expected);
+    }
 }
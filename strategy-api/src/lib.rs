@@ -1,5 +1,5 @@
 use lob_core::{MarketEvent, Price, Qty, SymbolId};
-use trading_types::{ExecutionReport, Intent};
+use trading_types::{ClientOrderId, ExecutionReport, Intent};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ContextSnapshot {
@@ -9,7 +9,18 @@ pub struct ContextSnapshot {
     pub best_ask: Option<(Price, Qty)>,
     pub position_lots: i64,
     pub open_orders: usize,
+    /// Best-priced open limit bid for `symbol` as `(id, price, unfilled qty)`.
+    pub open_bid: Option<(ClientOrderId, Price, Qty)>,
+    pub open_ask: Option<(ClientOrderId, Price, Qty)>,
+    /// Book levels per side, best first; `new` fills in just the top of book.
+    pub bid_depth: Vec<(Price, Qty)>,
+    pub ask_depth: Vec<(Price, Qty)>,
     pub mid_price: Option<Price>,
+    /// Traded volume for `symbol` over the engine's rolling trade window.
+    pub recent_trade_volume: Qty,
+    pub realized_pnl_ticks: i128,
+    /// Mark-to-mid PnL of the open position; `None` without a two-sided quote.
+    pub unrealized_pnl_ticks: Option<i128>,
 }
 
 impl ContextSnapshot {
@@ -37,12 +48,73 @@ impl ContextSnapshot {
             best_ask,
             position_lots,
             open_orders,
+            open_bid: None,
+            open_ask: None,
+            bid_depth: best_bid.into_iter().collect(),
+            ask_depth: best_ask.into_iter().collect(),
             mid_price,
+            recent_trade_volume: zero_qty(),
+            realized_pnl_ticks: 0,
+            unrealized_pnl_ticks: None,
         }
     }
+
+    pub fn with_recent_trade_volume(mut self, recent_trade_volume: Qty) -> Self {
+        self.recent_trade_volume = recent_trade_volume;
+        self
+    }
+
+    pub fn with_depth(mut self, bids: Vec<(Price, Qty)>, asks: Vec<(Price, Qty)>) -> Self {
+        self.bid_depth = bids;
+        self.ask_depth = asks;
+        self
+    }
+
+    pub fn with_open_quotes(
+        mut self,
+        open_bid: Option<(ClientOrderId, Price, Qty)>,
+        open_ask: Option<(ClientOrderId, Price, Qty)>,
+    ) -> Self {
+        self.open_bid = open_bid;
+        self.open_ask = open_ask;
+        self
+    }
+
+    pub fn with_pnl(
+        mut self,
+        realized_pnl_ticks: i128,
+        unrealized_pnl_ticks: Option<i128>,
+    ) -> Self {
+        self.realized_pnl_ticks = realized_pnl_ticks;
+        self.unrealized_pnl_ticks = unrealized_pnl_ticks;
+        self
+    }
+}
+
+fn zero_qty() -> Qty {
+    match Qty::new(0) {
+        Ok(qty) => qty,
+        Err(_) => unreachable!("zero qty must be valid"),
+    }
 }
 
+/// Identifies which scheduled timer fired; engines with a single timer use `DEFAULT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(pub u32);
+
+impl TimerId {
+    pub const DEFAULT: TimerId = TimerId(0);
+}
+
+/// Intents are appended to the caller-owned `out` buffer; callees never clear it.
 pub trait Strategy {
+    /// Checked by the engine before `on_market_event` and `on_timer`; while false, both
+    /// are skipped. Execution reports are always delivered. May be called any number of
+    /// times per event, so it must not change state.
+    fn is_ready(&self, _ctx: &ContextSnapshot) -> bool {
+        true
+    }
+
     fn on_market_event(
         &mut self,
         ctx: &ContextSnapshot,
@@ -52,6 +124,11 @@ pub trait Strategy {
 
     fn on_timer(&mut self, _ctx: &ContextSnapshot, _out: &mut Vec<Intent>) {}
 
+    /// What the engine calls on each timer tick; override to tell timers apart.
+    fn on_timer_id(&mut self, _timer: TimerId, ctx: &ContextSnapshot, out: &mut Vec<Intent>) {
+        self.on_timer(ctx, out);
+    }
+
     fn on_execution_report(
         &mut self,
         _ctx: &ContextSnapshot,
@@ -59,4 +136,22 @@ pub trait Strategy {
         _out: &mut Vec<Intent>,
     ) {
     }
+
+    /// Called after `on_execution_report` for `Accepted` reports.
+    fn on_order_accepted(
+        &mut self,
+        _ctx: &ContextSnapshot,
+        _report: &ExecutionReport,
+        _out: &mut Vec<Intent>,
+    ) {
+    }
+
+    /// Called after `on_execution_report` for `Rejected` reports.
+    fn on_order_rejected(
+        &mut self,
+        _ctx: &ContextSnapshot,
+        _report: &ExecutionReport,
+        _out: &mut Vec<Intent>,
+    ) {
+    }
 }
@@ -14,6 +14,14 @@ pub enum TimeInForce {
     Fok,
 }
 
+/// Book level a pegged order's price is taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PegRef {
+    BestBid,
+    BestAsk,
+    Mid,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderType {
     Limit,
@@ -43,6 +51,13 @@ pub struct OrderRequest {
     pub tif: TimeInForce,
 }
 
+/// Why a venue produced a report, when the status alone is ambiguous.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportReason {
+    /// Canceled instead of filling against another order from the same account.
+    SelfTradePrevention,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecutionReport {
     pub client_order_id: ClientOrderId,
@@ -56,6 +71,12 @@ pub struct ExecutionReport {
     pub ts_ns: u64,
     pub symbol: SymbolId,
     pub side: Side,
+    #[serde(default)]
+    pub reason: Option<ReportReason>,
+    /// Tag of the originating intent; venues leave it `None` and the engine fills it in
+    /// from the OMS before dispatching the report.
+    #[serde(default)]
+    pub tag: Option<OrderTag>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -68,6 +89,25 @@ pub enum Intent {
         tif: TimeInForce,
         tag: Option<OrderTag>,
     },
+    /// Takes liquidity at any price; any remainder the venue cannot fill is not rested.
+    PlaceMarket {
+        symbol: SymbolId,
+        side: Side,
+        qty: Qty,
+        tif: TimeInForce,
+        tag: Option<OrderTag>,
+    },
+    /// A limit order priced at `reference + offset_ticks`, resolved by the engine against
+    /// the book at intent time.
+    PlacePegged {
+        symbol: SymbolId,
+        side: Side,
+        reference: PegRef,
+        offset_ticks: i64,
+        qty: Qty,
+        tif: TimeInForce,
+        tag: Option<OrderTag>,
+    },
     Cancel {
         client_order_id: ClientOrderId,
     },
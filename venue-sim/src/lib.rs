@@ -1,11 +1,13 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 
-use lob_core::{Price, Qty, Side, SymbolId};
+use lob_core::{MarketEvent, Price, Qty, Side, SymbolId};
 use oms::OrderRequest;
 use orderbook::OrderBook;
-use trading_types::{ClientOrderId, ExecutionReport, OrderStatus, OrderType};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use trading_types::{ClientOrderId, ExecutionReport, OrderStatus, OrderType, ReportReason};
 use venue::ExecutionVenue;
 
 const MAX_PASSIVE_FILLS_PER_EVENT: usize = 1024;
@@ -30,76 +32,441 @@ struct LiveOrder {
     side: Side,
     price: Option<Price>,
     qty: Qty,
+    filled: Qty,
+    /// Displayed size ahead of this order at its price (queue model only).
+    queue_ahead_lots: i64,
+    /// Trade volume at or through this order's price beyond the queue ahead, not yet filled.
+    trade_fillable_lots: i64,
+}
+
+fn crosses(side: Side, limit: Option<Price>, level_price: Price) -> bool {
+    let Some(limit) = limit else {
+        return true;
+    };
+    match side {
+        Side::Bid => limit.ticks() >= level_price.ticks(),
+        Side::Ask => limit.ticks() <= level_price.ticks(),
+    }
+}
+
+/// Walks opposite-side levels best-first. When `capped` is false the whole remainder
+/// fills at the first crossing level, matching the original full-fill model.
+fn walk_levels(
+    levels: impl Iterator<Item = (Price, Qty)>,
+    side: Side,
+    limit: Option<Price>,
+    remaining_lots: i64,
+    capped: bool,
+    out: &mut Vec<(Price, Qty)>,
+) {
+    let mut left = remaining_lots;
+    for (level_price, level_qty) in levels {
+        if left == 0 || !crosses(side, limit, level_price) {
+            break;
+        }
+        let take = if capped {
+            left.min(level_qty.lots())
+        } else {
+            left
+        };
+        let Ok(take_qty) = Qty::new(take) else {
+            break;
+        };
+        out.push((level_price, take_qty));
+        left -= take;
+        if !capped {
+            break;
+        }
+    }
+}
+
+/// Self-trade prevention policy; every order routed through one venue is treated as
+/// coming from the same account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StpMode {
+    #[default]
+    Off,
+    /// When a resting buy and sell would both fill at the same price in one book update,
+    /// the newer order (higher client order id) is canceled instead of filled.
+    CancelNewest,
+}
+
+/// One fee tier, active once cumulative filled lots reach `min_volume_lots`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeTier {
+    pub min_volume_lots: i64,
+    pub maker_fee_ticks: i64,
+    pub taker_fee_ticks: i64,
+}
+
+/// Maker/taker fees per fill, tiered by the venue's cumulative filled lots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeSchedule {
+    /// Ascending by `min_volume_lots`; the first tier always starts at zero.
+    tiers: Vec<FeeTier>,
+}
+
+impl FeeSchedule {
+    pub fn flat(maker_fee_ticks: i64, taker_fee_ticks: i64) -> Self {
+        Self {
+            tiers: vec![FeeTier {
+                min_volume_lots: 0,
+                maker_fee_ticks,
+                taker_fee_ticks,
+            }],
+        }
+    }
+
+    /// Adds a tier, replacing any existing tier with the same threshold.
+    pub fn with_tier(
+        mut self,
+        min_volume_lots: i64,
+        maker_fee_ticks: i64,
+        taker_fee_ticks: i64,
+    ) -> Self {
+        let tier = FeeTier {
+            min_volume_lots: min_volume_lots.max(0),
+            maker_fee_ticks,
+            taker_fee_ticks,
+        };
+        match self
+            .tiers
+            .binary_search_by_key(&tier.min_volume_lots, |tier| tier.min_volume_lots)
+        {
+            Ok(index) => self.tiers[index] = tier,
+            Err(index) => self.tiers.insert(index, tier),
+        }
+        self
+    }
+
+    /// The highest tier whose threshold `volume_lots` has reached.
+    pub fn tier_for(&self, volume_lots: i64) -> FeeTier {
+        let index = self
+            .tiers
+            .partition_point(|tier| tier.min_volume_lots <= volume_lots);
+        self.tiers[index.saturating_sub(1)]
+    }
+
+    fn fee_ticks(&self, liquidity: Liquidity, volume_lots: i64) -> i64 {
+        let tier = self.tier_for(volume_lots);
+        match liquidity {
+            Liquidity::Maker => tier.maker_fee_ticks,
+            Liquidity::Taker => tier.taker_fee_ticks,
+        }
+    }
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        Self::flat(0, 0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Liquidity {
+    Maker,
+    Taker,
 }
 
 pub struct SimVenue {
     book: Rc<RefCell<OrderBook>>,
-    maker_fee_ticks: i64,
-    taker_fee_ticks: i64,
+    fee_schedule: FeeSchedule,
+    /// Lots filled across all orders, which selects the fee tier.
+    traded_lots: i64,
+    liquidity_aware_fills: bool,
+    queue_model: bool,
+    impact_bps_per_lot: i64,
+    reject_rate: f64,
+    reject_rng: Option<StdRng>,
+    stp_mode: StpMode,
+    latency_ns: u64,
+    pending: VecDeque<(u64, OrderRequest)>,
     next_ts_ns: u64,
     live_orders: HashMap<ClientOrderId, LiveOrder>,
     order_scan_ids: Vec<ClientOrderId>,
-    fill_candidates: Vec<(ClientOrderId, Price)>,
+    fill_levels: Vec<(Price, Qty)>,
+    stp_fill_prices: Vec<(Side, Price)>,
+    stp_canceled: Vec<ClientOrderId>,
 }
 
 impl SimVenue {
     pub fn new(book: Rc<RefCell<OrderBook>>, maker_fee_ticks: i64, taker_fee_ticks: i64) -> Self {
         Self {
             book,
-            maker_fee_ticks,
-            taker_fee_ticks,
+            fee_schedule: FeeSchedule::flat(maker_fee_ticks, taker_fee_ticks),
+            traded_lots: 0,
+            liquidity_aware_fills: false,
+            queue_model: false,
+            impact_bps_per_lot: 0,
+            reject_rate: 0.0,
+            reject_rng: None,
+            stp_mode: StpMode::Off,
+            latency_ns: 0,
+            pending: VecDeque::new(),
             next_ts_ns: 1,
             live_orders: HashMap::new(),
             order_scan_ids: Vec::new(),
-            fill_candidates: Vec::new(),
+            fill_levels: Vec::new(),
+            stp_fill_prices: Vec::new(),
+            stp_canceled: Vec::new(),
         }
     }
 
+    /// Replaces the flat fees with `schedule`. Each fill is charged at the tier for the
+    /// volume filled before it, so the fill that crosses a threshold still pays the old rate.
+    pub fn with_fee_schedule(mut self, schedule: FeeSchedule) -> Self {
+        self.fee_schedule = schedule;
+        self
+    }
+
+    /// Lots filled so far across all orders.
+    pub fn traded_lots(&self) -> i64 {
+        self.traded_lots
+    }
+
+    /// Caps fills at displayed level size, walking deeper levels while they still cross.
+    /// Unfilled limit remainder keeps resting; fills report `PartiallyFilled` until done.
+    /// The replay book is never depleted, so each order sees the full displayed depth.
+    pub fn with_liquidity_aware_fills(mut self, enabled: bool) -> Self {
+        self.liquidity_aware_fills = enabled;
+        self
+    }
+
+    /// Resting orders join the back of the displayed queue at their price. A passive
+    /// fill needs either the book to trade through the price, or trade prints at the
+    /// price exceeding the queue ahead; merely touching the price is not enough.
+    /// Cancels ahead of the order are not modeled, so the estimate is conservative.
+    pub fn with_queue_model(mut self, enabled: bool) -> Self {
+        self.queue_model = enabled;
+        self
+    }
+
+    /// Worsens marketable fills by `impact_ticks = level_price * bps_per_lot * (N - 1) / 10_000`
+    /// (integer floor), where `N` is the order size in lots. Buys fill higher and sells
+    /// lower, never past the order's limit or below zero; a one-lot order has no impact.
+    /// Passive fills are unaffected.
+    pub fn with_impact(mut self, bps_per_lot: i64) -> Self {
+        self.impact_bps_per_lot = bps_per_lot;
+        self
+    }
+
+    /// Rejects roughly `rate` of `Place`/`Replace` requests using a `StdRng` seeded with
+    /// `seed`, so the same seed and request stream always reject the same requests.
+    /// A rejected replace also drops the resting order, matching the OMS terminal state.
+    pub fn with_reject_rate(mut self, rate: f64, seed: u64) -> Self {
+        self.reject_rate = if rate.is_nan() {
+            0.0
+        } else {
+            rate.clamp(0.0, 1.0)
+        };
+        self.reject_rng = Some(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Canceled self-trades carry `ReportReason::SelfTradePrevention`.
+    pub fn with_self_trade_prevention(mut self, mode: StpMode) -> Self {
+        self.stp_mode = mode;
+        self
+    }
+
+    /// Delays every request by `latency_ns` of book time. Requests are queued at
+    /// submit and processed in `on_book_update` once the book's last event timestamp
+    /// reaches `submit_ts + latency_ns`, against the book state at that moment.
+    pub fn with_latency_ns(mut self, latency_ns: u64) -> Self {
+        self.latency_ns = latency_ns;
+        self
+    }
+
     fn next_ts(&mut self) -> u64 {
         let ts = self.next_ts_ns;
         self.next_ts_ns = self.next_ts_ns.saturating_add(1);
         ts
     }
 
+    /// Fills `fill_levels` with the executions available to an order taking liquidity.
+    fn collect_fills(&mut self, side: Side, limit: Option<Price>, remaining_lots: i64) {
+        self.fill_levels.clear();
+        if remaining_lots <= 0 {
+            return;
+        }
+
+        let book = self.book.borrow();
+        let capped = self.liquidity_aware_fills;
+        match side {
+            Side::Bid => walk_levels(
+                book.ask_levels(),
+                side,
+                limit,
+                remaining_lots,
+                capped,
+                &mut self.fill_levels,
+            ),
+            Side::Ask => walk_levels(
+                book.bid_levels(),
+                side,
+                limit,
+                remaining_lots,
+                capped,
+                &mut self.fill_levels,
+            ),
+        }
+    }
+
+    fn should_reject(&mut self) -> bool {
+        let rate = self.reject_rate;
+        self.reject_rng
+            .as_mut()
+            .is_some_and(|rng| rng.gen_bool(rate))
+    }
+
+    fn apply_impact(&mut self, side: Side, limit: Option<Price>, order_lots: i64) {
+        if self.impact_bps_per_lot == 0 {
+            return;
+        }
+        let extra_lots = order_lots.saturating_sub(1).max(0) as i128;
+        for (price, _) in &mut self.fill_levels {
+            let impact =
+                price.ticks() as i128 * self.impact_bps_per_lot as i128 * extra_lots / 10_000;
+            let impacted = match side {
+                Side::Bid => price.ticks() as i128 + impact,
+                Side::Ask => price.ticks() as i128 - impact,
+            };
+            let impacted = match (side, limit) {
+                (Side::Bid, Some(limit)) => impacted.min(limit.ticks() as i128),
+                (Side::Ask, Some(limit)) => impacted.max(limit.ticks() as i128),
+                (_, None) => impacted,
+            };
+            let impacted = impacted.clamp(0, i64::MAX as i128) as i64;
+            *price = Price::new(impacted).unwrap_or(*price);
+        }
+    }
+
+    fn queue_ahead_at(&self, side: Side, price: Option<Price>) -> i64 {
+        if !self.queue_model {
+            return 0;
+        }
+        price
+            .and_then(|price| self.book.borrow().level_qty(side, price))
+            .map_or(0, Qty::lots)
+    }
+
+    fn collect_queue_fills(&mut self, order: &LiveOrder, limit: Price, remaining_lots: i64) {
+        let through_ticks = match order.side {
+            Side::Bid => limit.ticks() - 1,
+            Side::Ask => limit.ticks() + 1,
+        };
+        match Price::new(through_ticks) {
+            Ok(through) => self.collect_fills(order.side, Some(through), remaining_lots),
+            Err(_) => self.fill_levels.clear(),
+        }
+
+        if self.fill_levels.is_empty() && order.trade_fillable_lots > 0 {
+            if let Ok(qty) = Qty::new(order.trade_fillable_lots.min(remaining_lots)) {
+                self.fill_levels.push((limit, qty));
+            }
+        }
+    }
+
+    /// Collects the passive fills `order` would get from the current book into
+    /// `fill_levels`; returns its limit price when there is at least one.
+    fn collect_passive_fills(&mut self, order: &LiveOrder) -> Option<Price> {
+        let limit_price = order.price?;
+        let remaining = order.qty.lots() - order.filled.lots();
+        if self.queue_model {
+            self.collect_queue_fills(order, limit_price, remaining);
+        } else {
+            self.collect_fills(order.side, Some(limit_price), remaining);
+        }
+        (!self.fill_levels.is_empty()).then_some(limit_price)
+    }
+
+    /// Fills the `stp_canceled` list: scanning `ids_by_age` oldest first, an order whose
+    /// first fill price matches an older, uncanceled opposite-side fill is canceled.
+    fn mark_self_trades(&mut self, ids_by_age: &[ClientOrderId]) {
+        self.stp_fill_prices.clear();
+        self.stp_canceled.clear();
+        for client_order_id in ids_by_age {
+            let Some(order) = self.live_orders.get(client_order_id).cloned() else {
+                continue;
+            };
+            if self.collect_passive_fills(&order).is_none() {
+                continue;
+            }
+            let first_fill_price = self.fill_levels[0].0;
+            let self_trade = self
+                .stp_fill_prices
+                .iter()
+                .any(|(side, price)| *side != order.side && *price == first_fill_price);
+            if self_trade {
+                self.stp_canceled.push(*client_order_id);
+            } else {
+                self.stp_fill_prices.push((order.side, first_fill_price));
+            }
+        }
+        self.fill_levels.clear();
+    }
+
+    /// Emits one report per collected fill and returns the new cumulative filled lots.
+    fn emit_fills(
+        &mut self,
+        client_order_id: ClientOrderId,
+        order: &LiveOrder,
+        liquidity: Liquidity,
+        out: &mut Vec<ExecutionReport>,
+    ) -> i64 {
+        let mut fill_levels = std::mem::take(&mut self.fill_levels);
+        let mut filled = order.filled.lots();
+        for (fill_price, fill_qty) in fill_levels.drain(..) {
+            let fee_ticks = self.fee_schedule.fee_ticks(liquidity, self.traded_lots);
+            self.traded_lots = self.traded_lots.saturating_add(fill_qty.lots());
+            filled += fill_qty.lots();
+            let status = if filled >= order.qty.lots() {
+                OrderStatus::Filled
+            } else {
+                OrderStatus::PartiallyFilled
+            };
+            out.push(ExecutionReport {
+                client_order_id,
+                status,
+                filled_qty: Qty::new(filled).unwrap_or(order.qty),
+                last_fill_price: fill_price,
+                fee_ticks,
+                ts_ns: self.next_ts(),
+                symbol: order.symbol,
+                side: order.side,
+                reason: None,
+                tag: None,
+            });
+        }
+        self.fill_levels = fill_levels;
+        filled
+    }
+
     fn handle_place(
         &mut self,
         order: &trading_types::OrderRequest,
         out: &mut Vec<ExecutionReport>,
     ) {
-        let (best_bid, best_ask) = {
-            let book = self.book.borrow();
-            (book.best_bid(), book.best_ask())
-        };
+        if self.should_reject() {
+            out.push(self.rejected(order));
+            return;
+        }
 
-        let crossing_price = match order.order_type {
+        let limit = match order.order_type {
             OrderType::Limit => {
                 let Some(limit) = order.price else {
                     out.push(self.rejected(order));
                     return;
                 };
-                match order.side {
-                    Side::Bid => best_ask.and_then(|(ask, _)| {
-                        if limit.ticks() >= ask.ticks() {
-                            Some(ask)
-                        } else {
-                            None
-                        }
-                    }),
-                    Side::Ask => best_bid.and_then(|(bid, _)| {
-                        if limit.ticks() <= bid.ticks() {
-                            Some(bid)
-                        } else {
-                            None
-                        }
-                    }),
-                }
+                Some(limit)
             }
-            OrderType::Market => match order.side {
-                Side::Bid => best_ask.map(|(ask, _)| ask),
-                Side::Ask => best_bid.map(|(bid, _)| bid),
-            },
+            OrderType::Market => None,
         };
 
+        self.collect_fills(order.side, limit, order.qty.lots());
+        self.apply_impact(order.side, limit, order.qty.lots());
+        let crossing_price = self.fill_levels.first().map(|(price, _)| *price);
+
         let ack_price = crossing_price.or(order.price).unwrap_or_else(zero_price);
         out.push(ExecutionReport {
             client_order_id: order.client_order_id,
@@ -110,29 +477,35 @@ impl SimVenue {
             ts_ns: self.next_ts(),
             symbol: order.symbol,
             side: order.side,
+            reason: None,
+            tag: None,
         });
 
-        if let Some(fill_price) = crossing_price {
-            out.push(ExecutionReport {
-                client_order_id: order.client_order_id,
-                status: OrderStatus::Filled,
-                filled_qty: order.qty,
-                last_fill_price: fill_price,
-                fee_ticks: self.taker_fee_ticks,
-                ts_ns: self.next_ts(),
-                symbol: order.symbol,
-                side: order.side,
-            });
-        } else if order.order_type == OrderType::Limit {
-            self.live_orders.insert(
-                order.client_order_id,
-                LiveOrder {
-                    symbol: order.symbol,
-                    side: order.side,
-                    price: order.price,
-                    qty: order.qty,
-                },
-            );
+        let mut live = LiveOrder {
+            symbol: order.symbol,
+            side: order.side,
+            price: order.price,
+            qty: order.qty,
+            filled: zero_qty(),
+            queue_ahead_lots: 0,
+            trade_fillable_lots: 0,
+        };
+        let filled = self.emit_fills(order.client_order_id, &live, Liquidity::Taker, out);
+        if filled >= order.qty.lots() {
+            return;
+        }
+
+        live.filled = Qty::new(filled).unwrap_or_else(|_| zero_qty());
+        match order.order_type {
+            OrderType::Limit => {
+                live.queue_ahead_lots = self.queue_ahead_at(order.side, order.price);
+                out.push(self.working(order.client_order_id, &live));
+                self.live_orders.insert(order.client_order_id, live);
+            }
+            OrderType::Market if filled > 0 => {
+                out.push(self.canceled(order.client_order_id, &live, ack_price));
+            }
+            OrderType::Market => {}
         }
     }
 
@@ -147,54 +520,64 @@ impl SimVenue {
             return;
         };
 
-        order.price = Some(new_price);
-        order.qty = new_qty;
+        if self.should_reject() {
+            out.push(ExecutionReport {
+                client_order_id,
+                status: OrderStatus::Rejected,
+                filled_qty: order.filled,
+                last_fill_price: new_price,
+                fee_ticks: 0,
+                ts_ns: self.next_ts(),
+                symbol: order.symbol,
+                side: order.side,
+                reason: None,
+                tag: None,
+            });
+            return;
+        }
 
-        let (best_bid, best_ask) = {
-            let book = self.book.borrow();
-            (book.best_bid(), book.best_ask())
-        };
+        // A same-price size-down keeps queue priority, like real venues do.
+        if order.price == Some(new_price) && new_qty <= order.qty {
+            order.qty = new_qty;
+            if new_qty <= order.filled {
+                out.push(self.canceled(client_order_id, &order, new_price));
+                return;
+            }
+            out.push(self.working(client_order_id, &order));
+            self.live_orders.insert(client_order_id, order);
+            return;
+        }
 
-        let crossing_price = match order.side {
-            Side::Bid => best_ask.and_then(|(ask, _)| {
-                if new_price.ticks() >= ask.ticks() {
-                    Some(ask)
-                } else {
-                    None
-                }
-            }),
-            Side::Ask => best_bid.and_then(|(bid, _)| {
-                if new_price.ticks() <= bid.ticks() {
-                    Some(bid)
-                } else {
-                    None
-                }
-            }),
-        };
+        order.price = Some(new_price);
+        order.qty = new_qty;
 
         out.push(ExecutionReport {
             client_order_id,
             status: OrderStatus::Accepted,
-            filled_qty: zero_qty(),
+            filled_qty: order.filled,
             last_fill_price: new_price,
             fee_ticks: 0,
             ts_ns: self.next_ts(),
             symbol: order.symbol,
             side: order.side,
+            reason: None,
+            tag: None,
         });
 
-        if let Some(fill_price) = crossing_price {
-            out.push(ExecutionReport {
-                client_order_id,
-                status: OrderStatus::Filled,
-                filled_qty: new_qty,
-                last_fill_price: fill_price,
-                fee_ticks: self.taker_fee_ticks,
-                ts_ns: self.next_ts(),
-                symbol: order.symbol,
-                side: order.side,
-            });
-        } else {
+        let remaining = new_qty.lots() - order.filled.lots();
+        if remaining <= 0 {
+            out.push(self.canceled(client_order_id, &order, new_price));
+            return;
+        }
+
+        self.collect_fills(order.side, Some(new_price), remaining);
+        self.apply_impact(order.side, Some(new_price), remaining);
+        let filled = self.emit_fills(client_order_id, &order, Liquidity::Taker, out);
+        if filled < new_qty.lots() {
+            order.filled = Qty::new(filled).unwrap_or(order.filled);
+            order.queue_ahead_lots = self.queue_ahead_at(order.side, order.price);
+            order.trade_fillable_lots = 0;
+            out.push(self.working(client_order_id, &order));
             self.live_orders.insert(client_order_id, order);
         }
     }
@@ -204,16 +587,44 @@ impl SimVenue {
             return;
         };
 
-        out.push(ExecutionReport {
+        let price = order.price.unwrap_or_else(zero_price);
+        out.push(self.canceled(client_order_id, &order, price));
+    }
+
+    /// Confirms an order is resting on the book.
+    fn working(&mut self, client_order_id: ClientOrderId, order: &LiveOrder) -> ExecutionReport {
+        ExecutionReport {
             client_order_id,
-            status: OrderStatus::Canceled,
-            filled_qty: zero_qty(),
+            status: OrderStatus::Working,
+            filled_qty: order.filled,
             last_fill_price: order.price.unwrap_or_else(zero_price),
             fee_ticks: 0,
             ts_ns: self.next_ts(),
             symbol: order.symbol,
             side: order.side,
-        });
+            reason: None,
+            tag: None,
+        }
+    }
+
+    fn canceled(
+        &mut self,
+        client_order_id: ClientOrderId,
+        order: &LiveOrder,
+        price: Price,
+    ) -> ExecutionReport {
+        ExecutionReport {
+            client_order_id,
+            status: OrderStatus::Canceled,
+            filled_qty: order.filled,
+            last_fill_price: price,
+            fee_ticks: 0,
+            ts_ns: self.next_ts(),
+            symbol: order.symbol,
+            side: order.side,
+            reason: None,
+            tag: None,
+        }
     }
 
     fn rejected(&mut self, order: &trading_types::OrderRequest) -> ExecutionReport {
@@ -226,36 +637,14 @@ impl SimVenue {
             ts_ns: self.next_ts(),
             symbol: order.symbol,
             side: order.side,
-        }
-    }
-
-    fn passive_fill_price(
-        side: Side,
-        limit_price: Price,
-        best_bid: Option<(Price, Qty)>,
-        best_ask: Option<(Price, Qty)>,
-    ) -> Option<Price> {
-        match side {
-            Side::Bid => best_ask.and_then(|(ask, _)| {
-                if limit_price.ticks() >= ask.ticks() {
-                    Some(ask)
-                } else {
-                    None
-                }
-            }),
-            Side::Ask => best_bid.and_then(|(bid, _)| {
-                if limit_price.ticks() <= bid.ticks() {
-                    Some(bid)
-                } else {
-                    None
-                }
-            }),
+            reason: None,
+            tag: None,
         }
     }
 }
 
-impl ExecutionVenue for SimVenue {
-    fn submit(&mut self, req: &OrderRequest, out: &mut Vec<ExecutionReport>) {
+impl SimVenue {
+    fn process_request(&mut self, req: &OrderRequest, out: &mut Vec<ExecutionReport>) {
         match req {
             OrderRequest::Place(order) => self.handle_place(order, out),
             OrderRequest::Cancel {
@@ -270,67 +659,222 @@ impl ExecutionVenue for SimVenue {
         }
     }
 
-    fn on_book_update(&mut self, out: &mut Vec<ExecutionReport>) {
-        let (best_bid, best_ask) = {
-            let book = self.book.borrow();
-            (book.best_bid(), book.best_ask())
-        };
-
-        self.order_scan_ids.clear();
-        self.order_scan_ids.extend(self.live_orders.keys().copied());
-        self.order_scan_ids.sort_unstable_by_key(|id| id.0);
-
-        self.fill_candidates.clear();
-        for client_order_id in &self.order_scan_ids {
-            if self.fill_candidates.len() >= MAX_PASSIVE_FILLS_PER_EVENT {
+    fn release_ready_requests(&mut self, out: &mut Vec<ExecutionReport>) {
+        let now_ns = self.book.borrow().last_ts_ns();
+        while let Some((ready_ts_ns, _)) = self.pending.front() {
+            if *ready_ts_ns > now_ns {
                 break;
             }
-            let Some(order) = self.live_orders.get(client_order_id) else {
-                continue;
+            let Some((_, req)) = self.pending.pop_front() else {
+                break;
             };
-            let Some(limit_price) = order.price else {
+            self.process_request(&req, out);
+        }
+    }
+}
+
+impl ExecutionVenue for SimVenue {
+    fn submit(&mut self, req: &OrderRequest, out: &mut Vec<ExecutionReport>) {
+        if self.latency_ns == 0 {
+            self.process_request(req, out);
+            return;
+        }
+
+        let submit_ts_ns = self.book.borrow().last_ts_ns();
+        self.pending
+            .push_back((submit_ts_ns.saturating_add(self.latency_ns), req.clone()));
+    }
+
+    fn on_market_event(&mut self, event: &MarketEvent) {
+        if !self.queue_model {
+            return;
+        }
+        let MarketEvent::Trade {
+            symbol,
+            price,
+            qty,
+            aggressor_side,
+            ..
+        } = event
+        else {
+            return;
+        };
+
+        for order in self.live_orders.values_mut() {
+            let Some(limit) = order.price else {
                 continue;
             };
-            let Some(fill_price) =
-                Self::passive_fill_price(order.side, limit_price, best_bid, best_ask)
-            else {
+            if order.symbol != *symbol || order.side == *aggressor_side {
                 continue;
+            }
+
+            let through = match order.side {
+                Side::Bid => price.ticks() < limit.ticks(),
+                Side::Ask => price.ticks() > limit.ticks(),
             };
-            self.fill_candidates.push((*client_order_id, fill_price));
+            if through {
+                order.queue_ahead_lots = 0;
+                order.trade_fillable_lots = order.trade_fillable_lots.saturating_add(qty.lots());
+            } else if *price == limit {
+                let consumed = qty.lots().min(order.queue_ahead_lots);
+                order.queue_ahead_lots -= consumed;
+                order.trade_fillable_lots = order
+                    .trade_fillable_lots
+                    .saturating_add(qty.lots() - consumed);
+            }
+        }
+    }
+
+    fn on_book_update(&mut self, out: &mut Vec<ExecutionReport>) {
+        let mut order_scan_ids = std::mem::take(&mut self.order_scan_ids);
+        order_scan_ids.clear();
+        order_scan_ids.extend(self.live_orders.keys().copied());
+        order_scan_ids.sort_unstable_by_key(|id| id.0);
+        if self.stp_mode == StpMode::CancelNewest {
+            self.mark_self_trades(&order_scan_ids);
         }
+        // Most aggressive price first on each side, then oldest; bids scan before asks.
+        order_scan_ids.sort_unstable_by_key(|id| {
+            let order = &self.live_orders[id];
+            let priority = match (order.side, order.price) {
+                (_, None) => i64::MAX,
+                (Side::Bid, Some(price)) => -price.ticks(),
+                (Side::Ask, Some(price)) => price.ticks(),
+            };
+            (order.side == Side::Ask, priority, id.0)
+        });
 
-        let mut fill_candidates = std::mem::take(&mut self.fill_candidates);
-        for (client_order_id, fill_price) in fill_candidates.drain(..) {
-            let Some(order) = self.live_orders.remove(&client_order_id) else {
+        let mut filled_orders = 0usize;
+        for client_order_id in &order_scan_ids {
+            if filled_orders >= MAX_PASSIVE_FILLS_PER_EVENT {
+                break;
+            }
+            let Some(order) = self.live_orders.get(client_order_id).cloned() else {
                 continue;
             };
-            out.push(ExecutionReport {
-                client_order_id,
-                status: OrderStatus::Filled,
-                filled_qty: order.qty,
-                last_fill_price: fill_price,
-                fee_ticks: self.maker_fee_ticks,
-                ts_ns: self.next_ts(),
-                symbol: order.symbol,
-                side: order.side,
-            });
+            let Some(limit_price) = self.collect_passive_fills(&order) else {
+                continue;
+            };
+
+            if self.stp_canceled.contains(client_order_id) {
+                self.live_orders.remove(client_order_id);
+                let mut report = self.canceled(*client_order_id, &order, limit_price);
+                report.reason = Some(ReportReason::SelfTradePrevention);
+                out.push(report);
+                continue;
+            }
+
+            filled_orders += 1;
+            let filled = self.emit_fills(*client_order_id, &order, Liquidity::Maker, out);
+            if filled >= order.qty.lots() {
+                self.live_orders.remove(client_order_id);
+            } else if let Some(live) = self.live_orders.get_mut(client_order_id) {
+                live.filled = Qty::new(filled).unwrap_or(live.filled);
+                live.trade_fillable_lots = 0;
+            }
         }
-        self.fill_candidates = fill_candidates;
+        self.order_scan_ids = order_scan_ids;
+
+        // Released after the passive scan so a newly live remainder is not filled twice
+        // against the same displayed depth within one book update.
+        self.release_ready_requests(out);
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use lob_core::{LevelUpdate, MarketEvent};
-    use oms::OrderRequest as OmsOrderRequest;
-    use trading_types::{OrderRequest as NewOrderRequest, TimeInForce};
+/// One `SimVenue` per symbol, each over its own book. Market events are applied to the
+/// matching book and only that symbol's venue sees the following `on_book_update`;
+/// cancels and replaces follow the symbol their order was placed on.
+#[derive(Default)]
+pub struct RoutingVenue {
+    routes: HashMap<SymbolId, (Rc<RefCell<OrderBook>>, SimVenue)>,
+    order_symbols: HashMap<ClientOrderId, SymbolId>,
+    last_event_symbol: Option<SymbolId>,
+}
 
-    fn place_req(
-        client_order_id: u64,
+impl RoutingVenue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fee-free venues for `symbols`.
+    pub fn for_symbols(symbols: &[SymbolId]) -> Self {
+        symbols.iter().fold(Self::new(), |routes, &symbol| {
+            routes.with_symbol(symbol, |book| SimVenue::new(book, 0, 0))
+        })
+    }
+
+    /// Routes `symbol` to the venue built by `make_venue` over a fresh book for `symbol`.
+    pub fn with_symbol(
+        mut self,
         symbol: SymbolId,
-        side: Side,
-        price_ticks: i64,
+        make_venue: impl FnOnce(Rc<RefCell<OrderBook>>) -> SimVenue,
+    ) -> Self {
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let venue = make_venue(book.clone());
+        self.routes.insert(symbol, (book, venue));
+        self
+    }
+
+    pub fn venue(&self, symbol: SymbolId) -> Option<&SimVenue> {
+        self.routes.get(&symbol).map(|(_, venue)| venue)
+    }
+}
+
+impl ExecutionVenue for RoutingVenue {
+    fn submit(&mut self, req: &OrderRequest, out: &mut Vec<ExecutionReport>) {
+        let symbol = match req {
+            OrderRequest::Place(order) => {
+                self.order_symbols
+                    .insert(order.client_order_id, order.symbol);
+                order.symbol
+            }
+            OrderRequest::Cancel {
+                client_order_id, ..
+            }
+            | OrderRequest::Replace {
+                client_order_id, ..
+            } => match self.order_symbols.get(client_order_id) {
+                Some(&symbol) => symbol,
+                None => return,
+            },
+        };
+        if let Some((_, venue)) = self.routes.get_mut(&symbol) {
+            venue.submit(req, out);
+        }
+    }
+
+    fn on_market_event(&mut self, event: &MarketEvent) {
+        let symbol = event.symbol();
+        self.last_event_symbol = None;
+        if let Some((book, venue)) = self.routes.get_mut(&symbol) {
+            book.borrow_mut().apply(event);
+            venue.on_market_event(event);
+            self.last_event_symbol = Some(symbol);
+        }
+    }
+
+    fn on_book_update(&mut self, out: &mut Vec<ExecutionReport>) {
+        let Some(symbol) = self.last_event_symbol.take() else {
+            return;
+        };
+        if let Some((_, venue)) = self.routes.get_mut(&symbol) {
+            venue.on_book_update(out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lob_core::{LevelUpdate, MarketEvent};
+    use oms::OrderRequest as OmsOrderRequest;
+    use trading_types::{OrderRequest as NewOrderRequest, TimeInForce};
+
+    fn place_req(
+        client_order_id: u64,
+        symbol: SymbolId,
+        side: Side,
+        price_ticks: i64,
         qty_lots: i64,
     ) -> OmsOrderRequest {
         OmsOrderRequest::Place(NewOrderRequest {
@@ -378,4 +922,561 @@ mod tests {
         assert_eq!(out[1].client_order_id, ClientOrderId(20));
         assert!(out.iter().all(|r| r.status == OrderStatus::Filled));
     }
+
+    #[test]
+    fn fee_schedule_lowers_rates_after_volume_threshold() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let schedule = FeeSchedule::flat(2, 5).with_tier(10, 1, 3);
+        assert_eq!(schedule.tier_for(9).taker_fee_ticks, 5);
+        assert_eq!(schedule.tier_for(10).taker_fee_ticks, 3);
+        let mut venue = SimVenue::new(book.clone(), 2, 5).with_fee_schedule(schedule);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(99).expect("price"), Qty::new(100).expect("qty"))],
+            asks: vec![(Price::new(101).expect("price"), Qty::new(100).expect("qty"))],
+        }));
+
+        let mut taker_fees = Vec::new();
+        for id in 1..=3 {
+            let mut out = Vec::new();
+            venue.submit(&place_req(id, symbol, Side::Bid, 101, 6), &mut out);
+            assert_eq!(out[1].status, OrderStatus::Filled);
+            taker_fees.push(out[1].fee_ticks);
+        }
+        // The second fill crosses 10 lots but is charged at the tier it started in.
+        assert_eq!(taker_fees, vec![5, 5, 3]);
+        assert_eq!(venue.traded_lots(), 18);
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(4, symbol, Side::Ask, 105, 1), &mut out);
+        out.clear();
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Delta {
+            ts_ns: 2,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Bid,
+                price: Price::new(106).expect("price"),
+                qty: Qty::new(1).expect("qty"),
+            }],
+        }));
+        venue.on_book_update(&mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].fee_ticks, 1);
+    }
+
+    #[test]
+    fn negative_maker_fee_is_reported_as_rebate() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue = SimVenue::new(book.clone(), -2, 3);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(99).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(110).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Bid, 105, 1), &mut out);
+        assert_eq!(out.len(), 2);
+        out.clear();
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Delta {
+            ts_ns: 2,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Ask,
+                price: Price::new(104).expect("price"),
+                qty: Qty::new(1).expect("qty"),
+            }],
+        }));
+        venue.on_book_update(&mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].status, OrderStatus::Filled);
+        assert_eq!(out[0].fee_ticks, -2);
+    }
+
+    #[test]
+    fn liquidity_aware_fills_walk_levels_and_rest_remainder() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue = SimVenue::new(book.clone(), 0, 0).with_liquidity_aware_fills(true);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(99).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![
+                (Price::new(101).expect("price"), Qty::new(2).expect("qty")),
+                (Price::new(102).expect("price"), Qty::new(3).expect("qty")),
+                (Price::new(103).expect("price"), Qty::new(5).expect("qty")),
+            ],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Bid, 102, 6), &mut out);
+        let fills: Vec<(OrderStatus, i64, i64)> = out
+            .iter()
+            .skip(1)
+            .map(|r| (r.status, r.filled_qty.lots(), r.last_fill_price.ticks()))
+            .collect();
+        assert_eq!(out[0].status, OrderStatus::Accepted);
+        assert_eq!(
+            fills,
+            vec![
+                (OrderStatus::PartiallyFilled, 2, 101),
+                (OrderStatus::PartiallyFilled, 5, 102),
+                (OrderStatus::Working, 5, 102),
+            ]
+        );
+
+        out.clear();
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Delta {
+            ts_ns: 2,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Ask,
+                price: Price::new(100).expect("price"),
+                qty: Qty::new(4).expect("qty"),
+            }],
+        }));
+        venue.on_book_update(&mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].status, OrderStatus::Filled);
+        assert_eq!(out[0].filled_qty.lots(), 6);
+        assert_eq!(out[0].last_fill_price.ticks(), 100);
+
+        out.clear();
+        venue.on_book_update(&mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn default_mode_fills_full_qty_at_best_level() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue = SimVenue::new(book.clone(), 0, 0);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(99).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(101).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Bid, 105, 10), &mut out);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[1].status, OrderStatus::Filled);
+        assert_eq!(out[1].filled_qty.lots(), 10);
+        assert_eq!(out[1].last_fill_price.ticks(), 101);
+    }
+
+    #[test]
+    fn latency_defers_requests_until_book_time_reaches_ready_ts() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue = SimVenue::new(book.clone(), 0, 0).with_latency_ns(50);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 100,
+            symbol,
+            bids: vec![(Price::new(99).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(101).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Bid, 101, 1), &mut out);
+        assert!(out.is_empty());
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Delta {
+            ts_ns: 120,
+            symbol,
+            updates: vec![],
+        }));
+        venue.on_book_update(&mut out);
+        assert!(out.is_empty());
+
+        // The ask moved away before the order became live, so it rests instead of crossing.
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Delta {
+            ts_ns: 150,
+            symbol,
+            updates: vec![
+                LevelUpdate {
+                    side: Side::Ask,
+                    price: Price::new(101).expect("price"),
+                    qty: Qty::new(0).expect("qty"),
+                },
+                LevelUpdate {
+                    side: Side::Ask,
+                    price: Price::new(103).expect("price"),
+                    qty: Qty::new(1).expect("qty"),
+                },
+            ],
+        }));
+        venue.on_book_update(&mut out);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].status, OrderStatus::Accepted);
+        assert_eq!(out[1].status, OrderStatus::Working);
+    }
+
+    #[test]
+    fn queue_model_waits_for_trades_to_clear_queue_ahead() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue = SimVenue::new(book.clone(), 0, 0).with_queue_model(true);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).expect("price"), Qty::new(5).expect("qty"))],
+            asks: vec![(Price::new(102).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Bid, 100, 2), &mut out);
+        out.clear();
+
+        // Ask touching our bid is not enough with five lots queued ahead.
+        let touch = MarketEvent::L2Delta {
+            ts_ns: 2,
+            symbol,
+            updates: vec![LevelUpdate {
+                side: Side::Ask,
+                price: Price::new(100).expect("price"),
+                qty: Qty::new(3).expect("qty"),
+            }],
+        };
+        assert!(book.borrow_mut().apply(&touch));
+        venue.on_market_event(&touch);
+        venue.on_book_update(&mut out);
+        assert!(out.is_empty());
+
+        let trade = |ts_ns, lots| MarketEvent::Trade {
+            ts_ns,
+            symbol,
+            price: Price::new(100).expect("price"),
+            qty: Qty::new(lots).expect("qty"),
+            aggressor_side: Side::Ask,
+        };
+        venue.on_market_event(&trade(3, 4));
+        venue.on_book_update(&mut out);
+        assert!(out.is_empty());
+
+        venue.on_market_event(&trade(4, 2));
+        venue.on_book_update(&mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].status, OrderStatus::PartiallyFilled);
+        assert_eq!(out[0].filled_qty.lots(), 1);
+
+        out.clear();
+        venue.on_market_event(&trade(5, 3));
+        venue.on_book_update(&mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].status, OrderStatus::Filled);
+        assert_eq!(out[0].filled_qty.lots(), 2);
+    }
+
+    #[test]
+    fn resting_order_reports_accepted_then_working() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue = SimVenue::new(book.clone(), 0, 0);
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(99).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(101).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+
+        let mut oms = oms::Oms::new();
+        let request = oms
+            .apply_intent(
+                trading_types::Intent::PlaceLimit {
+                    symbol,
+                    side: Side::Bid,
+                    price: Price::new(100).expect("price"),
+                    qty: Qty::new(2).expect("qty"),
+                    tif: TimeInForce::Gtc,
+                    tag: None,
+                },
+                1,
+            )
+            .expect("place request");
+        let mut out = Vec::new();
+        venue.submit(&request, &mut out);
+        let statuses: Vec<_> = out.iter().map(|report| report.status).collect();
+        assert_eq!(statuses, vec![OrderStatus::Accepted, OrderStatus::Working]);
+        assert_eq!(out[1].last_fill_price.ticks(), 100);
+
+        // Both reports land on the same live entry rather than counting it twice.
+        for report in &out {
+            oms.on_execution_report(report);
+        }
+        assert_eq!(oms.open_orders(), 1);
+        assert_eq!(
+            oms.order_state(out[1].client_order_id),
+            Some(oms::OrderState::Live)
+        );
+
+        // Orders that fill immediately never rest, so no Working report follows.
+        out.clear();
+        venue.submit(&place_req(9, symbol, Side::Bid, 101, 1), &mut out);
+        let statuses: Vec<_> = out.iter().map(|report| report.status).collect();
+        assert_eq!(statuses, vec![OrderStatus::Accepted, OrderStatus::Filled]);
+    }
+
+    #[test]
+    fn size_down_replace_keeps_queue_position() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue = SimVenue::new(book.clone(), 0, 0).with_queue_model(true);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(100).expect("price"), Qty::new(5).expect("qty"))],
+            asks: vec![(Price::new(102).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Bid, 100, 4), &mut out);
+        venue.on_market_event(&MarketEvent::Trade {
+            ts_ns: 2,
+            symbol,
+            price: Price::new(100).expect("price"),
+            qty: Qty::new(3).expect("qty"),
+            aggressor_side: Side::Ask,
+        });
+        let queue_ahead = |venue: &SimVenue| venue.live_orders[&ClientOrderId(1)].queue_ahead_lots;
+        assert_eq!(queue_ahead(&venue), 2);
+
+        let replace = |new_qty| OmsOrderRequest::Replace {
+            client_order_id: ClientOrderId(1),
+            new_price: Price::new(100).expect("price"),
+            new_qty: Qty::new(new_qty).expect("qty"),
+            ts_ns: 3,
+        };
+        out.clear();
+        venue.submit(&replace(2), &mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].status, OrderStatus::Working);
+        assert_eq!(queue_ahead(&venue), 2);
+        assert_eq!(venue.live_orders[&ClientOrderId(1)].qty.lots(), 2);
+
+        // Growing the order sends it to the back of the displayed queue.
+        out.clear();
+        venue.submit(&replace(3), &mut out);
+        assert_eq!(out[0].status, OrderStatus::Accepted);
+        assert_eq!(queue_ahead(&venue), 5);
+    }
+
+    #[test]
+    fn impact_worsens_marketable_fills_with_size() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue = SimVenue::new(book.clone(), 0, 0).with_impact(1);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(9_990).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(
+                Price::new(10_000).expect("price"),
+                Qty::new(1).expect("qty")
+            )],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Bid, 20_000, 1), &mut out);
+        assert_eq!(out[1].status, OrderStatus::Filled);
+        assert_eq!(out[1].last_fill_price.ticks(), 10_000);
+
+        out.clear();
+        venue.submit(&place_req(2, symbol, Side::Bid, 20_000, 100), &mut out);
+        assert_eq!(out[1].status, OrderStatus::Filled);
+        assert_eq!(out[1].last_fill_price.ticks(), 10_099);
+
+        out.clear();
+        venue.submit(&place_req(3, symbol, Side::Ask, 1, 100), &mut out);
+        assert_eq!(out[1].last_fill_price.ticks(), 9_892);
+
+        out.clear();
+        venue.submit(&place_req(4, symbol, Side::Bid, 10_050, 100), &mut out);
+        assert_eq!(out[1].last_fill_price.ticks(), 10_050);
+    }
+
+    #[test]
+    fn reject_rate_is_deterministic_for_seed() {
+        fn statuses(rate: f64, seed: u64) -> Vec<OrderStatus> {
+            let symbol = SymbolId::from_u32(1);
+            let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+            let mut venue = SimVenue::new(book, 0, 0).with_reject_rate(rate, seed);
+            let mut out = Vec::new();
+            for id in 1..=32 {
+                venue.submit(&place_req(id, symbol, Side::Bid, 100, 1), &mut out);
+            }
+            out.iter().map(|report| report.status).collect()
+        }
+
+        let first = statuses(0.5, 7);
+        assert_eq!(first, statuses(0.5, 7));
+        assert!(first.contains(&OrderStatus::Rejected));
+        assert!(first.contains(&OrderStatus::Accepted));
+
+        assert!(statuses(1.0, 7)
+            .iter()
+            .all(|status| *status == OrderStatus::Rejected));
+        assert!(statuses(0.0, 7)
+            .iter()
+            .all(|status| matches!(status, OrderStatus::Accepted | OrderStatus::Working)));
+    }
+
+    #[test]
+    fn routing_venue_fills_each_symbol_against_its_own_book() {
+        let btc = SymbolId::from_u32(1);
+        let eth = SymbolId::from_u32(2);
+        let mut venue = RoutingVenue::for_symbols(&[btc, eth]);
+        let snapshot = |ts_ns, symbol, ask| MarketEvent::L2Snapshot {
+            ts_ns,
+            symbol,
+            bids: vec![(Price::new(90).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(ask).expect("price"), Qty::new(1).expect("qty"))],
+        };
+        let mut out = Vec::new();
+        venue.on_market_event(&snapshot(1, btc, 110));
+        venue.on_market_event(&snapshot(1, eth, 110));
+        venue.submit(&place_req(1, btc, Side::Bid, 100, 1), &mut out);
+        venue.submit(&place_req(2, eth, Side::Bid, 100, 1), &mut out);
+        out.clear();
+
+        venue.on_market_event(&snapshot(2, eth, 99));
+        venue.on_book_update(&mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].client_order_id, ClientOrderId(2));
+        assert_eq!(out[0].status, OrderStatus::Filled);
+        assert_eq!(out[0].last_fill_price, Price::new(99).expect("price"));
+
+        out.clear();
+        venue.submit(
+            &OmsOrderRequest::Cancel {
+                client_order_id: ClientOrderId(1),
+                ts_ns: 3,
+            },
+            &mut out,
+        );
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].symbol, btc);
+        assert_eq!(out[0].status, OrderStatus::Canceled);
+        assert_eq!(venue.venue(btc).expect("btc venue").traded_lots(), 0);
+    }
+
+    #[test]
+    fn passive_fills_go_to_better_price_before_lower_id() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue = SimVenue::new(book.clone(), 0, 0);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(90).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(110).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Bid, 100, 1), &mut out);
+        venue.submit(&place_req(2, symbol, Side::Bid, 102, 1), &mut out);
+        out.clear();
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 2,
+            symbol,
+            bids: vec![(Price::new(90).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(99).expect("price"), Qty::new(5).expect("qty"))],
+        }));
+        venue.on_book_update(&mut out);
+
+        let filled: Vec<_> = out
+            .iter()
+            .filter(|report| report.status == OrderStatus::Filled)
+            .map(|report| report.client_order_id)
+            .collect();
+        assert_eq!(filled, vec![ClientOrderId(2), ClientOrderId(1)]);
+    }
+
+    #[test]
+    fn self_trade_prevention_cancels_newest_even_when_it_scans_first() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue =
+            SimVenue::new(book.clone(), 0, 0).with_self_trade_prevention(StpMode::CancelNewest);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(90).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(110).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Ask, 95, 1), &mut out);
+        venue.submit(&place_req(2, symbol, Side::Bid, 105, 1), &mut out);
+        out.clear();
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 2,
+            symbol,
+            bids: vec![(Price::new(100).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(100).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+        venue.on_book_update(&mut out);
+
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].client_order_id, ClientOrderId(2));
+        assert_eq!(out[0].status, OrderStatus::Canceled);
+        assert_eq!(out[0].reason, Some(ReportReason::SelfTradePrevention));
+        assert_eq!(out[1].client_order_id, ClientOrderId(1));
+        assert_eq!(out[1].status, OrderStatus::Filled);
+    }
+
+    #[test]
+    fn self_trade_prevention_cancels_newest_order() {
+        let symbol = SymbolId::from_u32(1);
+        let book = Rc::new(RefCell::new(OrderBook::new(symbol)));
+        let mut venue =
+            SimVenue::new(book.clone(), 0, 0).with_self_trade_prevention(StpMode::CancelNewest);
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 1,
+            symbol,
+            bids: vec![(Price::new(90).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(110).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+
+        let mut out = Vec::new();
+        venue.submit(&place_req(1, symbol, Side::Bid, 105, 1), &mut out);
+        venue.submit(&place_req(2, symbol, Side::Ask, 95, 1), &mut out);
+        out.clear();
+
+        assert!(book.borrow_mut().apply(&MarketEvent::L2Snapshot {
+            ts_ns: 2,
+            symbol,
+            bids: vec![(Price::new(100).expect("price"), Qty::new(1).expect("qty"))],
+            asks: vec![(Price::new(100).expect("price"), Qty::new(1).expect("qty"))],
+        }));
+        venue.on_book_update(&mut out);
+
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].client_order_id, ClientOrderId(1));
+        assert_eq!(out[0].status, OrderStatus::Filled);
+        assert_eq!(out[0].reason, None);
+        assert_eq!(out[1].client_order_id, ClientOrderId(2));
+        assert_eq!(out[1].status, OrderStatus::Canceled);
+        assert_eq!(out[1].reason, Some(ReportReason::SelfTradePrevention));
+    }
 }
@@ -1,8 +1,13 @@
+use lob_core::MarketEvent;
 use oms::OrderRequest;
 use trading_types::ExecutionReport;
 
+/// Reports are appended to the caller-owned `out` buffer; callees never clear it.
 pub trait ExecutionVenue {
     fn submit(&mut self, req: &OrderRequest, out: &mut Vec<ExecutionReport>);
 
+    /// Observes every applied market event (including trade prints) before `on_book_update`.
+    fn on_market_event(&mut self, _event: &MarketEvent) {}
+
     fn on_book_update(&mut self, _out: &mut Vec<ExecutionReport>) {}
 }